@@ -1 +1,2 @@
+pub mod stash;
 pub mod user;
@@ -0,0 +1,61 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::value_objects::{date::Date, pid::Pid, stash_status::StashStatus},
+    infrastructure::messaging::{
+        context::EventContext,
+        event::{DomainEvent, TypedEvent},
+    },
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StashStatusUpdatedEvent {
+    stash_id: Pid,
+    /// owner of the stash
+    pub user_id: Pid,
+    pub old_status: StashStatus,
+    pub new_status: StashStatus,
+    created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
+}
+
+impl StashStatusUpdatedEvent {
+    pub fn new(stash_id: &Pid, user_id: &Pid, old_status: &StashStatus, new_status: &StashStatus) -> Box<Self> {
+        Box::new(Self {
+            stash_id: stash_id.to_owned(),
+            user_id: user_id.to_owned(),
+            old_status: old_status.to_owned(),
+            new_status: new_status.to_owned(),
+            created_at: Utc::now(),
+            context: EventContext::current(),
+        })
+    }
+}
+
+impl TypedEvent for StashStatusUpdatedEvent {
+    const EVENT_TYPE: &'static str = "StashStatusUpdated";
+}
+
+impl DomainEvent for StashStatusUpdatedEvent {
+    fn event_type(&self) -> &str {
+        Self::EVENT_TYPE
+    }
+
+    fn aggregate_id(&self) -> Pid {
+        self.stash_id.clone()
+    }
+
+    fn occurred_at(&self) -> Date {
+        self.created_at
+    }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
+}
@@ -1,14 +1,20 @@
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     domain::value_objects::{date::Date, pid::Pid, user_status::UserStatus},
-    infrastructure::messaging::event::DomainEvent,
+    infrastructure::messaging::{
+        context::EventContext,
+        event::{DomainEvent, TypedEvent},
+    },
 };
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct UserCreatedEvent {
-    user_id: Pid,
+    pub user_id: Pid,
     created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
 }
 
 impl UserCreatedEvent {
@@ -16,13 +22,18 @@ impl UserCreatedEvent {
         Box::new(Self {
             user_id: user_id.to_owned(),
             created_at: Utc::now(),
+            context: EventContext::current(),
         })
     }
 }
 
+impl TypedEvent for UserCreatedEvent {
+    const EVENT_TYPE: &'static str = "UserCreated";
+}
+
 impl DomainEvent for UserCreatedEvent {
     fn event_type(&self) -> &str {
-        "UserCreated"
+        Self::EVENT_TYPE
     }
 
     fn aggregate_id(&self) -> Pid {
@@ -32,14 +43,23 @@ impl DomainEvent for UserCreatedEvent {
     fn occurred_at(&self) -> Date {
         self.created_at.clone()
     }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ProfileCreatedEvent {
-    user_id: Pid,
-    #[allow(dead_code)]
-    profile_id: Pid,
+    pub user_id: Pid,
+    pub profile_id: Pid,
     created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
 }
 
 impl ProfileCreatedEvent {
@@ -48,13 +68,18 @@ impl ProfileCreatedEvent {
             user_id: user_id.to_owned(),
             profile_id: profile_id.to_owned(),
             created_at: Utc::now(),
+            context: EventContext::current(),
         })
     }
 }
 
+impl TypedEvent for ProfileCreatedEvent {
+    const EVENT_TYPE: &'static str = "ProfileCreated";
+}
+
 impl DomainEvent for ProfileCreatedEvent {
     fn event_type(&self) -> &str {
-        "ProfileCreated"
+        Self::EVENT_TYPE
     }
 
     fn aggregate_id(&self) -> Pid {
@@ -64,13 +89,23 @@ impl DomainEvent for ProfileCreatedEvent {
     fn occurred_at(&self) -> Date {
         self.created_at.clone()
     }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SessionActivatedEvent {
     user_id: Pid,
     pub session_id: Pid,
     created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
 }
 
 impl SessionActivatedEvent {
@@ -79,13 +114,18 @@ impl SessionActivatedEvent {
             user_id: user_id.to_owned(),
             session_id: session_id.to_owned(),
             created_at: Utc::now(),
+            context: EventContext::current(),
         })
     }
 }
 
+impl TypedEvent for SessionActivatedEvent {
+    const EVENT_TYPE: &'static str = "SessionActivated";
+}
+
 impl DomainEvent for SessionActivatedEvent {
     fn event_type(&self) -> &str {
-        "SessionActivated"
+        Self::EVENT_TYPE
     }
 
     fn aggregate_id(&self) -> Pid {
@@ -95,13 +135,23 @@ impl DomainEvent for SessionActivatedEvent {
     fn occurred_at(&self) -> Date {
         self.created_at.clone()
     }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SessionTerminatedEvent {
     user_id: Pid,
     pub session_id: Pid,
     created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
 }
 
 impl SessionTerminatedEvent {
@@ -110,13 +160,18 @@ impl SessionTerminatedEvent {
             user_id: user_id.to_owned(),
             session_id: session_id.to_owned(),
             created_at: Utc::now(),
+            context: EventContext::current(),
         })
     }
 }
 
+impl TypedEvent for SessionTerminatedEvent {
+    const EVENT_TYPE: &'static str = "SessionTerminated";
+}
+
 impl DomainEvent for SessionTerminatedEvent {
     fn event_type(&self) -> &str {
-        "SessionTerminated"
+        Self::EVENT_TYPE
     }
 
     fn aggregate_id(&self) -> Pid {
@@ -126,14 +181,24 @@ impl DomainEvent for SessionTerminatedEvent {
     fn occurred_at(&self) -> Date {
         self.created_at.clone()
     }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct UserStatusUpdatedEvent {
     pub user_id: Pid,
     pub old_status: UserStatus,
     pub new_status: UserStatus,
     pub created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
 }
 
 impl UserStatusUpdatedEvent {
@@ -143,13 +208,18 @@ impl UserStatusUpdatedEvent {
             old_status: old_status.to_owned(),
             new_status: new_status.to_owned(),
             created_at: Utc::now(),
+            context: EventContext::current(),
         })
     }
 }
 
+impl TypedEvent for UserStatusUpdatedEvent {
+    const EVENT_TYPE: &'static str = "UserStatusUpdated";
+}
+
 impl DomainEvent for UserStatusUpdatedEvent {
     fn event_type(&self) -> &str {
-        "UserStatusUpdated"
+        Self::EVENT_TYPE
     }
 
     fn aggregate_id(&self) -> Pid {
@@ -159,4 +229,64 @@ impl DomainEvent for UserStatusUpdatedEvent {
     fn occurred_at(&self) -> Date {
         self.created_at.clone()
     }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
+}
+
+/// Security event raised for every wrong OTP code submitted to a session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OtpVerificationFailedEvent {
+    user_id: Pid,
+    pub session_id: Pid,
+    pub failed_attempts: u8,
+    /// whether this failure locked the session
+    pub locked: bool,
+    created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
+}
+
+impl OtpVerificationFailedEvent {
+    pub fn new(user_id: &Pid, session_id: &Pid, failed_attempts: u8, locked: bool) -> Box<Self> {
+        Box::new(Self {
+            user_id: user_id.to_owned(),
+            session_id: session_id.to_owned(),
+            failed_attempts,
+            locked,
+            created_at: Utc::now(),
+            context: EventContext::current(),
+        })
+    }
+}
+
+impl TypedEvent for OtpVerificationFailedEvent {
+    const EVENT_TYPE: &'static str = "OtpVerificationFailed";
+}
+
+impl DomainEvent for OtpVerificationFailedEvent {
+    fn event_type(&self) -> &str {
+        Self::EVENT_TYPE
+    }
+
+    fn aggregate_id(&self) -> Pid {
+        self.user_id.clone()
+    }
+
+    fn occurred_at(&self) -> Date {
+        self.created_at
+    }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
 }
@@ -2,5 +2,6 @@ pub mod asset;
 pub mod date;
 pub mod mula;
 pub mod pid;
+pub mod stash_status;
 pub mod user_status;
 pub mod wallet_address;
@@ -1,11 +1,32 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
 use crate::domain::value_objects::asset::Asset;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Mula {
+    /// serialized as a string, JSON numbers cannot hold every `u128`
+    #[serde(with = "amount_as_string")]
     amount: u128,
     asset: Asset,
 }
 
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum MulaError {
+    #[error("Asset mismatch: {0} and {1}")]
+    AssetMismatch(String, String),
+    #[error("Amount overflow")]
+    Overflow,
+    #[error("Insufficient amount")]
+    Underflow,
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    #[error("Too many decimal places, max: {0}")]
+    TooManyDecimals(u8),
+}
+
 impl Mula {
     pub fn new(amount: u128, asset: &Asset) -> Self {
         Self {
@@ -14,7 +35,107 @@ impl Mula {
         }
     }
 
+    pub fn zero(asset: &Asset) -> Self {
+        Self::new(0, asset)
+    }
+
+    /// parses a human readable decimal amount e.g. `"12.5"` into the asset's smallest unit
+    pub fn parse(value: &str, asset: &Asset) -> Result<Self, MulaError> {
+        let invalid = || MulaError::InvalidAmount(value.to_owned());
+        let (whole, fraction) = value.trim().split_once('.').unwrap_or((value.trim(), ""));
+
+        if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        if fraction.len() > usize::from(asset.decimals) {
+            return Err(MulaError::TooManyDecimals(asset.decimals));
+        }
+
+        let padded_fraction = format!("{:0<width$}", fraction, width = usize::from(asset.decimals));
+        let whole = whole.parse::<u128>().map_err(|_| MulaError::Overflow)?;
+        let fraction = if padded_fraction.is_empty() {
+            0
+        } else {
+            padded_fraction.parse::<u128>().map_err(|_| invalid())?
+        };
+
+        let amount = Self::unit(asset)?
+            .checked_mul(whole)
+            .and_then(|amount| amount.checked_add(fraction))
+            .ok_or(MulaError::Overflow)?;
+
+        Ok(Self::new(amount, asset))
+    }
+
+    pub fn get_amount(&self) -> u128 {
+        self.amount
+    }
+
     pub fn get_asset(&self) -> &Asset {
         &self.asset
     }
+
+    pub fn is_zero(&self) -> bool {
+        self.amount == 0
+    }
+
+    pub fn checked_add(&self, other: &Mula) -> Result<Mula, MulaError> {
+        self.assert_same_asset(other)?;
+        let amount = self.amount.checked_add(other.amount).ok_or(MulaError::Overflow)?;
+        Ok(Self::new(amount, &self.asset))
+    }
+
+    pub fn checked_sub(&self, other: &Mula) -> Result<Mula, MulaError> {
+        self.assert_same_asset(other)?;
+        let amount = self.amount.checked_sub(other.amount).ok_or(MulaError::Underflow)?;
+        Ok(Self::new(amount, &self.asset))
+    }
+
+    pub fn checked_cmp(&self, other: &Mula) -> Result<Ordering, MulaError> {
+        self.assert_same_asset(other)?;
+        Ok(self.amount.cmp(&other.amount))
+    }
+
+    fn assert_same_asset(&self, other: &Mula) -> Result<(), MulaError> {
+        if self.asset != other.asset {
+            return Err(MulaError::AssetMismatch(self.asset.symbol.clone(), other.asset.symbol.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// amount of the smallest unit making up one whole asset
+    fn unit(asset: &Asset) -> Result<u128, MulaError> {
+        10u128.checked_pow(u32::from(asset.decimals)).ok_or(MulaError::Overflow)
+    }
+}
+
+/// renders the amount with `display_decimals` fractional digits (truncated) followed by the asset symbol
+impl Display for Mula {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let decimals = usize::from(self.asset.decimals);
+        let display_decimals = usize::from(self.asset.display_decimals);
+        let digits = format!("{:0>width$}", self.amount, width = decimals + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        let fraction = format!("{:0<display_decimals$}", &fraction[..fraction.len().min(display_decimals)]);
+
+        if fraction.is_empty() {
+            write!(f, "{} {}", whole, self.asset.symbol)
+        } else {
+            write!(f, "{}.{} {}", whole, fraction, self.asset.symbol)
+        }
+    }
+}
+
+mod amount_as_string {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(amount: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&amount.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
 }
@@ -1,10 +1,10 @@
 use std::str::FromStr;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Pid(Uuid);
 
 #[derive(Debug, Error, Clone, PartialEq, Eq)]
@@ -17,6 +17,16 @@ impl Pid {
     pub fn new() -> Self {
         Self(Uuid::new_v4())
     }
+
+    pub fn as_uuid(&self) -> &Uuid {
+        &self.0
+    }
+}
+
+impl From<Uuid> for Pid {
+    fn from(id: Uuid) -> Self {
+        Self(id)
+    }
 }
 
 impl FromStr for Pid {
@@ -37,6 +47,15 @@ impl<'de> Deserialize<'de> for Pid {
     }
 }
 
+impl Serialize for Pid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
 impl ToString for Pid {
     fn to_string(&self) -> String {
         self.0.to_string()
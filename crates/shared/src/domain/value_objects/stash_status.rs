@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum StashStatus {
+    ACTIVE,
+    PAUSED,
+    CLOSED,
+}
+
+impl StashStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ACTIVE => "ACTIVE",
+            Self::PAUSED => "PAUSED",
+            Self::CLOSED => "CLOSED",
+        }
+    }
+
+    /// ACTIVE and PAUSED move freely between each other and into CLOSED; CLOSED is terminal
+    pub fn can_transition_to(&self, next: &StashStatus) -> bool {
+        matches!(
+            (self, next),
+            (Self::ACTIVE, Self::PAUSED) | (Self::PAUSED, Self::ACTIVE) | (Self::ACTIVE | Self::PAUSED, Self::CLOSED)
+        )
+    }
+}
+
+impl FromStr for StashStatus {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "ACTIVE" => Self::ACTIVE,
+            "PAUSED" => Self::PAUSED,
+            "CLOSED" => Self::CLOSED,
+            _ => return Err("invalid stash status"),
+        })
+    }
+}
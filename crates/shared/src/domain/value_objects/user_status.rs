@@ -1,7 +1,34 @@
-#[derive(Debug, Clone, Eq, PartialEq)]
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum UserStatus {
     Active,
     Suspended,
     PendingProfile,
     Deleted,
 }
+
+impl UserStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "Active",
+            Self::Suspended => "Suspended",
+            Self::PendingProfile => "PendingProfile",
+            Self::Deleted => "Deleted",
+        }
+    }
+}
+
+impl FromStr for UserStatus {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Active" => Self::Active,
+            "Suspended" => Self::Suspended,
+            "PendingProfile" => Self::PendingProfile,
+            "Deleted" => Self::Deleted,
+            _ => return Err("invalid user status"),
+        })
+    }
+}
@@ -1,5 +1,5 @@
 use alloy::{hex::FromHexError, primitives::Address};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -21,6 +21,15 @@ impl ToString for WalletAddress {
     }
 }
 
+impl Serialize for WalletAddress {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
 impl<'de> Deserialize<'de> for WalletAddress {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -0,0 +1,81 @@
+use crate::{
+    domain::value_objects::pid::Pid,
+    infrastructure::types::{Result, error::Error},
+};
+use async_trait::async_trait;
+use axum::{
+    extract::FromRequestParts,
+    http::{header::AUTHORIZATION, request::Parts},
+};
+use std::sync::Arc;
+
+/// Verifies a bearer token and resolves the user and session it was issued to.
+///
+/// Implementations must reject tokens of terminated sessions, not only expired or forged ones.
+#[async_trait]
+pub trait TokenVerifier: Send + Sync {
+    async fn verify(&self, token: &str) -> Result<AuthenticatedUser>;
+}
+
+/// Verifies a bearer token presented by another service of the platform, never by a user.
+#[async_trait]
+pub trait ServiceTokenVerifier: Send + Sync {
+    async fn verify(&self, token: &str) -> Result<AuthenticatedService>;
+}
+
+/// The user authenticated by the request's `Authorization: Bearer <token>` header.
+///
+/// Routers using it must install the verifier as an extension:
+/// `router.layer(Extension(verifier as Arc<dyn TokenVerifier>))`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedUser {
+    pub user_id: Pid,
+    /// session the token was issued for
+    pub session_id: Pid,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for AuthenticatedUser {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        let Some(verifier) = parts.extensions.get::<Arc<dyn TokenVerifier>>() else {
+            println!("no TokenVerifier extension installed on the router");
+            return Err(Error::ServiceError);
+        };
+
+        verifier.verify(bearer_token(parts)?).await
+    }
+}
+
+/// The service authenticated by the request's `Authorization: Bearer <token>` header, for routes users must not call.
+///
+/// Routers using it must install the verifier as an extension:
+/// `router.layer(Extension(verifier as Arc<dyn ServiceTokenVerifier>))`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedService {
+    /// name the service is known by
+    pub name: String,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for AuthenticatedService {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        let Some(verifier) = parts.extensions.get::<Arc<dyn ServiceTokenVerifier>>() else {
+            println!("no ServiceTokenVerifier extension installed on the router");
+            return Err(Error::ServiceError);
+        };
+
+        verifier.verify(bearer_token(parts)?).await
+    }
+}
+
+fn bearer_token(parts: &Parts) -> Result<&str> {
+    parts
+        .headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .ok_or(Error::Unauthorized)
+}
@@ -0,0 +1,40 @@
+use crate::{domain::value_objects::pid::Pid, infrastructure::messaging::context::EventContext};
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+/// Header carrying the correlation id of a request, echoed on the response
+pub const CORRELATION_ID: HeaderName = HeaderName::from_static("x-correlation-id");
+
+/// Middleware running the request in a correlated [`EventContext`], so every event it raises
+/// carries the caller's correlation id, or a new one when the caller sent none or an invalid one
+pub async fn correlate(request: Request, next: Next) -> Response {
+    let correlation_id = request
+        .headers()
+        .get(&CORRELATION_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_valid(value))
+        .map(str::to_owned)
+        .unwrap_or_else(|| Pid::new().to_string());
+
+    let mut response = EventContext::correlated(correlation_id.clone()).scope(next.run(request)).await;
+    if let Ok(value) = HeaderValue::from_str(&correlation_id) {
+        response.headers_mut().insert(CORRELATION_ID, value);
+    }
+    response
+}
+
+/// correlation ids end up in logs and stored events, so only short ids of `[A-Za-z0-9-_]` are kept
+fn is_valid(correlation_id: &str) -> bool {
+    (1..=max_correlation_id_length()).contains(&correlation_id.len())
+        && correlation_id
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+}
+
+fn max_correlation_id_length() -> usize {
+    128
+}
@@ -0,0 +1,75 @@
+use crate::infrastructure::types::error::{DomainError, Error};
+use axum::{
+    Json,
+    http::{HeaderValue, StatusCode, header::RETRY_AFTER},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::time::Duration;
+
+pub mod auth;
+pub mod correlation;
+pub mod extract;
+
+/// JSON body returned for every failed request
+#[derive(Debug, Serialize)]
+pub struct ErrorResponse {
+    /// Stable, machine readable error code
+    pub code: &'static str,
+    /// Human readable description of the failure
+    pub message: String,
+}
+
+impl Error {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Error::DomainError(DomainError::EntityNotFound) => StatusCode::NOT_FOUND,
+            Error::DomainError(DomainError::EntityAlreadyExist | DomainError::EntityConflict) | Error::InvalidState(_) => StatusCode::CONFLICT,
+            Error::DomainError(DomainError::EntityInvalid) | Error::AssertError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::BuilderError(_) | Error::InvalidRequest(_) | Error::ParseError => StatusCode::BAD_REQUEST,
+            Error::Unauthorized => StatusCode::UNAUTHORIZED,
+            Error::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Error::ServiceError | Error::EventMismatch { .. } | Error::ConfigError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> ErrorResponse {
+        let (code, message) = match self {
+            Error::DomainError(DomainError::EntityNotFound) => ("entity_not_found", "entity not found".to_owned()),
+            Error::DomainError(DomainError::EntityAlreadyExist) => ("entity_already_exist", "entity already exist".to_owned()),
+            Error::DomainError(DomainError::EntityConflict) => ("entity_conflict", "entity conflicts with its current state".to_owned()),
+            Error::DomainError(DomainError::EntityInvalid) => ("entity_invalid", "entity is invalid".to_owned()),
+            Error::AssertError(message) => ("assertion_failed", message.to_owned()),
+            Error::InvalidState(message) => ("invalid_state", message.to_owned()),
+            Error::BuilderError(message) | Error::InvalidRequest(message) => ("invalid_request", message.to_owned()),
+            Error::ParseError => ("invalid_request", "request could not be parsed".to_owned()),
+            Error::Unauthorized => ("unauthorized", "missing or invalid credentials".to_owned()),
+            Error::TooManyRequests { retry_after } => (
+                "too_many_requests",
+                format!("too many requests, retry in {} seconds", retry_after_seconds(retry_after)),
+            ),
+            // internal details are logged where the error is raised, never returned
+            Error::ServiceError | Error::EventMismatch { .. } | Error::ConfigError(_) => ("internal_error", "internal server error".to_owned()),
+        };
+
+        ErrorResponse { code, message }
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let mut response = (self.status_code(), Json(self.error_response())).into_response();
+        if let Error::TooManyRequests { retry_after } = &self {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(retry_after_seconds(retry_after)));
+        }
+
+        response
+    }
+}
+
+/// whole seconds, rounded up so clients never retry early
+fn retry_after_seconds(retry_after: &Duration) -> u64 {
+    retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)
+}
@@ -1,7 +1,9 @@
 use crate::infrastructure::types::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+pub mod smtp;
 pub mod stub_mailer;
+pub mod template;
 
 /// The structure representing an email details.
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -28,6 +30,7 @@ pub struct Email {
 pub struct Deliveries {
     pub count: usize,
     pub messages: Vec<String>,
+    pub emails: Vec<Email>,
 }
 
 #[async_trait]
@@ -0,0 +1,164 @@
+#[cfg(feature = "testing")]
+use super::Deliveries;
+use super::Email;
+use crate::infrastructure::{
+    mailing::{Mailer, stub_mailer::StubMailer},
+    types::{Result, error::Error},
+};
+use async_trait::async_trait;
+use di::{Injectable, ServiceCollection, singleton};
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::{Mailbox, Mailboxes, MultiPart},
+    transport::smtp::authentication::Credentials,
+};
+use serde::Deserialize;
+use std::{sync::Arc, time::Duration};
+
+/// How the connection to the SMTP server is secured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// plain text connection, only meant for local sinks
+    None,
+    /// upgrade a plain connection with `STARTTLS`
+    #[default]
+    StartTls,
+    /// implicit TLS from the first byte (SMTPS)
+    Tls,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// defaults to the well known port of `security`
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// bounds connecting as well as the whole SMTP exchange of one delivery
+    #[serde(default = "SmtpConfig::default_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+impl SmtpConfig {
+    fn default_timeout_seconds() -> u64 {
+        10
+    }
+
+    fn port(&self) -> u16 {
+        self.port.unwrap_or(match self.security {
+            SmtpSecurity::None => 25,
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::Tls => 465,
+        })
+    }
+}
+
+/// Mailer delivering through an SMTP relay, sending text and HTML as `multipart/alternative`
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    timeout: Duration,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &SmtpConfig) -> Result<Self> {
+        let builder = match config.security {
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .map_err(|e| Error::AssertError(format!("invalid smtp relay `{}`: {e}", config.host)))?,
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+                .map_err(|e| Error::AssertError(format!("invalid smtp relay `{}`: {e}", config.host)))?,
+        };
+
+        let timeout = Duration::from_secs(config.timeout_seconds);
+        let mut builder = builder.port(config.port()).timeout(Some(timeout));
+        match (&config.username, &config.password) {
+            (Some(username), Some(password)) => {
+                builder = builder.credentials(Credentials::new(username.to_owned(), password.to_owned()));
+            }
+            (None, None) => {}
+            _ => return Err(Error::ConfigError("smtp username and password must be set together".to_owned())),
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            timeout,
+        })
+    }
+
+    fn message(email: &Email) -> Result<Message> {
+        let mut builder = Message::builder().from(parse_mailbox(&email.from)?).subject(email.subject.as_str());
+
+        for to in parse_mailboxes(&email.to)? {
+            builder = builder.to(to);
+        }
+        if let Some(cc) = &email.cc {
+            for cc in parse_mailboxes(cc)? {
+                builder = builder.cc(cc);
+            }
+        }
+        if let Some(bcc) = &email.bcc {
+            for bcc in parse_mailboxes(bcc)? {
+                builder = builder.bcc(bcc);
+            }
+        }
+        if let Some(reply_to) = &email.reply_to {
+            builder = builder.reply_to(parse_mailbox(reply_to)?);
+        }
+
+        builder
+            .multipart(MultiPart::alternative_plain_html(email.text.clone(), email.html.clone()))
+            .map_err(|e| Error::AssertError(format!("invalid email: {e}")))
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn mail(&self, email: &Email) -> Result<()> {
+        let message = Self::message(email)?;
+        // the transport only bounds connecting, a stalled server could otherwise hold the delivery forever
+        match tokio::time::timeout(self.timeout, self.transport.send(message)).await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => {
+                println!("failed to deliver email to {}: {:?}", email.to, e);
+                Err(Error::ServiceError)
+            }
+            Err(_) => {
+                println!("delivering email to {} timed out", email.to);
+                Err(Error::ServiceError)
+            }
+        }
+    }
+
+    /// deliveries are only tracked by the `StubMailer`
+    #[cfg(feature = "testing")]
+    async fn deliveries(&self) -> Deliveries {
+        Deliveries::default()
+    }
+}
+
+fn parse_mailbox(address: &str) -> Result<Mailbox> {
+    address
+        .parse()
+        .map_err(|e| Error::AssertError(format!("invalid mailbox `{address}`: {e}")))
+}
+
+fn parse_mailboxes(addresses: &str) -> Result<Mailboxes> {
+    addresses
+        .parse()
+        .map_err(|e| Error::AssertError(format!("invalid mailboxes `{addresses}`: {e}")))
+}
+
+/// Registers an `SmtpMailer` built from `config` as the `Mailer`, or the `StubMailer` when no relay is configured
+pub fn add_mailer<'a>(services: &'a mut ServiceCollection, config: Option<&SmtpConfig>) -> Result<&'a mut ServiceCollection> {
+    let Some(config) = config else {
+        return Ok(services.add(StubMailer::singleton()));
+    };
+    let mailer: Arc<dyn Mailer> = Arc::new(SmtpMailer::new(config)?);
+    Ok(services.add(singleton::<dyn Mailer, SmtpMailer>().from(move |_| mailer.clone())))
+}
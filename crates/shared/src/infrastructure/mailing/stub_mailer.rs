@@ -20,6 +20,7 @@ impl Mailer for StubMailer {
         deliveries.count += 1;
         let message = format!("{} \n ----------- \n {}", email.html, email.text);
         deliveries.messages.push(message);
+        deliveries.emails.push(email.clone());
         Ok(())
     }
 
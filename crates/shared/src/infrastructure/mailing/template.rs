@@ -0,0 +1,148 @@
+use crate::infrastructure::types::{Result, error::Error};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, str::FromStr};
+
+/// Languages the embedded mail templates are translated to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+impl Locale {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = &'static str;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match s {
+            "en" => Self::En,
+            "de" => Self::De,
+            _ => return Err("invalid locale"),
+        })
+    }
+}
+
+/// Transactional mails known to the template subsystem
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailTemplate {
+    Otp,
+    Welcome,
+    ProfileCreated,
+    StashStatusChanged,
+}
+
+/// Values substituted for the `{{ name }}` placeholders of a template
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext(BTreeMap<&'static str, String>);
+
+impl TemplateContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.0.insert(name, value.to_string());
+        self
+    }
+}
+
+/// A template rendered for one locale, ready to be put into an `Email`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedTemplate {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+struct TemplateSource {
+    subject: &'static str,
+    html: &'static str,
+    text: &'static str,
+}
+
+macro_rules! embed_template {
+    ($locale:literal, $name:literal) => {
+        TemplateSource {
+            subject: include_str!(concat!("../../../templates/mail/", $locale, "/", $name, ".subject")),
+            html: include_str!(concat!("../../../templates/mail/", $locale, "/", $name, ".html")),
+            text: include_str!(concat!("../../../templates/mail/", $locale, "/", $name, ".txt")),
+        }
+    };
+}
+
+impl MailTemplate {
+    fn source(&self, locale: Locale) -> TemplateSource {
+        match (locale, self) {
+            (Locale::En, MailTemplate::Otp) => embed_template!("en", "otp"),
+            (Locale::En, MailTemplate::Welcome) => embed_template!("en", "welcome"),
+            (Locale::En, MailTemplate::ProfileCreated) => embed_template!("en", "profile_created"),
+            (Locale::En, MailTemplate::StashStatusChanged) => embed_template!("en", "stash_status_changed"),
+            (Locale::De, MailTemplate::Otp) => embed_template!("de", "otp"),
+            (Locale::De, MailTemplate::Welcome) => embed_template!("de", "welcome"),
+            (Locale::De, MailTemplate::ProfileCreated) => embed_template!("de", "profile_created"),
+            (Locale::De, MailTemplate::StashStatusChanged) => embed_template!("de", "stash_status_changed"),
+        }
+    }
+
+    /// renders subject, HTML and plain-text variants; values are escaped in the HTML variant
+    pub fn render(&self, locale: Locale, context: &TemplateContext) -> Result<RenderedTemplate> {
+        let source = self.source(locale);
+
+        Ok(RenderedTemplate {
+            subject: substitute(source.subject.trim(), context, false)?,
+            html: substitute(source.html, context, true)?,
+            text: substitute(source.text, context, false)?,
+        })
+    }
+}
+
+fn substitute(source: &str, context: &TemplateContext, escape: bool) -> Result<String> {
+    let mut rendered = String::with_capacity(source.len());
+    let mut rest = source;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| Error::AssertError("unterminated template placeholder".to_owned()))?;
+        let name = rest[start + 2..start + end].trim();
+        let value = context
+            .0
+            .get(name)
+            .ok_or_else(|| Error::AssertError(format!("missing template value `{name}`")))?;
+
+        if escape {
+            rendered.push_str(&escape_html(value));
+        } else {
+            rendered.push_str(value);
+        }
+        rest = &rest[start + end + 2..];
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
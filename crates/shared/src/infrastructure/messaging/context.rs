@@ -0,0 +1,46 @@
+use crate::{domain::value_objects::pid::Pid, infrastructure::messaging::event::DomainEvent};
+use std::future::Future;
+
+tokio::task_local! {
+    static CURRENT: EventContext;
+}
+
+/// Correlation and causation of the work in progress.
+///
+/// Events capture the current context when they are raised, event buses run every handler in
+/// the context caused by the event it handles, so the events a handler raises link back to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventContext {
+    /// id shared by every event of one request or workflow
+    pub correlation_id: Option<String>,
+    /// id of the event being handled
+    pub causation_id: Option<String>,
+}
+
+impl EventContext {
+    /// the context of the current scope, empty outside of one
+    pub fn current() -> Self {
+        CURRENT.try_with(Clone::clone).unwrap_or_default()
+    }
+
+    /// context of work that was not caused by an event, e.g. an HTTP request
+    pub fn correlated(correlation_id: impl Into<String>) -> Self {
+        Self {
+            correlation_id: Some(correlation_id.into()),
+            causation_id: None,
+        }
+    }
+
+    /// context for handling `event` published as `event_id`, starting a correlation when it has none
+    pub fn caused_by(event: &dyn DomainEvent, event_id: &Pid) -> Self {
+        Self {
+            correlation_id: Some(event.correlation_id().unwrap_or_else(|| Pid::new().to_string())),
+            causation_id: Some(event_id.to_string()),
+        }
+    }
+
+    /// runs `future` with `self` as the current context
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT.scope(self, future).await
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{
+        messaging::{
+            context::EventContext,
+            event::{DomainEvent, TypedEvent},
+        },
+        types::{Result, error::Error},
+    },
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Wire format of a domain event leaving the process
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope {
+    /// unique per event, consumers use it to drop duplicates
+    pub event_id: Pid,
+    pub event_type: String,
+    /// layout version of `payload`
+    pub schema_version: u32,
+    pub aggregate_id: Pid,
+    /// id shared by every event of one request or workflow
+    pub correlation_id: Option<String>,
+    /// id of the event or command that caused this event
+    pub causation_id: Option<String>,
+    pub occurred_at: Date,
+    pub payload: Value,
+}
+
+impl EventEnvelope {
+    /// wraps `event` under a new event id
+    pub fn seal(event: &dyn DomainEvent) -> Result<Self> {
+        let payload = event
+            .payload()
+            .map_err(|e| Error::AssertError(format!("unserializable event {}: {e}", event.event_type())))?;
+
+        Ok(Self {
+            event_id: Pid::new(),
+            event_type: event.event_type().to_owned(),
+            schema_version: event.schema_version(),
+            aggregate_id: event.aggregate_id(),
+            correlation_id: event.correlation_id(),
+            causation_id: event.causation_id(),
+            occurred_at: event.occurred_at(),
+            payload,
+        })
+    }
+}
+
+type Decode = fn(Value) -> serde_json::Result<Box<dyn DomainEvent>>;
+
+/// Turns envelopes back into typed events, keyed by event type and schema version
+#[derive(Default)]
+pub struct EventRegistry {
+    decoders: HashMap<(String, u32), Decode>,
+}
+
+impl EventRegistry {
+    /// registers `E` as the payload of its event type in `schema_version`
+    pub fn register<E: TypedEvent + DeserializeOwned>(&mut self, schema_version: u32) -> &mut Self {
+        self.decoders.insert((E::EVENT_TYPE.to_owned(), schema_version), |payload| {
+            serde_json::from_value::<E>(payload).map(|event| Box::new(event) as Box<dyn DomainEvent>)
+        });
+        self
+    }
+
+    pub fn decode(&self, envelope: &EventEnvelope) -> Result<Box<dyn DomainEvent>> {
+        let decode = self
+            .decoders
+            .get(&(envelope.event_type.clone(), envelope.schema_version))
+            .ok_or_else(|| Error::AssertError(format!("no event registered for: {} v{}", envelope.event_type, envelope.schema_version)))?;
+
+        let mut event = decode(envelope.payload.clone()).map_err(|e| Error::AssertError(format!("invalid {} payload: {e}", envelope.event_type)))?;
+        *event.context_mut() = EventContext {
+            correlation_id: envelope.correlation_id.clone(),
+            causation_id: envelope.causation_id.clone(),
+        };
+        Ok(event)
+    }
+}
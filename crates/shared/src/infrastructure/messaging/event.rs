@@ -1,26 +1,62 @@
-use std::{any::Any, fmt::Debug};
+use serde::Serialize;
+use serde_json::Value;
+use std::{any::Any, fmt::Debug, sync::Arc};
 
-use crate::domain::value_objects::{date::Date, pid::Pid};
+use crate::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{
+        messaging::context::EventContext,
+        types::{Result, error::Error},
+    },
+};
 
 /// Represents a domain event - something that happened in the domain.
-pub trait DomainEvent: Debug + Send + Sync + Any {
+pub trait DomainEvent: Debug + Send + Sync + Any + EventPayload {
     /// Returns the type name of this event (e.g., "StashCreated")
     fn event_type(&self) -> &str;
 
+    /// Returns the version of the payload layout, bumped on every incompatible change
+    fn schema_version(&self) -> u32 {
+        1
+    }
+
     /// Returns the ID of the aggregate that emitted this event
     fn aggregate_id(&self) -> Pid;
 
     /// Returns when this event occurred
     fn occurred_at(&self) -> Date;
 
-    /// Optional: Returns correlation ID for tracing
+    /// Returns the context the event was raised in
+    fn context(&self) -> &EventContext;
+
+    /// Lets decoders restore the context carried next to the payload
+    fn context_mut(&mut self) -> &mut EventContext;
+
+    /// Returns correlation ID for tracing
     fn correlation_id(&self) -> Option<String> {
-        None
+        self.context().correlation_id.clone()
     }
 
-    /// Optional: Returns causation ID (the event that caused this event)
+    /// Returns causation ID (the event that caused this event)
     fn causation_id(&self) -> Option<String> {
-        None
+        self.context().causation_id.clone()
+    }
+}
+
+/// A concrete event type, named without needing an instance
+pub trait TypedEvent: DomainEvent + Sized {
+    /// the value `event_type` returns for every instance
+    const EVENT_TYPE: &'static str;
+}
+
+/// JSON representation of an event, implemented for every serializable type
+pub trait EventPayload {
+    fn payload(&self) -> serde_json::Result<Value>;
+}
+
+impl<T: Serialize> EventPayload for T {
+    fn payload(&self) -> serde_json::Result<Value> {
+        serde_json::to_value(self)
     }
 }
 
@@ -40,6 +76,16 @@ impl AsAny for Box<dyn DomainEvent> {
     }
 }
 
-pub fn downcast_event<T: DomainEvent>(event: &Box<dyn DomainEvent>) -> &T {
-    event.as_any().downcast_ref::<T>().unwrap()
+impl AsAny for Arc<dyn DomainEvent> {
+    fn as_any(&self) -> &dyn Any {
+        &**self
+    }
+}
+
+/// the concrete `T` behind `event`, `Error::EventMismatch` when it is another event
+pub fn downcast_event<T: TypedEvent>(event: &Arc<dyn DomainEvent>) -> Result<&T> {
+    event.as_any().downcast_ref::<T>().ok_or_else(|| Error::EventMismatch {
+        expected: T::EVENT_TYPE,
+        actual: event.event_type().to_owned(),
+    })
 }
@@ -1,32 +1,218 @@
-use crate::infrastructure::{
-    messaging::{EventBus, EventHandler, event::DomainEvent},
-    types::Result,
+use crate::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        messaging::{EventBus, EventHandler, FailureLog, HandlerFailure, context::EventContext, event::DomainEvent},
+        types::{Result, error::Error},
+    },
 };
 use async_trait::async_trait;
 use di::injectable;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    sync::{Mutex, RwLock},
+    task::JoinSet,
+    time::Instant,
+};
+
+/// How the bus runs the handlers matching a published event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DispatchMode {
+    /// Handlers run one after the other before `publish` returns
+    #[default]
+    Inline,
+    /// Every handler runs on its own tokio task; `publish` returns immediately
+    Spawned,
+}
+
+/// Ids of the events published within the last `window`, kept to drop redelivered events
+/// without remembering every id ever published
+#[derive(Debug)]
+struct RecentIds {
+    window: Duration,
+    ids: HashSet<Pid>,
+    seen: VecDeque<(Instant, Pid)>,
+}
+
+impl RecentIds {
+    /// `false` when `id` was already seen within the window
+    fn insert(&mut self, id: &Pid) -> bool {
+        let now = Instant::now();
+        while let Some((seen_at, _)) = self.seen.front()
+            && now.duration_since(*seen_at) >= self.window
+        {
+            if let Some((_, expired)) = self.seen.pop_front() {
+                self.ids.remove(&expired);
+            }
+        }
+
+        if !self.ids.insert(id.to_owned()) {
+            return false;
+        }
+        self.seen.push_back((now, id.to_owned()));
+        true
+    }
+}
+
+impl Default for RecentIds {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(10 * 60),
+            ids: HashSet::new(),
+            seen: VecDeque::new(),
+        }
+    }
+}
+
+/// Published events are only kept for the `published` assertion of the tests
+#[cfg(feature = "testing")]
+type PublishedEvents = Mutex<Vec<Arc<dyn DomainEvent>>>;
+#[cfg(not(feature = "testing"))]
+type PublishedEvents = ();
 
 #[injectable(EventBus)]
 pub struct InMemoryEventBus {
-    published_events: Mutex<Vec<Box<dyn DomainEvent>>>,
+    mode: DispatchMode,
+    handlers: RwLock<HashMap<String, Vec<Arc<dyn EventHandler>>>>,
+    in_flight: Mutex<JoinSet<Option<HandlerFailure>>>,
+    failures: Mutex<FailureLog>,
+    published_events: PublishedEvents,
+    published_ids: Mutex<RecentIds>,
+}
+
+impl InMemoryEventBus {
+    pub fn new(mode: DispatchMode) -> Self {
+        Self {
+            mode,
+            handlers: Default::default(),
+            in_flight: Default::default(),
+            failures: Default::default(),
+            published_events: Default::default(),
+            published_ids: Default::default(),
+        }
+    }
+
+    /// Redelivered events are dropped only within `window` of the first delivery
+    pub fn with_dedup_window(mut self, window: Duration) -> Self {
+        self.published_ids.get_mut().window = window;
+        self
+    }
+
+    /// Drains the latest handler failures
+    pub async fn take_failures(&self) -> Vec<HandlerFailure> {
+        self.failures.lock().await.take()
+    }
+
+    async fn handlers_for(&self, event_type: &str) -> Vec<Arc<dyn EventHandler>> {
+        let handlers = self.handlers.read().await;
+        handlers.get(event_type).cloned().unwrap_or_default()
+    }
+
+    async fn run_handler(handler: Arc<dyn EventHandler>, event: Arc<dyn DomainEvent>, context: EventContext) -> Option<HandlerFailure> {
+        let result = context.scope(handler.handle(Arc::clone(&event))).await;
+        result.err().map(|error| Self::failure(&event, error))
+    }
+
+    fn failure(event: &Arc<dyn DomainEvent>, error: Error) -> HandlerFailure {
+        println!("event handler failed for: {} error: {:?}", event.event_type(), error);
+        HandlerFailure {
+            event_type: event.event_type().to_owned(),
+            aggregate_id: event.aggregate_id().to_string(),
+            error,
+        }
+    }
+
+    async fn record(&self, failure: Option<HandlerFailure>) {
+        if let Some(failure) = failure {
+            self.failures.lock().await.push(failure);
+        }
+    }
+
+    /// Waits for every spawned handler, including the ones spawned while waiting
+    pub async fn join_in_flight(&self) {
+        loop {
+            let mut in_flight = std::mem::take(&mut *self.in_flight.lock().await);
+            if in_flight.is_empty() {
+                return;
+            }
+
+            while let Some(joined) = in_flight.join_next().await {
+                match joined {
+                    Ok(failure) => self.record(failure).await,
+                    Err(e) => println!("event handler task aborted: {e}"),
+                }
+            }
+        }
+    }
+
+    /// Runs the handlers of `event` in the context it causes as `event_id`
+    async fn dispatch(&self, event_id: &Pid, event: Box<dyn DomainEvent>) {
+        let event: Arc<dyn DomainEvent> = Arc::from(event);
+        #[cfg(feature = "testing")]
+        self.published_events.lock().await.push(Arc::clone(&event));
+
+        let context = EventContext::caused_by(&*event, event_id);
+        let handlers = self.handlers_for(event.event_type()).await;
+        match self.mode {
+            DispatchMode::Inline => {
+                for handler in handlers {
+                    let failure = Self::run_handler(handler, Arc::clone(&event), context.clone()).await;
+                    self.record(failure).await;
+                }
+            }
+            DispatchMode::Spawned => {
+                let mut in_flight = self.in_flight.lock().await;
+                for handler in handlers {
+                    in_flight.spawn(Self::run_handler(handler, Arc::clone(&event), context.clone()));
+                }
+            }
+        }
+    }
 }
 
 #[async_trait]
 impl EventBus for InMemoryEventBus {
     async fn publish(&self, event: Box<dyn DomainEvent>) -> Result<()> {
-        let mut published_events = self.published_events.lock().await;
-        published_events.push(event);
+        self.dispatch(&Pid::new(), event).await;
+        Ok(())
+    }
+
+    async fn publish_with_id(&self, event_id: &Pid, event: Box<dyn DomainEvent>) -> Result<()> {
+        if !self.published_ids.lock().await.insert(event_id) {
+            return Ok(());
+        }
+
+        self.dispatch(event_id, event).await;
         Ok(())
     }
 
-    async fn subscribe(&self, _handler: Arc<dyn EventHandler>) -> Result<()> {
+    async fn subscribe(&self, handler: Arc<dyn EventHandler>) -> Result<()> {
+        let mut handlers = self.handlers.write().await;
+        handlers.entry(handler.event_type().to_owned()).or_default().push(handler);
         Ok(())
     }
 
     #[cfg(feature = "testing")]
     async fn published(&self, event: Box<dyn DomainEvent>) -> bool {
         let published_events = self.published_events.lock().await;
-        published_events.iter().find(|e| e.event_type() == event.event_type()).is_some()
+        published_events.iter().any(|e| e.event_type() == event.event_type())
+    }
+
+    #[cfg(feature = "testing")]
+    async fn wait_until_idle(&self) -> Vec<HandlerFailure> {
+        loop {
+            self.join_in_flight().await;
+            let handlers: Vec<Arc<dyn EventHandler>> = self.handlers.read().await.values().flatten().cloned().collect();
+            let mut pending = false;
+            for handler in handlers {
+                pending |= handler.join_pending().await;
+            }
+            if !pending {
+                return self.take_failures().await;
+            }
+        }
     }
 }
@@ -1,9 +1,26 @@
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 
-use crate::infrastructure::{messaging::event::DomainEvent, types::Result};
+use crate::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        messaging::{
+            event::{DomainEvent, TypedEvent, downcast_event},
+            retry::RetryPolicy,
+        },
+        types::{Result, error::Error},
+    },
+};
 use async_trait::async_trait;
+pub mod context;
+pub mod envelope;
 pub mod event;
 pub mod memory;
+pub mod outbox;
+pub mod quarantine;
+pub mod redis_streams;
+pub mod retry;
+pub mod subscriber;
+pub mod workers;
 
 /// Trait for handling domain events
 #[async_trait]
@@ -12,7 +29,110 @@ where
     Self: Send + Sync,
 {
     fn event_type(&self) -> &'static str;
-    async fn handle(&self, event: Box<dyn DomainEvent>) -> Result<()>;
+
+    /// Stable name of the handler, brokers derive its durable subscription from it
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// How often the handler is retried before its event is quarantined
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    async fn handle(&self, event: Arc<dyn DomainEvent>) -> Result<()>;
+
+    /// Waits for the work the handler moved off the publishing path, `false` when there was none
+    #[cfg(feature = "testing")]
+    async fn join_pending(&self) -> bool {
+        false
+    }
+}
+
+/// Handler for a single concrete event type.
+///
+/// Every `TypedEventHandler` is an `EventHandler` subscribed to `Event::EVENT_TYPE`; an event of
+/// another type is rejected with `Error::EventMismatch` instead of reaching `handle`.
+#[async_trait]
+pub trait TypedEventHandler
+where
+    Self: Send + Sync,
+{
+    type Event: TypedEvent;
+
+    /// see `EventHandler::name`
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// see `EventHandler::retry_policy`
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    async fn handle(&self, event: &Self::Event) -> Result<()>;
+}
+
+#[async_trait]
+impl<H: TypedEventHandler> EventHandler for H {
+    fn event_type(&self) -> &'static str {
+        H::Event::EVENT_TYPE
+    }
+
+    fn name(&self) -> &str {
+        TypedEventHandler::name(self)
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        TypedEventHandler::retry_policy(self)
+    }
+
+    async fn handle(&self, event: Arc<dyn DomainEvent>) -> Result<()> {
+        let event = downcast_event::<H::Event>(&event)?;
+        TypedEventHandler::handle(self, event).await
+    }
+}
+
+/// A handler invocation that returned an error
+#[derive(Debug, Clone)]
+pub struct HandlerFailure {
+    /// Type of the event the handler was invoked with
+    pub event_type: String,
+    /// ID of the aggregate that emitted the event
+    pub aggregate_id: String,
+    /// Error returned by the handler
+    pub error: Error,
+}
+
+/// The latest handler failures of a bus, the oldest are dropped beyond `capacity`
+/// so a long running bus does not keep every failure it ever saw
+#[derive(Debug)]
+pub(crate) struct FailureLog {
+    capacity: usize,
+    failures: VecDeque<HandlerFailure>,
+}
+
+impl FailureLog {
+    pub(crate) fn push(&mut self, failure: HandlerFailure) {
+        if self.failures.len() == self.capacity {
+            self.failures.pop_front();
+        }
+        self.failures.push_back(failure);
+    }
+
+    /// Drains the failures kept so far, oldest first
+    pub(crate) fn take(&mut self) -> Vec<HandlerFailure> {
+        self.failures.drain(..).collect()
+    }
+}
+
+impl Default for FailureLog {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            failures: VecDeque::new(),
+        }
+    }
 }
 
 /// Trait for publishing and subscribing to domain events
@@ -21,9 +141,16 @@ pub trait EventBus: Sync + Send {
     /// Publishes a domain event to all subscribers
     async fn publish(&self, event: Box<dyn DomainEvent>) -> Result<()>;
 
+    /// Publishes a domain event carrying a deduplication id; an id published before is dropped
+    async fn publish_with_id(&self, event_id: &Pid, event: Box<dyn DomainEvent>) -> Result<()>;
+
     /// Subscribes a handler to a specific event type
     async fn subscribe(&self, handler: Arc<dyn EventHandler>) -> Result<()>;
 
     #[cfg(feature = "testing")]
     async fn published(&self, event: Box<dyn DomainEvent>) -> bool;
+
+    /// Waits until every dispatched handler has finished and drains the failures collected so far
+    #[cfg(feature = "testing")]
+    async fn wait_until_idle(&self) -> Vec<HandlerFailure>;
 }
@@ -0,0 +1,64 @@
+use crate::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        messaging::{envelope::EventEnvelope, outbox::Outbox},
+        types::Result,
+    },
+};
+use async_trait::async_trait;
+use di::injectable;
+use tokio::sync::Mutex;
+
+/// Outbox for in-memory repositories; nothing survives the process
+#[injectable(Outbox)]
+#[derive(Default)]
+pub struct InMemoryOutbox {
+    envelopes: Mutex<Vec<(EventEnvelope, Delivery)>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    Pending,
+    Published,
+    Failed,
+}
+
+impl InMemoryOutbox {
+    async fn mark(&self, id: &Pid, delivery: Delivery) {
+        let mut stored = self.envelopes.lock().await;
+        stored
+            .iter_mut()
+            .filter(|(envelope, current)| &envelope.event_id == id && *current == Delivery::Pending)
+            .for_each(|(_, current)| *current = delivery);
+    }
+}
+
+#[async_trait]
+impl Outbox for InMemoryOutbox {
+    async fn append(&self, envelopes: &[EventEnvelope]) -> Result<()> {
+        let mut stored = self.envelopes.lock().await;
+        stored.extend(envelopes.iter().cloned().map(|envelope| (envelope, Delivery::Pending)));
+        Ok(())
+    }
+
+    async fn pending(&self, limit: u32) -> Result<Vec<EventEnvelope>> {
+        let stored = self.envelopes.lock().await;
+        let pending = stored
+            .iter()
+            .filter(|(_, delivery)| *delivery == Delivery::Pending)
+            .map(|(envelope, _)| envelope.clone())
+            .take(limit as usize)
+            .collect();
+        Ok(pending)
+    }
+
+    async fn mark_published(&self, id: &Pid) -> Result<()> {
+        self.mark(id, Delivery::Published).await;
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: &Pid, _error: &str) -> Result<()> {
+        self.mark(id, Delivery::Failed).await;
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        messaging::{
+            EventBus,
+            envelope::{EventEnvelope, EventRegistry},
+            event::DomainEvent,
+        },
+        types::Result,
+    },
+};
+use async_trait::async_trait;
+use di::injectable;
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+pub mod memory;
+
+/// An event recorded for the outbox, kept together with the typed event for publishing right after commit
+#[derive(Debug)]
+pub struct RecordedEvent {
+    pub envelope: EventEnvelope,
+    pub event: Box<dyn DomainEvent>,
+}
+
+impl RecordedEvent {
+    pub fn record<E: DomainEvent>(event: Box<E>) -> Result<Self> {
+        let envelope = EventEnvelope::seal(&*event)?;
+        Ok(Self { envelope, event })
+    }
+}
+
+/// Store of events waiting to be published.
+///
+/// Repositories append to it within the unit of work that saves the aggregate, so an event
+/// is stored if and only if the state change it describes is.
+#[async_trait]
+pub trait Outbox: Sync + Send {
+    async fn append(&self, envelopes: &[EventEnvelope]) -> Result<()>;
+    /// envelopes neither published nor failed, oldest first
+    async fn pending(&self, limit: u32) -> Result<Vec<EventEnvelope>>;
+    async fn mark_published(&self, id: &Pid) -> Result<()>;
+    /// sets aside an envelope that can never be published, keeping `error` for whoever inspects it
+    async fn mark_failed(&self, id: &Pid, error: &str) -> Result<()>;
+}
+
+/// Moves outbox events to the `EventBus`.
+///
+/// Delivery is at least once: an event is marked published only after the bus accepted it,
+/// and the bus drops ids it has seen before.
+#[injectable]
+pub struct OutboxRelay {
+    outbox: Arc<dyn Outbox>,
+    event_bus: Arc<dyn EventBus>,
+    registry: Arc<EventRegistry>,
+}
+
+impl OutboxRelay {
+    /// publishes the events of a committed unit of work; failures are left for `relay_pending`
+    pub async fn dispatch(&self, recorded: Vec<RecordedEvent>) {
+        for RecordedEvent { envelope, event } in recorded {
+            if let Err(e) = self.publish(&envelope.event_id, event).await {
+                println!("failed to dispatch event: {} error: {:?}", envelope.event_type, e);
+            }
+        }
+    }
+
+    /// stores events not tied to a saved aggregate, then publishes them like `dispatch`
+    pub async fn record(&self, recorded: Vec<RecordedEvent>) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = recorded.iter().map(|recorded| recorded.envelope.clone()).collect();
+        self.outbox.append(&envelopes).await?;
+        self.dispatch(recorded).await;
+        Ok(())
+    }
+
+    /// publishes up to `limit` events left behind by failed dispatches or crashes, returns how many were relayed
+    pub async fn relay_pending(&self, limit: u32) -> Result<usize> {
+        let pending = self.outbox.pending(limit).await?;
+        let mut relayed = 0;
+
+        for envelope in pending {
+            // an undecodable event never will be, so it is marked failed instead of blocking the events behind it;
+            // one the bus rejected stays pending and is retried by the next run
+            let event = match self.registry.decode(&envelope) {
+                Ok(event) => event,
+                Err(e) => {
+                    println!("failed to decode event: {} error: {:?}", envelope.event_type, e);
+                    self.outbox.mark_failed(&envelope.event_id, &format!("{e:?}")).await?;
+                    continue;
+                }
+            };
+            match self.publish(&envelope.event_id, event).await {
+                Ok(_) => relayed += 1,
+                Err(e) => println!("failed to relay event: {} error: {:?}", envelope.event_type, e),
+            }
+        }
+
+        Ok(relayed)
+    }
+
+    /// runs `relay_pending` every `interval` until the returned task is aborted
+    pub fn spawn(self: Arc<Self>, interval: Duration, batch_size: u32) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.relay_pending(batch_size).await {
+                    println!("outbox relay run failed: {:?}", e);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    async fn publish(&self, id: &Pid, event: Box<dyn DomainEvent>) -> Result<()> {
+        self.event_bus.publish_with_id(id, event).await?;
+        self.outbox.mark_published(id).await
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        messaging::quarantine::{QuarantineStore, QuarantinedEvent},
+        types::Result,
+    },
+};
+use async_trait::async_trait;
+use di::injectable;
+use tokio::sync::Mutex;
+
+/// Quarantine for in-memory setups; nothing survives the process
+#[injectable(QuarantineStore)]
+#[derive(Default)]
+pub struct InMemoryQuarantineStore {
+    /// quarantined events in the order they arrived
+    events: Mutex<Vec<QuarantinedEvent>>,
+}
+
+#[async_trait]
+impl QuarantineStore for InMemoryQuarantineStore {
+    async fn put(&self, event: &QuarantinedEvent) -> Result<()> {
+        let mut events = self.events.lock().await;
+        match events.iter_mut().find(|stored| stored.pid == event.pid) {
+            Some(stored) => *stored = event.clone(),
+            None => events.push(event.clone()),
+        }
+        Ok(())
+    }
+
+    async fn list(&self, limit: u32) -> Result<Vec<QuarantinedEvent>> {
+        let events = self.events.lock().await;
+        Ok(events.iter().take(limit as usize).cloned().collect())
+    }
+
+    async fn find(&self, id: &Pid) -> Result<Option<QuarantinedEvent>> {
+        let events = self.events.lock().await;
+        Ok(events.iter().find(|event| &event.pid == id).cloned())
+    }
+
+    async fn remove(&self, id: &Pid) -> Result<()> {
+        self.events.lock().await.retain(|event| &event.pid != id);
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{
+        messaging::{
+            EventHandler,
+            context::EventContext,
+            envelope::{EventEnvelope, EventRegistry},
+        },
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use di::injectable;
+use std::sync::Arc;
+
+pub mod memory;
+
+/// An event a handler kept failing on, parked until it is replayed
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedEvent {
+    pub pid: Pid,
+    /// `EventHandler::name` of the failing handler
+    pub handler: String,
+    pub envelope: EventEnvelope,
+    /// failed invocations, replays included
+    pub attempts: u32,
+    pub last_error: String,
+    pub quarantined_at: Date,
+}
+
+impl QuarantinedEvent {
+    pub fn new(handler: &str, envelope: EventEnvelope, attempts: u32, error: &Error) -> Self {
+        Self {
+            pid: Pid::new(),
+            handler: handler.to_owned(),
+            envelope,
+            attempts,
+            last_error: format!("{error:?}"),
+            quarantined_at: Utc::now(),
+        }
+    }
+}
+
+/// Store of quarantined events
+#[async_trait]
+pub trait QuarantineStore: Sync + Send {
+    /// inserts the event, or updates it when it is quarantined already
+    async fn put(&self, event: &QuarantinedEvent) -> Result<()>;
+    /// oldest first
+    async fn list(&self, limit: u32) -> Result<Vec<QuarantinedEvent>>;
+    async fn find(&self, id: &Pid) -> Result<Option<QuarantinedEvent>>;
+    async fn remove(&self, id: &Pid) -> Result<()>;
+}
+
+/// Outcome of replaying a batch of quarantined events
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayRun {
+    pub replayed: usize,
+    pub failed: usize,
+}
+
+/// Hands quarantined events back to the handler that failed on them, once it is fixed.
+///
+/// The event is handled exactly as the first time, in the context it caused, and leaves the
+/// quarantine only when the handler succeeds.
+#[injectable]
+pub struct QuarantineReplayer {
+    quarantine: Arc<dyn QuarantineStore>,
+    registry: Arc<EventRegistry>,
+    handlers: Vec<Arc<dyn EventHandler>>,
+}
+
+impl QuarantineReplayer {
+    pub async fn get_quarantined(&self, limit: u32) -> Result<Vec<QuarantinedEvent>> {
+        self.quarantine.list(limit).await
+    }
+
+    /// replays one event, keeping it quarantined with the new error when its handler fails again
+    pub async fn replay(&self, id: &Pid) -> Result<()> {
+        let mut quarantined = self.quarantine.find(id).await?.ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+        let handler = self
+            .handlers
+            .iter()
+            .find(|handler| handler.name() == quarantined.handler && handler.event_type() == quarantined.envelope.event_type)
+            .ok_or_else(|| Error::AssertError(format!("no handler named: {}", quarantined.handler)))?;
+
+        let event = Arc::from(self.registry.decode(&quarantined.envelope)?);
+        let context = EventContext::caused_by(&*event, &quarantined.envelope.event_id);
+        match context.scope(handler.handle(event)).await {
+            Ok(()) => self.quarantine.remove(id).await,
+            Err(e) => {
+                quarantined.attempts += 1;
+                quarantined.last_error = format!("{e:?}");
+                self.quarantine.put(&quarantined).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// replays up to `limit` quarantined events, oldest first
+    pub async fn replay_all(&self, limit: u32) -> Result<ReplayRun> {
+        let mut run = ReplayRun::default();
+        for quarantined in self.quarantine.list(limit).await? {
+            match self.replay(&quarantined.pid).await {
+                Ok(()) => run.replayed += 1,
+                Err(e) => {
+                    println!(
+                        "replay of {} failed for handler {} error: {:?}",
+                        quarantined.pid.to_string(),
+                        quarantined.handler,
+                        e
+                    );
+                    run.failed += 1;
+                }
+            }
+        }
+
+        Ok(run)
+    }
+}
@@ -0,0 +1,470 @@
+use crate::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        messaging::{
+            EventBus, EventHandler, FailureLog, HandlerFailure,
+            context::EventContext,
+            envelope::{EventEnvelope, EventRegistry},
+            event::DomainEvent,
+        },
+        types::{Result, error::Error},
+    },
+};
+use ::redis::{
+    AsyncCommands, Client, RedisError, Script,
+    aio::{ConnectionManager, MultiplexedConnection},
+    streams::{StreamAutoClaimOptions, StreamAutoClaimReply, StreamId, StreamReadOptions, StreamReadReply},
+};
+use async_trait::async_trait;
+use di::{ServiceCollection, singleton};
+use serde::Deserialize;
+#[cfg(feature = "testing")]
+use std::collections::HashSet;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{sync::Mutex, task::JoinSet};
+
+/// field of a stream entry holding the JSON `EventEnvelope`
+const ENVELOPE_FIELD: &str = "envelope";
+
+/// Appends to the stream only when the event id was not published within the deduplication window
+const PUBLISH_SCRIPT: &str = r"
+if redis.call('SET', KEYS[1], '1', 'NX', 'EX', ARGV[1]) then
+    return redis.call('XADD', KEYS[2], '*', ARGV[2], ARGV[3])
+end
+return false
+";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RedisStreamsConfig {
+    pub url: String,
+    /// every event type gets its own stream, named `{stream_prefix}:{event_type}`
+    #[serde(default = "RedisStreamsConfig::default_stream_prefix")]
+    pub stream_prefix: String,
+    /// name of this process within the consumer groups, random when unset
+    #[serde(default)]
+    pub consumer: Option<String>,
+    /// entries read per round trip
+    #[serde(default = "RedisStreamsConfig::default_batch_size")]
+    pub batch_size: usize,
+    /// how long a consumer waits for new entries before checking for abandoned ones
+    #[serde(default = "RedisStreamsConfig::default_block_millis")]
+    pub block_millis: u64,
+    /// idle time after which an unacknowledged entry is delivered again
+    #[serde(default = "RedisStreamsConfig::default_claim_idle_millis")]
+    pub claim_idle_millis: u64,
+    /// how long published event ids are remembered to drop duplicates
+    #[serde(default = "RedisStreamsConfig::default_dedup_ttl_seconds")]
+    pub dedup_ttl_seconds: u64,
+}
+
+impl RedisStreamsConfig {
+    fn default_stream_prefix() -> String {
+        "events".to_owned()
+    }
+
+    fn default_batch_size() -> usize {
+        16
+    }
+
+    fn default_block_millis() -> u64 {
+        1000
+    }
+
+    fn default_claim_idle_millis() -> u64 {
+        30_000
+    }
+
+    fn default_dedup_ttl_seconds() -> u64 {
+        86_400
+    }
+
+    fn stream(&self, event_type: &str) -> String {
+        format!("{}:{}", self.stream_prefix, event_type)
+    }
+
+    /// entries no handler can decode, with the group and error they failed with
+    fn dead_letter_stream(&self) -> String {
+        format!("{}:dead-letter", self.stream_prefix)
+    }
+}
+
+/// Consumer group of one handler, with the entries its handler rejected
+struct Subscription {
+    stream: String,
+    group: String,
+    handler: Arc<dyn EventHandler>,
+    /// tells the entries left pending on purpose from unprocessed ones while waiting for idle
+    #[cfg(feature = "testing")]
+    nacked: Mutex<HashSet<String>>,
+}
+
+impl Subscription {
+    #[cfg(feature = "testing")]
+    async fn track(&self, entry_id: &str, nacked: bool) {
+        let mut nacked_entries = self.nacked.lock().await;
+        if nacked {
+            nacked_entries.insert(entry_id.to_owned());
+        } else {
+            nacked_entries.remove(entry_id);
+        }
+    }
+}
+
+/// Event bus on top of Redis Streams.
+///
+/// Each event type is a stream and each handler a durable consumer group on it, named after
+/// `EventHandler::name`, so every handler sees every event once per group no matter how many
+/// processes consume it. An entry is acknowledged once its handler succeeded; a failed entry
+/// stays pending and is delivered again after `claim_idle_millis`, as are entries left behind
+/// by crashed consumers. Entries that can not be decoded are moved to `{stream_prefix}:dead-letter`.
+pub struct RedisStreamsEventBus {
+    client: Client,
+    connection: ConnectionManager,
+    registry: Arc<EventRegistry>,
+    config: RedisStreamsConfig,
+    consumer: String,
+    publish_script: Script,
+    subscriptions: Mutex<Vec<Arc<Subscription>>>,
+    consumers: Mutex<JoinSet<()>>,
+    in_flight: Arc<AtomicUsize>,
+    failures: Arc<Mutex<FailureLog>>,
+    #[cfg(feature = "testing")]
+    published_events: Mutex<Vec<String>>,
+}
+
+impl RedisStreamsEventBus {
+    pub async fn connect(config: &RedisStreamsConfig, registry: Arc<EventRegistry>) -> Result<Self> {
+        let client = Client::open(config.url.as_str()).map_err(redis_error)?;
+        let connection = ConnectionManager::new(client.clone()).await.map_err(redis_error)?;
+
+        Ok(Self {
+            client,
+            connection,
+            registry,
+            config: config.clone(),
+            consumer: config.consumer.clone().unwrap_or_else(|| Pid::new().to_string()),
+            publish_script: Script::new(PUBLISH_SCRIPT),
+            subscriptions: Default::default(),
+            consumers: Default::default(),
+            in_flight: Default::default(),
+            failures: Default::default(),
+            #[cfg(feature = "testing")]
+            published_events: Default::default(),
+        })
+    }
+
+    /// Drains the latest handler failures
+    pub async fn take_failures(&self) -> Vec<HandlerFailure> {
+        self.failures.lock().await.take()
+    }
+
+    async fn append(&self, envelope: &EventEnvelope) -> Result<()> {
+        let payload = serde_json::to_string(envelope).map_err(|e| Error::AssertError(format!("unserializable envelope: {e}")))?;
+        let mut connection = self.connection.clone();
+        let _: Option<String> = self
+            .publish_script
+            .key(format!("{}:published:{}", self.config.stream_prefix, envelope.event_id.to_string()))
+            .key(self.config.stream(&envelope.event_type))
+            .arg(self.config.dedup_ttl_seconds)
+            .arg(ENVELOPE_FIELD)
+            .arg(payload)
+            .invoke_async(&mut connection)
+            .await
+            .map_err(redis_error)?;
+
+        #[cfg(feature = "testing")]
+        self.published_events.lock().await.push(envelope.event_type.clone());
+        Ok(())
+    }
+
+    /// Whether every group caught up with its stream and holds no entry besides the rejected ones
+    #[cfg(feature = "testing")]
+    async fn is_idle(&self) -> Result<bool> {
+        if self.in_flight.load(Ordering::SeqCst) > 0 {
+            return Ok(false);
+        }
+
+        let mut connection = self.connection.clone();
+        for subscription in self.subscriptions.lock().await.iter() {
+            if subscription.handler.join_pending().await {
+                return Ok(false);
+            }
+            let groups: ::redis::streams::StreamInfoGroupsReply = connection.xinfo_groups(&subscription.stream).await.map_err(redis_error)?;
+            let Some(group) = groups.groups.iter().find(|group| group.name == subscription.group) else {
+                continue;
+            };
+            if group.lag.unwrap_or(0) > 0 {
+                return Ok(false);
+            }
+
+            // entries acked by another consumer since they were rejected here are no longer nacked
+            let pending: ::redis::streams::StreamPendingCountReply = connection
+                .xpending_count(&subscription.stream, &subscription.group, "-", "+", group.pending.max(1))
+                .await
+                .map_err(redis_error)?;
+            let mut nacked = subscription.nacked.lock().await;
+            nacked.retain(|id| pending.ids.iter().any(|entry| &entry.id == id));
+            if pending.ids.len() > nacked.len() {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl EventBus for RedisStreamsEventBus {
+    async fn publish(&self, event: Box<dyn DomainEvent>) -> Result<()> {
+        self.append(&EventEnvelope::seal(&*event)?).await
+    }
+
+    async fn publish_with_id(&self, event_id: &Pid, event: Box<dyn DomainEvent>) -> Result<()> {
+        let mut envelope = EventEnvelope::seal(&*event)?;
+        envelope.event_id = event_id.to_owned();
+        self.append(&envelope).await
+    }
+
+    /// Creates the handler's consumer group when missing and starts consuming it
+    async fn subscribe(&self, handler: Arc<dyn EventHandler>) -> Result<()> {
+        let subscription = Arc::new(Subscription {
+            stream: self.config.stream(handler.event_type()),
+            group: handler.name().to_owned(),
+            handler: Arc::clone(&handler),
+            #[cfg(feature = "testing")]
+            nacked: Default::default(),
+        });
+
+        // a new group starts at the end of the stream, an existing one where it left off
+        let mut connection = self.connection.clone();
+        let created: std::result::Result<(), RedisError> = connection.xgroup_create_mkstream(&subscription.stream, &subscription.group, "$").await;
+        if let Err(e) = created
+            && e.code() != Some("BUSYGROUP")
+        {
+            return Err(redis_error(e));
+        }
+
+        let consumer = Consumer {
+            connection: self.client.get_multiplexed_async_connection().await.map_err(redis_error)?,
+            client: self.client.clone(),
+            subscription: Arc::clone(&subscription),
+            consumer: self.consumer.clone(),
+            handler,
+            registry: Arc::clone(&self.registry),
+            config: self.config.clone(),
+            in_flight: Arc::clone(&self.in_flight),
+            failures: Arc::clone(&self.failures),
+        };
+        self.subscriptions.lock().await.push(subscription);
+        self.consumers.lock().await.spawn(consumer.run());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "testing")]
+    async fn published(&self, event: Box<dyn DomainEvent>) -> bool {
+        let published_events = self.published_events.lock().await;
+        published_events.iter().any(|event_type| event_type == event.event_type())
+    }
+
+    #[cfg(feature = "testing")]
+    async fn wait_until_idle(&self) -> Vec<HandlerFailure> {
+        loop {
+            match self.is_idle().await {
+                Ok(true) => return self.take_failures().await,
+                Ok(false) => {}
+                Err(e) => println!("failed to inspect consumer groups: {:?}", e),
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+}
+
+/// Reads the entries of one consumer group and hands them to its handler
+struct Consumer {
+    connection: MultiplexedConnection,
+    client: Client,
+    subscription: Arc<Subscription>,
+    consumer: String,
+    handler: Arc<dyn EventHandler>,
+    registry: Arc<EventRegistry>,
+    config: RedisStreamsConfig,
+    in_flight: Arc<AtomicUsize>,
+    failures: Arc<Mutex<FailureLog>>,
+}
+
+impl Consumer {
+    async fn run(mut self) {
+        let mut claim_cursor = "0-0".to_owned();
+        let mut failed_polls = 0;
+        loop {
+            match self.poll(&mut claim_cursor).await {
+                Ok(entries) => {
+                    failed_polls = 0;
+                    for entry in entries {
+                        self.process(entry).await;
+                        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                    }
+                }
+                Err(e) => {
+                    println!("failed to read stream: {} error: {:?}", self.subscription.stream, e);
+                    failed_polls += 1;
+                    tokio::time::sleep(self.backoff(failed_polls)).await;
+                    self.reconnect().await;
+                }
+            }
+        }
+    }
+
+    /// a multiplexed connection stays broken once the broker dropped it, e.g. on a restart, so a failed poll
+    /// opens a new one
+    async fn reconnect(&mut self) {
+        match self.client.get_multiplexed_async_connection().await {
+            Ok(connection) => self.connection = connection,
+            Err(e) => println!("failed to reconnect consumer of stream: {} error: {:?}", self.subscription.stream, e),
+        }
+    }
+
+    /// doubles the wait with every failed poll in a row, up to `max_reconnect_backoff`
+    fn backoff(&self, failed_polls: u32) -> Duration {
+        let wait = Duration::from_millis(self.config.block_millis).saturating_mul(1 << failed_polls.min(10));
+        wait.min(max_reconnect_backoff())
+    }
+
+    /// entries idle for longer than `claim_idle_millis` first, then new ones
+    async fn poll(&mut self, claim_cursor: &mut String) -> Result<Vec<StreamId>> {
+        let Subscription { stream, group, .. } = &*self.subscription;
+        let claimed: StreamAutoClaimReply = self
+            .connection
+            .xautoclaim_options(
+                stream,
+                group,
+                &self.consumer,
+                self.config.claim_idle_millis,
+                claim_cursor.as_str(),
+                StreamAutoClaimOptions::default().count(self.config.batch_size),
+            )
+            .await
+            .map_err(redis_error)?;
+        *claim_cursor = claimed.next_stream_id;
+        if !claimed.claimed.is_empty() {
+            self.in_flight.fetch_add(claimed.claimed.len(), Ordering::SeqCst);
+            return Ok(claimed.claimed);
+        }
+
+        let options = StreamReadOptions::default()
+            .group(group, &self.consumer)
+            .count(self.config.batch_size)
+            .block(self.config.block_millis as usize);
+        let read: Option<StreamReadReply> = self.connection.xread_options(&[stream], &[">"], &options).await.map_err(redis_error)?;
+        let entries: Vec<StreamId> = read.into_iter().flat_map(|reply| reply.keys).flat_map(|key| key.ids).collect();
+        self.in_flight.fetch_add(entries.len(), Ordering::SeqCst);
+
+        Ok(entries)
+    }
+
+    /// acks the entry once handled, leaves it pending (nack) when the handler failed
+    async fn process(&mut self, entry: StreamId) {
+        let (event, context) = match self.decode(&entry) {
+            Ok(decoded) => decoded,
+            Err(error) => return self.dead_letter(&entry, error).await,
+        };
+
+        match context.scope(self.handler.handle(Arc::clone(&event))).await {
+            Ok(()) => {
+                let Subscription { stream, group, .. } = &*self.subscription;
+                let acked: std::result::Result<usize, RedisError> = self.connection.xack(stream, group, &[&entry.id]).await;
+                match acked {
+                    Ok(_) => {
+                        #[cfg(feature = "testing")]
+                        self.subscription.track(&entry.id, false).await;
+                    }
+                    Err(e) => println!("failed to ack entry: {} error: {:?}", entry.id, e),
+                }
+            }
+            Err(error) => {
+                println!("event handler {} failed for entry: {} error: {:?}", self.handler.name(), entry.id, error);
+                #[cfg(feature = "testing")]
+                self.subscription.track(&entry.id, true).await;
+                self.record_failure(event.aggregate_id().to_string(), error).await;
+            }
+        }
+    }
+
+    /// an entry that can not be decoded never will be, so it is moved to the dead letter stream and acked
+    /// instead of being claimed again forever
+    async fn dead_letter(&mut self, entry: &StreamId, error: Error) {
+        println!("dead lettering entry: {} of {} error: {:?}", entry.id, self.subscription.stream, error);
+        let Subscription { stream, group, .. } = &*self.subscription;
+        let payload: String = entry.get(ENVELOPE_FIELD).unwrap_or_default();
+        let reason = format!("{error:?}");
+        let fields = [
+            ("stream", stream.as_str()),
+            ("group", group.as_str()),
+            ("entry", entry.id.as_str()),
+            (ENVELOPE_FIELD, payload.as_str()),
+            ("error", reason.as_str()),
+        ];
+        let moved: std::result::Result<(), RedisError> = ::redis::pipe()
+            .atomic()
+            .xadd(self.config.dead_letter_stream(), "*", &fields)
+            .ignore()
+            .xack(stream, group, &[&entry.id])
+            .ignore()
+            .query_async(&mut self.connection)
+            .await;
+        if let Err(e) = &moved {
+            println!("failed to dead letter entry: {} error: {:?}", entry.id, e);
+        }
+        #[cfg(feature = "testing")]
+        self.subscription.track(&entry.id, moved.is_err()).await;
+
+        self.record_failure(String::new(), error).await;
+    }
+
+    async fn record_failure(&self, aggregate_id: String, error: Error) {
+        self.failures.lock().await.push(HandlerFailure {
+            event_type: self.handler.event_type().to_owned(),
+            aggregate_id,
+            error,
+        });
+    }
+
+    /// decodes the event of an entry and the context its handler runs in
+    fn decode(&self, entry: &StreamId) -> Result<(Arc<dyn DomainEvent>, EventContext)> {
+        let payload: String = entry
+            .get(ENVELOPE_FIELD)
+            .ok_or_else(|| Error::AssertError(format!("entry {} has no envelope", entry.id)))?;
+        let envelope: EventEnvelope = serde_json::from_str(&payload).map_err(|e| Error::AssertError(format!("invalid envelope: {e}")))?;
+
+        let event: Arc<dyn DomainEvent> = Arc::from(self.registry.decode(&envelope)?);
+        let context = EventContext::caused_by(&*event, &envelope.event_id);
+        Ok((event, context))
+    }
+}
+
+/// longest wait between two reconnect attempts of a consumer
+fn max_reconnect_backoff() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn redis_error(e: RedisError) -> Error {
+    println!("redis error: {e}");
+    Error::ServiceError
+}
+
+/// Connects to the broker described by `config` and registers it as the `EventBus`
+pub async fn add_redis_event_bus<'a>(
+    services: &'a mut ServiceCollection,
+    config: &RedisStreamsConfig,
+    registry: Arc<EventRegistry>,
+) -> Result<&'a mut ServiceCollection> {
+    let event_bus: Arc<dyn EventBus> = Arc::new(RedisStreamsEventBus::connect(config, registry).await?);
+    Ok(services.add(singleton::<dyn EventBus, RedisStreamsEventBus>().from(move |_| event_bus.clone())))
+}
@@ -0,0 +1,166 @@
+use crate::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        messaging::{
+            EventHandler,
+            context::EventContext,
+            envelope::EventEnvelope,
+            event::DomainEvent,
+            quarantine::{QuarantineStore, QuarantinedEvent},
+        },
+        types::Result,
+    },
+};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::Mutex, task::JoinSet};
+
+/// How often a handler is invoked for one event before the event is quarantined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// invocations before giving up, the first one included
+    pub max_attempts: u32,
+    /// wait after the first failure, doubled with every further one
+    pub base_backoff_millis: u64,
+    pub max_backoff_millis: u64,
+}
+
+impl RetryPolicy {
+    /// exponential backoff after `attempts` failed invocations, capped at `max_backoff_millis`
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempts.saturating_sub(1));
+        Duration::from_millis(self.base_backoff_millis.saturating_mul(factor).min(self.max_backoff_millis))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_backoff_millis: 100,
+            max_backoff_millis: 5_000,
+        }
+    }
+}
+
+/// Runs a handler under its `RetryPolicy`, quarantining events it keeps failing on.
+///
+/// Only the first invocation runs on the publishing path. An event it fails on is quarantined
+/// right away and counts as handled, so brokers stop redelivering it; the remaining attempts run
+/// on a background task and take the event out of the quarantine once one succeeds, otherwise it
+/// stays in the `QuarantineStore` until replayed. Only when quarantining fails is the handler's
+/// error returned.
+pub struct RetryingHandler {
+    inner: Arc<dyn EventHandler>,
+    quarantine: Arc<dyn QuarantineStore>,
+    retries: Mutex<JoinSet<()>>,
+}
+
+impl RetryingHandler {
+    pub fn new(inner: Arc<dyn EventHandler>, quarantine: Arc<dyn QuarantineStore>) -> Self {
+        Self {
+            inner,
+            quarantine,
+            retries: Default::default(),
+        }
+    }
+
+    /// buses run handlers in the context caused by the event, so its causation is the event id
+    fn envelope(event: &dyn DomainEvent) -> Result<EventEnvelope> {
+        let mut envelope = EventEnvelope::seal(event)?;
+        if let Some(event_id) = EventContext::current().causation_id.and_then(|id| id.parse::<Pid>().ok()) {
+            envelope.event_id = event_id;
+        }
+        Ok(envelope)
+    }
+
+    /// invokes the handler for the attempts left after the first one, keeping the quarantined event up to date
+    async fn retry(
+        inner: Arc<dyn EventHandler>,
+        quarantine: Arc<dyn QuarantineStore>,
+        event: Arc<dyn DomainEvent>,
+        mut quarantined: QuarantinedEvent,
+    ) {
+        let policy = inner.retry_policy();
+        while quarantined.attempts < policy.max_attempts {
+            tokio::time::sleep(policy.backoff(quarantined.attempts)).await;
+            let error = match inner.handle(Arc::clone(&event)).await {
+                Ok(()) => {
+                    if let Err(e) = quarantine.remove(&quarantined.pid).await {
+                        println!("failed to release {} from quarantine error: {:?}", event.event_type(), e);
+                    }
+                    return;
+                }
+                Err(error) => error,
+            };
+
+            quarantined.attempts += 1;
+            quarantined.last_error = format!("{error:?}");
+            println!(
+                "event handler {} failed for: {} attempt: {} error: {:?}",
+                inner.name(),
+                event.event_type(),
+                quarantined.attempts,
+                error
+            );
+            if let Err(e) = quarantine.put(&quarantined).await {
+                println!("failed to update quarantined {} error: {:?}", event.event_type(), e);
+            }
+        }
+
+        println!(
+            "event {} stays quarantined for handler {} after {} attempts",
+            event.event_type(),
+            inner.name(),
+            quarantined.attempts
+        );
+    }
+}
+
+#[async_trait]
+impl EventHandler for RetryingHandler {
+    fn event_type(&self) -> &'static str {
+        self.inner.event_type()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.inner.retry_policy()
+    }
+
+    async fn handle(&self, event: Arc<dyn DomainEvent>) -> Result<()> {
+        let error = match self.inner.handle(Arc::clone(&event)).await {
+            Ok(()) => return Ok(()),
+            Err(error) => error,
+        };
+
+        println!("quarantining {} for handler {} error: {:?}", event.event_type(), self.name(), error);
+        let Ok(envelope) = Self::envelope(&*event) else {
+            return Err(error);
+        };
+        let quarantined = QuarantinedEvent::new(self.name(), envelope, 1, &error);
+        if let Err(e) = self.quarantine.put(&quarantined).await {
+            println!("failed to quarantine {} error: {:?}", event.event_type(), e);
+            return Err(error);
+        }
+
+        let retry = Self::retry(Arc::clone(&self.inner), Arc::clone(&self.quarantine), event, quarantined);
+        let mut retries = self.retries.lock().await;
+        while retries.try_join_next().is_some() {}
+        retries.spawn(EventContext::current().scope(retry));
+        Ok(())
+    }
+
+    #[cfg(feature = "testing")]
+    async fn join_pending(&self) -> bool {
+        let mut retries = std::mem::take(&mut *self.retries.lock().await);
+        let pending = !retries.is_empty();
+        while retries.join_next().await.is_some() {}
+        pending
+    }
+}
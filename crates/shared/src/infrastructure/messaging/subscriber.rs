@@ -0,0 +1,23 @@
+use crate::infrastructure::messaging::{EventBus, EventHandler, quarantine::QuarantineStore, retry::RetryingHandler};
+use di::injectable;
+use std::sync::Arc;
+
+/// Subscribes the event handlers registered with the container to the `EventBus`
+#[injectable]
+pub struct EventSubscriber {
+    event_bus: Arc<dyn EventBus>,
+    event_listeners: Vec<Arc<dyn EventHandler>>,
+    quarantine: Arc<dyn QuarantineStore>,
+}
+
+impl EventSubscriber {
+    /// subscribes every listener under its retry policy, quarantining the events it keeps failing on
+    pub async fn subscribe_listeners(&self) {
+        for listener in &self.event_listeners {
+            let retrying = RetryingHandler::new(Arc::clone(listener), Arc::clone(&self.quarantine));
+            if let Err(e) = self.event_bus.subscribe(Arc::new(retrying)).await {
+                println!("failed to subscribe event: {} error: {:?}", listener.event_type(), e)
+            }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::infrastructure::messaging::outbox::OutboxRelay;
+use di::ServiceProvider;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// starts the messaging background tasks a context runs next to its router; abort the handles to stop them
+pub fn spawn_workers(provider: &ServiceProvider) -> Vec<JoinHandle<()>> {
+    let outbox_relay = provider.get_required::<OutboxRelay>();
+    vec![outbox_relay.spawn(outbox_poll_interval(), outbox_batch_size())]
+}
+
+/// wait between two runs relaying events a failed dispatch left in the outbox
+fn outbox_poll_interval() -> Duration {
+    Duration::from_secs(5)
+}
+
+/// outbox events relayed per run
+fn outbox_batch_size() -> u32 {
+    100
+}
@@ -1,5 +1,7 @@
 pub mod config;
 pub mod env;
+pub mod http;
 pub mod mailing;
 pub mod messaging;
+pub mod rate_limiting;
 pub mod types;
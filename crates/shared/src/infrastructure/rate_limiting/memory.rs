@@ -0,0 +1,90 @@
+use crate::infrastructure::{
+    rate_limiting::{RateLimitPolicy, RateLimiter},
+    types::{Result, error::Error},
+};
+use async_trait::async_trait;
+use di::injectable;
+use std::{collections::HashMap, time::Duration};
+use tokio::{sync::Mutex, time::Instant};
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+    policy: RateLimitPolicy,
+}
+
+impl Bucket {
+    fn tokens_at(&self, now: Instant) -> f64 {
+        let refilled = now.duration_since(self.refilled_at).as_secs_f64() / self.policy.refill_interval.as_secs_f64();
+        (self.tokens + refilled).min(f64::from(self.policy.capacity))
+    }
+
+    /// a full bucket behaves like a missing one and can be dropped
+    fn is_full_at(&self, now: Instant) -> bool {
+        self.tokens_at(now) >= f64::from(self.policy.capacity)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    swept_at: Option<Instant>,
+}
+
+impl Buckets {
+    /// drops the buckets that refilled completely, at most once per `sweep_interval`
+    fn sweep(&mut self, now: Instant) {
+        if self.swept_at.is_some_and(|swept_at| now.duration_since(swept_at) < sweep_interval()) {
+            return;
+        }
+        self.by_key.retain(|_, bucket| !bucket.is_full_at(now));
+        self.swept_at = Some(now);
+    }
+}
+
+/// Token buckets held in process memory; limits are per instance
+#[injectable(RateLimiter)]
+#[derive(Default)]
+pub struct InMemoryRateLimiter {
+    buckets: Mutex<Buckets>,
+}
+
+impl InMemoryRateLimiter {
+    /// Number of keys holding a bucket that is not full
+    #[cfg(feature = "testing")]
+    pub async fn tracked_keys(&self) -> usize {
+        self.buckets.lock().await.by_key.len()
+    }
+}
+
+#[async_trait]
+impl RateLimiter for InMemoryRateLimiter {
+    async fn acquire(&self, key: &str, policy: &RateLimitPolicy) -> Result<()> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().await;
+        buckets.sweep(now);
+        let bucket = buckets.by_key.entry(key.to_owned()).or_insert(Bucket {
+            tokens: f64::from(policy.capacity),
+            refilled_at: now,
+            policy: policy.clone(),
+        });
+
+        bucket.policy = policy.clone();
+        bucket.tokens = bucket.tokens_at(now);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+
+        let retry_after = policy.refill_interval.mul_f64(1.0 - bucket.tokens);
+        Err(Error::TooManyRequests { retry_after })
+    }
+}
+
+/// wait between two sweeps of the full buckets
+fn sweep_interval() -> Duration {
+    Duration::from_secs(60)
+}
@@ -0,0 +1,36 @@
+use crate::infrastructure::types::Result;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::Duration;
+
+pub mod memory;
+
+/// Token bucket shape: `capacity` requests in a burst, one more every `refill_seconds`
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitPolicy {
+    pub capacity: u32,
+    #[serde(rename = "refill_seconds", deserialize_with = "deserialize_seconds")]
+    pub refill_interval: Duration,
+}
+
+impl RateLimitPolicy {
+    pub fn new(capacity: u32, refill_interval: Duration) -> Self {
+        Self { capacity, refill_interval }
+    }
+}
+
+/// Trait for limiting how often a keyed action may happen.
+///
+/// Implementations backed by a shared store (e.g. redis) let several instances enforce one limit.
+#[async_trait]
+pub trait RateLimiter: Sync + Send {
+    /// Takes one token from the bucket of `key`, failing with `Error::TooManyRequests` when it is empty
+    async fn acquire(&self, key: &str, policy: &RateLimitPolicy) -> Result<()>;
+}
+
+fn deserialize_seconds<'de, D>(deserializer: D) -> std::result::Result<Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    u64::deserialize(deserializer).map(Duration::from_secs)
+}
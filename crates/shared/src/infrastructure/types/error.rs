@@ -1,15 +1,34 @@
+use std::time::Duration;
+
 #[derive(Debug, Clone)]
 pub enum DomainError {
     EntityNotFound,
     EntityAlreadyExist,
     EntityInvalid,
+    EntityConflict,
 }
 
 #[derive(Debug, Clone)]
 pub enum Error {
     DomainError(DomainError),
     ServiceError,
+    Unauthorized,
+    /// the caller exceeded a rate limit and may retry once `retry_after` elapsed
+    TooManyRequests {
+        retry_after: Duration,
+    },
+    /// a handler received an event of another type than the one it handles
+    EventMismatch {
+        expected: &'static str,
+        actual: String,
+    },
     AssertError(String),
+    /// the current state of the entity does not allow the operation, e.g. an invalid status transition
+    InvalidState(String),
+    /// the configuration cannot be used, e.g. credentials missing their password
+    ConfigError(String),
     BuilderError(String),
+    /// the request could not be extracted, e.g. a malformed body or path parameter
+    InvalidRequest(String),
     ParseError,
 }
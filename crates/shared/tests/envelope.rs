@@ -0,0 +1,108 @@
+use shared::{
+    domain::{
+        events::user::{OtpVerificationFailedEvent, UserStatusUpdatedEvent},
+        value_objects::{pid::Pid, user_status::UserStatus},
+    },
+    infrastructure::{
+        messaging::{
+            context::EventContext,
+            envelope::{EventEnvelope, EventRegistry},
+            event::AsAny,
+        },
+        types::{Result, error::Error},
+    },
+};
+
+fn registry() -> EventRegistry {
+    let mut registry = EventRegistry::default();
+    registry.register::<UserStatusUpdatedEvent>(1).register::<OtpVerificationFailedEvent>(1);
+    registry
+}
+
+#[test]
+fn decodes_typed_event_from_wire_format() -> Result<()> {
+    // Arrange
+    let user_id = Pid::new();
+    let event = UserStatusUpdatedEvent::new(&user_id, &UserStatus::Active, &UserStatus::Suspended);
+    let envelope = EventEnvelope::seal(&*event)?;
+    let json = serde_json::to_string(&envelope).unwrap();
+
+    // Act
+    let received: EventEnvelope = serde_json::from_str(&json).unwrap();
+    let decoded = registry().decode(&received)?;
+
+    // Assert
+    assert_eq!(received, envelope, "envelope must survive the wire");
+    assert_eq!(received.event_type, "UserStatusUpdated");
+    assert_eq!(received.schema_version, 1);
+    assert_eq!(received.aggregate_id, user_id);
+    let decoded = decoded.as_any().downcast_ref::<UserStatusUpdatedEvent>().unwrap();
+    assert_eq!(decoded.user_id, user_id);
+    assert_eq!(decoded.old_status, UserStatus::Active);
+    assert_eq!(decoded.new_status, UserStatus::Suspended);
+    assert_eq!(decoded.created_at, event.created_at);
+
+    Ok(())
+}
+
+#[test]
+fn seals_every_event_under_a_new_id() -> Result<()> {
+    // Arrange
+    let event = OtpVerificationFailedEvent::new(&Pid::new(), &Pid::new(), 3, true);
+
+    // Act
+    let first = EventEnvelope::seal(&*event)?;
+    let second = EventEnvelope::seal(&*event)?;
+
+    // Assert
+    assert_ne!(first.event_id, second.event_id);
+    assert_eq!(first.payload, second.payload);
+    assert_eq!(first.payload["failed_attempts"], 3);
+
+    Ok(())
+}
+
+#[test]
+fn rejects_unregistered_schema_version() -> Result<()> {
+    // Arrange
+    let event = UserStatusUpdatedEvent::new(&Pid::new(), &UserStatus::Active, &UserStatus::Suspended);
+    let mut envelope = EventEnvelope::seal(&*event)?;
+    envelope.schema_version = 2;
+
+    // Act
+    let result = registry().decode(&envelope);
+
+    // Assert
+    assert!(
+        matches!(result, Err(Error::AssertError(_))),
+        "unknown version must be rejected, got: {result:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn carries_event_context_across_the_wire() -> Result<()> {
+    // Arrange
+    let cause = Pid::new();
+    let context = EventContext {
+        correlation_id: Some("request-1".to_owned()),
+        causation_id: Some(cause.to_string()),
+    };
+    let event = context
+        .clone()
+        .scope(async { UserStatusUpdatedEvent::new(&Pid::new(), &UserStatus::Active, &UserStatus::Suspended) })
+        .await;
+
+    // Act
+    let envelope = EventEnvelope::seal(&*event)?;
+    let decoded = registry().decode(&envelope)?;
+
+    // Assert
+    assert_eq!(envelope.correlation_id.as_deref(), Some("request-1"));
+    assert_eq!(envelope.causation_id, Some(cause.to_string()));
+    assert_eq!(decoded.context(), &context, "decoded event must keep its context");
+    assert_eq!(EventContext::current(), EventContext::default(), "context must not leak out of its scope");
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use shared::domain::value_objects::{
+    asset::Asset,
+    mula::{Mula, MulaError},
+};
+use std::cmp::Ordering;
+
+fn eth() -> Asset {
+    Asset {
+        name: "Ether".to_owned(),
+        symbol: "ETH".to_owned(),
+        network: "ethereum".to_owned(),
+        address: None,
+        decimals: 18,
+        display_decimals: 4,
+    }
+}
+
+#[test]
+fn can_add_and_subtract_same_asset() -> Result<(), MulaError> {
+    // Arrange
+    let ten = Mula::new(10, &Asset::usdt());
+    let four = Mula::new(4, &Asset::usdt());
+
+    // Act
+    let sum = ten.checked_add(&four)?;
+    let difference = ten.checked_sub(&four)?;
+
+    // Assert
+    assert_eq!(sum, Mula::new(14, &Asset::usdt()));
+    assert_eq!(difference, Mula::new(6, &Asset::usdt()));
+    assert_eq!(ten.checked_cmp(&four)?, Ordering::Greater);
+    assert!(ten.checked_sub(&ten)?.is_zero());
+    assert!(Mula::zero(&Asset::usdt()).is_zero());
+
+    Ok(())
+}
+
+#[test]
+fn refuses_to_mix_assets() {
+    // Arrange
+    let usdt = Mula::new(10, &Asset::usdt());
+    let eth = Mula::new(10, &eth());
+
+    // Act & Assert
+    let mismatch = MulaError::AssetMismatch("USDT".to_owned(), "ETH".to_owned());
+    assert_eq!(usdt.checked_add(&eth), Err(mismatch.clone()));
+    assert_eq!(usdt.checked_sub(&eth), Err(mismatch.clone()));
+    assert_eq!(usdt.checked_cmp(&eth), Err(mismatch));
+}
+
+#[test]
+fn refuses_to_overflow_or_underflow() {
+    // Arrange
+    let max = Mula::new(u128::MAX, &Asset::usdt());
+    let one = Mula::new(1, &Asset::usdt());
+
+    // Act & Assert
+    assert_eq!(max.checked_add(&one), Err(MulaError::Overflow));
+    assert_eq!(one.checked_sub(&max), Err(MulaError::Underflow));
+}
+
+#[test]
+fn can_parse_human_amounts() -> Result<(), MulaError> {
+    // Act
+    let whole = Mula::parse("12", &eth())?;
+    let fractional = Mula::parse("0.000000000000000001", &eth())?;
+    let mixed = Mula::parse(" 1.5 ", &eth())?;
+
+    // Assert
+    assert_eq!(whole.get_amount(), 12_000_000_000_000_000_000);
+    assert_eq!(fractional.get_amount(), 1);
+    assert_eq!(mixed.get_amount(), 1_500_000_000_000_000_000);
+    assert_eq!(Mula::parse("0.0000000000000000001", &eth()), Err(MulaError::TooManyDecimals(18)));
+    assert_eq!(Mula::parse("-1", &eth()), Err(MulaError::InvalidAmount("-1".to_owned())));
+    assert_eq!(Mula::parse("1.2.3", &eth()), Err(MulaError::InvalidAmount("1.2.3".to_owned())));
+    assert_eq!(Mula::parse(".5", &eth()), Err(MulaError::InvalidAmount(".5".to_owned())));
+    assert_eq!(Mula::parse("340282366920938463464", &eth()), Err(MulaError::Overflow));
+
+    Ok(())
+}
+
+#[test]
+fn can_display_with_display_decimals() -> Result<(), MulaError> {
+    // Assert
+    assert_eq!(Mula::parse("1234.56789", &eth())?.to_string(), "1234.5678 ETH");
+    assert_eq!(Mula::new(1, &eth()).to_string(), "0.0000 ETH");
+    assert_eq!(Mula::parse("10", &Asset::usdt())?.to_string(), "10.0000000000 USDT");
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use async_trait::async_trait;
+use di::{Injectable, ServiceCollection, singleton_as_self};
+use shared::{
+    domain::{events::user::UserCreatedEvent, value_objects::pid::Pid},
+    infrastructure::{
+        messaging::{
+            EventBus, EventHandler,
+            envelope::EventRegistry,
+            event::DomainEvent,
+            memory::{DispatchMode, InMemoryEventBus},
+            outbox::{Outbox, OutboxRelay, RecordedEvent, memory::InMemoryOutbox},
+        },
+        types::Result,
+    },
+};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+/// Counts the `UserCreated` events it receives
+#[derive(Default)]
+struct CountingHandler {
+    handled: AtomicUsize,
+}
+
+#[async_trait]
+impl EventHandler for CountingHandler {
+    fn event_type(&self) -> &'static str {
+        "UserCreated"
+    }
+
+    async fn handle(&self, _event: Arc<dyn DomainEvent>) -> Result<()> {
+        self.handled.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+async fn setup() -> Result<(Arc<dyn Outbox>, Arc<CountingHandler>, Arc<OutboxRelay>)> {
+    let provider = ServiceCollection::new()
+        .add(InMemoryOutbox::singleton())
+        .add(InMemoryEventBus::singleton())
+        .add(OutboxRelay::singleton())
+        .add(singleton_as_self::<EventRegistry>().from(|_| {
+            let mut registry = EventRegistry::default();
+            registry.register::<UserCreatedEvent>(1);
+            Arc::new(registry)
+        }))
+        .build_provider()
+        .unwrap();
+    let handler = Arc::new(CountingHandler::default());
+    provider.get_required::<dyn EventBus>().subscribe(handler.clone()).await?;
+
+    Ok((provider.get_required::<dyn Outbox>(), handler, provider.get_required::<OutboxRelay>()))
+}
+
+#[tokio::test]
+async fn relays_pending_events_once() -> Result<()> {
+    // Arrange
+    let (outbox, handler, relay) = setup().await?;
+    let recorded = RecordedEvent::record(UserCreatedEvent::new(&Pid::new()))?;
+    outbox.append(&[recorded.envelope]).await?;
+
+    // Act
+    let first_run = relay.relay_pending(10).await?;
+    let second_run = relay.relay_pending(10).await?;
+
+    // Assert
+    assert_eq!(first_run, 1, "pending event must be relayed");
+    assert_eq!(second_run, 0, "relayed event must be marked published");
+    assert_eq!(handler.handled.load(Ordering::SeqCst), 1);
+    assert!(outbox.pending(10).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn drops_events_already_dispatched() -> Result<()> {
+    // Arrange
+    let (outbox, handler, relay) = setup().await?;
+    let recorded = RecordedEvent::record(UserCreatedEvent::new(&Pid::new()))?;
+    let envelope = recorded.envelope.clone();
+    outbox.append(std::slice::from_ref(&envelope)).await?;
+    relay.dispatch(vec![recorded]).await;
+    // a crash after publishing but before marking leaves the event pending
+    outbox.append(&[envelope]).await?;
+
+    // Act
+    let relayed = relay.relay_pending(10).await?;
+
+    // Assert
+    assert_eq!(relayed, 1, "event must be marked published");
+    assert_eq!(handler.handled.load(Ordering::SeqCst), 1, "event bus must drop the duplicate id");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn relays_events_behind_an_undecodable_one() -> Result<()> {
+    // Arrange
+    let (outbox, handler, relay) = setup().await?;
+    let mut poison = RecordedEvent::record(UserCreatedEvent::new(&Pid::new()))?.envelope;
+    poison.event_type = "Unknown".to_owned();
+    let good = RecordedEvent::record(UserCreatedEvent::new(&Pid::new()))?.envelope;
+    outbox.append(&[poison, good]).await?;
+
+    // Act
+    let first_run = relay.relay_pending(1).await?;
+    let second_run = relay.relay_pending(1).await?;
+
+    // Assert
+    assert_eq!(first_run, 0, "undecodable event must not be relayed");
+    assert_eq!(second_run, 1, "event behind the undecodable one must be relayed");
+    assert_eq!(handler.handled.load(Ordering::SeqCst), 1);
+    assert!(outbox.pending(10).await?.is_empty(), "undecodable event must be set aside");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn forgets_published_ids_after_the_dedup_window() -> Result<()> {
+    // Arrange
+    let event_bus = InMemoryEventBus::new(DispatchMode::Inline).with_dedup_window(Duration::ZERO);
+    let handler = Arc::new(CountingHandler::default());
+    event_bus.subscribe(handler.clone()).await?;
+    let event_id = Pid::new();
+
+    // Act
+    event_bus.publish_with_id(&event_id, UserCreatedEvent::new(&Pid::new())).await?;
+    event_bus.publish_with_id(&event_id, UserCreatedEvent::new(&Pid::new())).await?;
+
+    // Assert
+    assert_eq!(handler.handled.load(Ordering::SeqCst), 2, "ids outside the window must be forgotten");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn spawned_relay_publishes_pending_events() -> Result<()> {
+    // Arrange
+    let (outbox, handler, relay) = setup().await?;
+    let recorded = RecordedEvent::record(UserCreatedEvent::new(&Pid::new()))?;
+    outbox.append(&[recorded.envelope]).await?;
+
+    // Act
+    let task = relay.spawn(Duration::from_millis(10), 10);
+    let relayed = tokio::time::timeout(Duration::from_secs(5), async {
+        while handler.handled.load(Ordering::SeqCst) == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    task.abort();
+
+    // Assert
+    assert!(relayed.is_ok(), "pending event must be relayed by the spawned task");
+    assert!(outbox.pending(10).await?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use async_trait::async_trait;
+use di::{Injectable, ServiceCollection, ServiceProvider, singleton, singleton_as_self};
+use shared::{
+    domain::{events::user::UserCreatedEvent, value_objects::pid::Pid},
+    infrastructure::{
+        messaging::{
+            EventBus, EventHandler,
+            context::EventContext,
+            envelope::EventRegistry,
+            event::DomainEvent,
+            memory::InMemoryEventBus,
+            quarantine::{QuarantineReplayer, QuarantineStore, memory::InMemoryQuarantineStore},
+            retry::{RetryPolicy, RetryingHandler},
+        },
+        types::{Result, error::Error},
+    },
+};
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+/// Fails its first `failures` invocations, recording the context of the ones that succeed
+struct FlakyHandler {
+    failures: AtomicU32,
+    backoff_millis: u64,
+    invocations: AtomicU32,
+    handled: Mutex<Vec<EventContext>>,
+}
+
+impl FlakyHandler {
+    fn new(failures: u32) -> Arc<Self> {
+        Self::with_backoff(failures, 1)
+    }
+
+    fn with_backoff(failures: u32, backoff_millis: u64) -> Arc<Self> {
+        Arc::new(Self {
+            failures: AtomicU32::new(failures),
+            backoff_millis,
+            invocations: AtomicU32::new(0),
+            handled: Mutex::new(vec![]),
+        })
+    }
+
+    fn fix(&self) {
+        self.failures.store(0, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl EventHandler for FlakyHandler {
+    fn event_type(&self) -> &'static str {
+        "UserCreated"
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_backoff_millis: self.backoff_millis,
+            max_backoff_millis: self.backoff_millis * 5,
+        }
+    }
+
+    async fn handle(&self, _event: Arc<dyn DomainEvent>) -> Result<()> {
+        self.invocations.fetch_add(1, Ordering::SeqCst);
+        let failing = self
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| left.checked_sub(1))
+            .is_ok();
+        if failing {
+            return Err(Error::ServiceError);
+        }
+
+        self.handled.lock().unwrap().push(EventContext::current());
+        Ok(())
+    }
+}
+
+async fn setup(handler: Arc<FlakyHandler>) -> Result<ServiceProvider> {
+    let listener = handler.clone();
+    let provider = ServiceCollection::new()
+        .add(InMemoryEventBus::singleton())
+        .add(InMemoryQuarantineStore::singleton())
+        .add(QuarantineReplayer::singleton())
+        .add(singleton::<dyn EventHandler, FlakyHandler>().from(move |_| listener.clone()))
+        .add(singleton_as_self::<EventRegistry>().from(|_| {
+            let mut registry = EventRegistry::default();
+            registry.register::<UserCreatedEvent>(1);
+            Arc::new(registry)
+        }))
+        .build_provider()
+        .unwrap();
+    let retrying = RetryingHandler::new(handler, provider.get_required::<dyn QuarantineStore>());
+    provider.get_required::<dyn EventBus>().subscribe(Arc::new(retrying)).await?;
+
+    Ok(provider)
+}
+
+#[tokio::test]
+async fn retries_handler_until_it_succeeds() -> Result<()> {
+    // Arrange
+    let handler = FlakyHandler::new(2);
+    let provider = setup(handler.clone()).await?;
+    let event_bus = provider.get_required::<dyn EventBus>();
+
+    // Act
+    event_bus.publish(UserCreatedEvent::new(&Pid::new())).await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "retried handler must not fail: {failures:?}");
+    assert_eq!(handler.invocations.load(Ordering::SeqCst), 3);
+    assert_eq!(handler.handled.lock().unwrap().len(), 1);
+    assert!(provider.get_required::<dyn QuarantineStore>().list(10).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn publishing_does_not_wait_for_retries() -> Result<()> {
+    // Arrange
+    let handler = FlakyHandler::with_backoff(u32::MAX, 60_000);
+    let provider = setup(handler.clone()).await?;
+    let event_bus = provider.get_required::<dyn EventBus>();
+
+    // Act
+    let published = tokio::time::timeout(Duration::from_secs(5), event_bus.publish(UserCreatedEvent::new(&Pid::new()))).await;
+
+    // Assert
+    assert!(matches!(published, Ok(Ok(()))), "publish must return before the backoff elapses");
+    assert_eq!(handler.invocations.load(Ordering::SeqCst), 1, "retries must run in the background");
+    let quarantined = provider.get_required::<dyn QuarantineStore>().list(10).await?;
+    assert_eq!(quarantined.len(), 1, "failed event must be quarantined while it is retried");
+    assert_eq!(quarantined[0].attempts, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn quarantines_event_once_retries_are_exhausted() -> Result<()> {
+    // Arrange
+    let handler = FlakyHandler::new(u32::MAX);
+    let provider = setup(handler.clone()).await?;
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let event_id = Pid::new();
+
+    // Act
+    event_bus.publish_with_id(&event_id, UserCreatedEvent::new(&Pid::new())).await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "quarantined event must count as handled: {failures:?}");
+    assert_eq!(handler.invocations.load(Ordering::SeqCst), 3);
+    let quarantined = provider.get_required::<dyn QuarantineStore>().list(10).await?;
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].handler, handler.name());
+    assert_eq!(quarantined[0].envelope.event_id, event_id, "quarantine must keep the published id");
+    assert_eq!(quarantined[0].attempts, 3);
+    assert_eq!(quarantined[0].last_error, format!("{:?}", Error::ServiceError));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn replays_quarantined_event_once_handler_is_fixed() -> Result<()> {
+    // Arrange
+    let handler = FlakyHandler::new(u32::MAX);
+    let provider = setup(handler.clone()).await?;
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let quarantine = provider.get_required::<dyn QuarantineStore>();
+    let replayer = provider.get_required::<QuarantineReplayer>();
+    let event_id = Pid::new();
+    event_bus.publish_with_id(&event_id, UserCreatedEvent::new(&Pid::new())).await?;
+    event_bus.wait_until_idle().await;
+    let quarantined = quarantine.list(10).await?.remove(0);
+
+    // Act
+    let broken = replayer.replay(&quarantined.pid).await;
+    handler.fix();
+    let fixed = replayer.replay_all(10).await?;
+
+    // Assert
+    assert!(broken.is_err(), "replay must fail while the handler is broken");
+    assert_eq!(fixed.replayed, 1);
+    assert_eq!(fixed.failed, 0);
+    assert!(quarantine.list(10).await?.is_empty(), "replayed event must leave the quarantine");
+    let handled = handler.handled.lock().unwrap();
+    assert_eq!(handled.len(), 1);
+    assert_eq!(
+        handled[0].causation_id,
+        Some(event_id.to_string()),
+        "replay must run in the context the event caused"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn keeps_event_quarantined_while_replays_fail() -> Result<()> {
+    // Arrange
+    let handler = FlakyHandler::new(u32::MAX);
+    let provider = setup(handler.clone()).await?;
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let quarantine = provider.get_required::<dyn QuarantineStore>();
+    event_bus.publish(UserCreatedEvent::new(&Pid::new())).await?;
+    event_bus.wait_until_idle().await;
+
+    // Act
+    let run = provider.get_required::<QuarantineReplayer>().replay_all(10).await?;
+
+    // Assert
+    assert_eq!(run.replayed, 0);
+    assert_eq!(run.failed, 1);
+    let quarantined = quarantine.list(10).await?;
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].attempts, 4, "failed replay must count as an attempt");
+
+    Ok(())
+}
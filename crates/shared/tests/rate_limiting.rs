@@ -0,0 +1,61 @@
+use shared::infrastructure::{
+    rate_limiting::{RateLimitPolicy, RateLimiter, memory::InMemoryRateLimiter},
+    types::{Result, error::Error},
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn bucket_allows_burst_then_rejects_with_retry_after() -> Result<()> {
+    // Arrange
+    let rate_limiter = InMemoryRateLimiter::default();
+    let policy = RateLimitPolicy::new(2, Duration::from_secs(60));
+
+    // Act
+    rate_limiter.acquire("otp:tom", &policy).await?;
+    rate_limiter.acquire("otp:tom", &policy).await?;
+    let result = rate_limiter.acquire("otp:tom", &policy).await;
+
+    // Assert
+    let Err(Error::TooManyRequests { retry_after }) = result else {
+        panic!("third request must be limited, got: {result:?}");
+    };
+    assert!(retry_after <= Duration::from_secs(60), "retry after must not exceed the refill interval");
+    assert!(retry_after > Duration::from_secs(59), "bucket must be empty");
+    rate_limiter.acquire("otp:jerry", &policy).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bucket_refills_over_time() -> Result<()> {
+    // Arrange
+    let rate_limiter = InMemoryRateLimiter::default();
+    let policy = RateLimitPolicy::new(1, Duration::from_millis(50));
+    rate_limiter.acquire("otp:tom", &policy).await?;
+    assert!(rate_limiter.acquire("otp:tom", &policy).await.is_err(), "bucket must be empty");
+
+    // Act
+    tokio::time::sleep(Duration::from_millis(60)).await;
+
+    // Assert
+    rate_limiter.acquire("otp:tom", &policy).await
+}
+
+#[tokio::test(start_paused = true)]
+async fn refilled_buckets_are_dropped() -> Result<()> {
+    // Arrange
+    let rate_limiter = InMemoryRateLimiter::default();
+    let policy = RateLimitPolicy::new(2, Duration::from_secs(10));
+    rate_limiter.acquire("otp:tom", &policy).await?;
+    rate_limiter.acquire("otp:jerry", &policy).await?;
+    rate_limiter.acquire("otp:jerry", &policy).await?;
+
+    // Act
+    tokio::time::advance(Duration::from_secs(90)).await;
+    rate_limiter.acquire("otp:spike", &policy).await?;
+
+    // Assert
+    assert_eq!(rate_limiter.tracked_keys().await, 1, "only the bucket just used must be kept");
+
+    Ok(())
+}
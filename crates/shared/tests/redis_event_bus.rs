@@ -0,0 +1,294 @@
+use async_trait::async_trait;
+use redis::{AsyncCommands, IntoConnectionInfo};
+use shared::{
+    domain::{
+        events::user::UserStatusUpdatedEvent,
+        value_objects::{pid::Pid, user_status::UserStatus},
+    },
+    infrastructure::{
+        messaging::{
+            EventBus, TypedEventHandler,
+            envelope::EventRegistry,
+            redis_streams::{RedisStreamsConfig, RedisStreamsEventBus},
+        },
+        types::{Result, error::Error},
+    },
+};
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::Mutex,
+    task::JoinSet,
+};
+
+/// Records the users of the events it handled, rejecting the first `failures` deliveries
+struct RecordingHandler {
+    name: String,
+    failures: Mutex<usize>,
+    handled: Mutex<Vec<Pid>>,
+}
+
+impl RecordingHandler {
+    fn new(name: &str, failures: usize) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_owned(),
+            failures: Mutex::new(failures),
+            handled: Default::default(),
+        })
+    }
+
+    async fn handled(&self) -> Vec<Pid> {
+        self.handled.lock().await.clone()
+    }
+
+    /// waits up to five seconds for `count` handled events
+    async fn wait_for(&self, count: usize) -> Vec<Pid> {
+        let _ = tokio::time::timeout(Duration::from_secs(5), async {
+            while self.handled.lock().await.len() < count {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await;
+        self.handled().await
+    }
+}
+
+#[async_trait]
+impl TypedEventHandler for RecordingHandler {
+    type Event = UserStatusUpdatedEvent;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn handle(&self, event: &UserStatusUpdatedEvent) -> Result<()> {
+        let mut failures = self.failures.lock().await;
+        if *failures > 0 {
+            *failures -= 1;
+            return Err(Error::ServiceError);
+        }
+
+        self.handled.lock().await.push(event.user_id.clone());
+        Ok(())
+    }
+}
+
+/// Forwards connections to the broker at `REDIS_URL` and drops all of them on demand, the way a broker restart does
+struct DroppingProxy {
+    url: String,
+    connections: Arc<Mutex<JoinSet<()>>>,
+}
+
+impl DroppingProxy {
+    async fn start(broker_url: &str) -> Result<Self> {
+        let info = broker_url.into_connection_info().map_err(|e| Error::AssertError(e.to_string()))?;
+        let broker = info.addr.to_string();
+        let listener = TcpListener::bind("127.0.0.1:0").await.map_err(|e| Error::AssertError(e.to_string()))?;
+        let url = format!(
+            "redis://{}/{}",
+            listener.local_addr().map_err(|e| Error::AssertError(e.to_string()))?,
+            info.redis.db
+        );
+
+        let connections: Arc<Mutex<JoinSet<()>>> = Default::default();
+        let forwarded = Arc::clone(&connections);
+        tokio::spawn(async move {
+            while let Ok((mut inbound, _)) = listener.accept().await {
+                let Ok(mut outbound) = TcpStream::connect(&broker).await else {
+                    continue;
+                };
+                forwarded.lock().await.spawn(async move {
+                    let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                });
+            }
+        });
+
+        Ok(Self { url, connections })
+    }
+
+    async fn drop_connections(&self) {
+        self.connections.lock().await.abort_all();
+    }
+}
+
+/// Isolated broker namespace of one test on the broker at `REDIS_URL`.
+///
+/// The redis tests are ignored by default; start a throwaway broker (Redis 7 or later) with:
+/// `redis-server --port 6379 --save ''`
+/// and run them with `REDIS_URL=redis://127.0.0.1:6379 cargo test -- --ignored`.
+fn config() -> RedisStreamsConfig {
+    let url = std::env::var("REDIS_URL").expect("REDIS_URL must point to a redis broker");
+
+    RedisStreamsConfig {
+        url,
+        stream_prefix: format!("test-{}", Pid::new().to_string()),
+        consumer: None,
+        batch_size: 16,
+        block_millis: 100,
+        claim_idle_millis: 200,
+        dedup_ttl_seconds: 60,
+    }
+}
+
+async fn connect(config: &RedisStreamsConfig) -> Result<RedisStreamsEventBus> {
+    let mut registry = EventRegistry::default();
+    registry.register::<UserStatusUpdatedEvent>(1);
+    RedisStreamsEventBus::connect(config, Arc::new(registry)).await
+}
+
+fn suspended(user_id: &Pid) -> Box<UserStatusUpdatedEvent> {
+    UserStatusUpdatedEvent::new(user_id, &UserStatus::Active, &UserStatus::Suspended)
+}
+
+#[tokio::test]
+#[ignore = "needs a redis broker at REDIS_URL"]
+async fn delivers_event_to_every_consumer_group() -> Result<()> {
+    // Arrange
+    let config = config();
+    let event_bus = connect(&config).await?;
+    let stash = RecordingHandler::new("stash", 0);
+    let audit = RecordingHandler::new("audit", 0);
+    event_bus.subscribe(stash.clone()).await?;
+    event_bus.subscribe(audit.clone()).await?;
+    let user_id = Pid::new();
+
+    // Act
+    event_bus.publish(suspended(&user_id)).await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "handlers must not fail: {failures:?}");
+    assert_eq!(stash.handled().await, vec![user_id.clone()]);
+    assert_eq!(audit.handled().await, vec![user_id]);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a redis broker at REDIS_URL"]
+async fn redelivers_event_the_handler_rejected() -> Result<()> {
+    // Arrange
+    let config = config();
+    let event_bus = connect(&config).await?;
+    let handler = RecordingHandler::new("stash", 1);
+    event_bus.subscribe(handler.clone()).await?;
+    let user_id = Pid::new();
+
+    // Act
+    event_bus.publish(suspended(&user_id)).await?;
+    let failures = event_bus.wait_until_idle().await;
+    let handled = handler.wait_for(1).await;
+
+    // Assert
+    assert_eq!(failures.len(), 1, "first delivery must be rejected");
+    assert_eq!(failures[0].aggregate_id, user_id.to_string());
+    assert_eq!(handled, vec![user_id], "rejected event must be delivered again");
+    assert!(event_bus.wait_until_idle().await.is_empty(), "redelivered event must be acked");
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a redis broker at REDIS_URL"]
+async fn resumes_durable_group_after_restart() -> Result<()> {
+    // Arrange
+    let config = config();
+    let stopped = connect(&config).await?;
+    stopped.subscribe(RecordingHandler::new("stash", 0)).await?;
+    drop(stopped);
+    let publisher = connect(&config).await?;
+    let user_id = Pid::new();
+    publisher.publish(suspended(&user_id)).await?;
+
+    // Act
+    let restarted = connect(&config).await?;
+    let handler = RecordingHandler::new("stash", 0);
+    restarted.subscribe(handler.clone()).await?;
+    let failures = restarted.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "handlers must not fail: {failures:?}");
+    assert_eq!(handler.handled().await, vec![user_id], "event published while stopped must be delivered");
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a redis broker at REDIS_URL"]
+async fn drops_duplicate_event_ids() -> Result<()> {
+    // Arrange
+    let config = config();
+    let event_bus = connect(&config).await?;
+    let handler = RecordingHandler::new("stash", 0);
+    event_bus.subscribe(handler.clone()).await?;
+    let event_id = Pid::new();
+    let user_id = Pid::new();
+
+    // Act
+    event_bus.publish_with_id(&event_id, suspended(&user_id)).await?;
+    event_bus.publish_with_id(&event_id, suspended(&user_id)).await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "handlers must not fail: {failures:?}");
+    assert_eq!(handler.handled().await, vec![user_id], "duplicate must be dropped");
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a redis broker at REDIS_URL"]
+async fn dead_letters_undecodable_entries() -> Result<()> {
+    // Arrange
+    let config = config();
+    let event_bus = connect(&config).await?;
+    let handler = RecordingHandler::new("stash", 0);
+    event_bus.subscribe(handler.clone()).await?;
+    let mut connection = redis::Client::open(config.url.as_str())
+        .unwrap()
+        .get_multiplexed_async_connection()
+        .await
+        .unwrap();
+    let stream = format!("{}:UserStatusUpdated", config.stream_prefix);
+
+    // Act
+    let _: String = connection.xadd(&stream, "*", &[("envelope", "not an envelope")]).await.unwrap();
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert_eq!(failures.len(), 1, "undecodable entry must be reported");
+    let pending: redis::streams::StreamPendingReply = connection.xpending(&stream, "stash").await.unwrap();
+    assert_eq!(pending.count(), 0, "undecodable entry must be acked");
+    let dead_letters: usize = connection.xlen(format!("{}:dead-letter", config.stream_prefix)).await.unwrap();
+    assert_eq!(dead_letters, 1, "undecodable entry must be kept in the dead letter stream");
+    assert!(handler.handled().await.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a redis broker at REDIS_URL"]
+async fn consumes_again_after_broker_dropped_connections() -> Result<()> {
+    // Arrange
+    let mut config = config();
+    let proxy = DroppingProxy::start(&config.url).await?;
+    config.url = proxy.url.clone();
+    let event_bus = connect(&config).await?;
+    let handler = RecordingHandler::new("stash", 0);
+    event_bus.subscribe(handler.clone()).await?;
+    let before = Pid::new();
+    event_bus.publish(suspended(&before)).await?;
+    handler.wait_for(1).await;
+
+    // Act
+    proxy.drop_connections().await;
+    let after = Pid::new();
+    let publisher = connect(&config).await?;
+    publisher.publish(suspended(&after)).await?;
+    let handled = handler.wait_for(2).await;
+
+    // Assert
+    assert_eq!(handled, vec![before, after], "consumer must reconnect after its connection dropped");
+
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use di::ServiceCollection;
+use shared::infrastructure::{
+    mailing::{
+        Email, Mailer,
+        smtp::{SmtpConfig, SmtpMailer, SmtpSecurity, add_mailer},
+    },
+    types::{Result, error::Error},
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    sync::Mutex,
+};
+
+/// Envelope and raw message accepted by the sink
+#[derive(Debug, Clone, Default)]
+struct Received {
+    from: String,
+    recipients: Vec<String>,
+    data: String,
+}
+
+/// Minimal local SMTP sink: accepts every message without TLS or authentication and keeps it in memory
+async fn start_sink() -> (u16, Arc<Mutex<Vec<Received>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let inbox = received.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve(stream, inbox.clone()));
+        }
+    });
+
+    (port, received)
+}
+
+async fn serve(stream: TcpStream, inbox: Arc<Mutex<Vec<Received>>>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let mut current = Received::default();
+    writer.write_all(b"220 sink ESMTP\r\n").await.unwrap();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let command = line.to_uppercase();
+        let reply: &[u8] = if command.starts_with("EHLO") || command.starts_with("HELO") {
+            b"250 sink\r\n"
+        } else if command.starts_with("MAIL FROM:") {
+            current.from = line[10..].trim().to_owned();
+            b"250 OK\r\n"
+        } else if command.starts_with("RCPT TO:") {
+            current.recipients.push(line[8..].trim().to_owned());
+            b"250 OK\r\n"
+        } else if command == "DATA" {
+            writer.write_all(b"354 end data with <CR><LF>.<CR><LF>\r\n").await.unwrap();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line == "." {
+                    break;
+                }
+                current.data.push_str(&line);
+                current.data.push('\n');
+            }
+            inbox.lock().await.push(std::mem::take(&mut current));
+            b"250 queued\r\n"
+        } else if command == "QUIT" {
+            writer.write_all(b"221 bye\r\n").await.unwrap();
+            return;
+        } else {
+            b"250 OK\r\n"
+        };
+        writer.write_all(reply).await.unwrap();
+    }
+}
+
+fn config(port: u16) -> SmtpConfig {
+    SmtpConfig {
+        host: "127.0.0.1".to_owned(),
+        port: Some(port),
+        security: SmtpSecurity::None,
+        username: None,
+        password: None,
+        timeout_seconds: 1,
+    }
+}
+
+#[tokio::test]
+async fn delivers_multipart_mail_to_all_recipients() -> Result<()> {
+    // Arrange
+    let (port, received) = start_sink().await;
+    let mailer = SmtpMailer::new(&config(port))?;
+    let email = Email {
+        from: "Stash <noreply@stash.it>".to_owned(),
+        to: "tom@stash.it".to_owned(),
+        reply_to: Some("support@stash.it".to_owned()),
+        subject: "Hello".to_owned(),
+        text: "plain body".to_owned(),
+        html: "<p>html body</p>".to_owned(),
+        bcc: Some("audit@stash.it".to_owned()),
+        cc: Some("jerry@stash.it, spike@stash.it".to_owned()),
+    };
+
+    // Act
+    mailer.mail(&email).await?;
+
+    // Assert
+    let received = received.lock().await;
+    assert_eq!(received.len(), 1, "sink must have received one message");
+    let message = &received[0];
+    assert_eq!(message.from, "<noreply@stash.it>");
+    assert_eq!(
+        message.recipients,
+        vec!["<tom@stash.it>", "<jerry@stash.it>", "<spike@stash.it>", "<audit@stash.it>"],
+        "envelope must contain to, cc and bcc recipients"
+    );
+    assert!(message.data.contains("Cc: jerry@stash.it, spike@stash.it"));
+    assert!(message.data.contains("Reply-To: support@stash.it"));
+    assert!(!message.data.contains("Bcc:"), "bcc must not be visible to recipients");
+    assert!(message.data.contains("multipart/alternative"));
+    assert!(message.data.contains("Content-Type: text/plain"));
+    assert!(message.data.contains("plain body"));
+    assert!(message.data.contains("Content-Type: text/html"));
+    assert!(message.data.contains("<p>html body</p>"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_invalid_mailbox() -> Result<()> {
+    // Arrange
+    let (port, received) = start_sink().await;
+    let mailer = SmtpMailer::new(&config(port))?;
+    let email = Email {
+        from: "noreply@stash.it".to_owned(),
+        to: "not an address".to_owned(),
+        ..Default::default()
+    };
+
+    // Act
+    let result = mailer.mail(&email).await;
+
+    // Assert
+    assert!(
+        matches!(result, Err(Error::AssertError(_))),
+        "invalid recipient must be rejected, got: {result:?}"
+    );
+    assert!(received.lock().await.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn gives_up_on_unresponsive_server() -> Result<()> {
+    // Arrange
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        // accept without ever greeting the client
+        let mut connections = vec![];
+        while let Ok((stream, _)) = listener.accept().await {
+            connections.push(stream);
+        }
+    });
+    let mailer = SmtpMailer::new(&config(port))?;
+    let email = Email {
+        from: "noreply@stash.it".to_owned(),
+        to: "tom@stash.it".to_owned(),
+        ..Default::default()
+    };
+
+    // Act
+    let started = Instant::now();
+    let result = mailer.mail(&email).await;
+
+    // Assert
+    assert!(matches!(result, Err(Error::ServiceError)), "delivery must fail, got: {result:?}");
+    assert!(started.elapsed() < Duration::from_secs(5), "configured timeout must apply");
+
+    Ok(())
+}
+
+#[test]
+fn rejects_username_without_password() {
+    // Arrange
+    let config = SmtpConfig {
+        username: Some("stash".to_owned()),
+        ..config(25)
+    };
+
+    // Act
+    let result = SmtpMailer::new(&config);
+
+    // Assert
+    assert!(matches!(result, Err(Error::ConfigError(_))), "incomplete credentials must be rejected");
+}
+
+#[tokio::test]
+async fn registers_smtp_mailer_when_relay_is_configured() -> Result<()> {
+    // Arrange
+    let (port, received) = start_sink().await;
+    let mut services = ServiceCollection::new();
+    add_mailer(&mut services, Some(&config(port)))?;
+    let mailer = services.build_provider().unwrap().get_required::<dyn Mailer>();
+    let email = Email {
+        from: "noreply@stash.it".to_owned(),
+        to: "tom@stash.it".to_owned(),
+        ..Default::default()
+    };
+
+    // Act
+    mailer.mail(&email).await?;
+
+    // Assert
+    assert_eq!(received.lock().await.len(), 1, "mail must be delivered through the relay");
+
+    Ok(())
+}
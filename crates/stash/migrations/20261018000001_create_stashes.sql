@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS stashes (
+    id          BIGSERIAL PRIMARY KEY,
+    pid         UUID NOT NULL UNIQUE,
+    user_id     UUID NOT NULL,
+    name        TEXT NOT NULL,
+    status      TEXT NOT NULL,
+    tags        TEXT[] NOT NULL DEFAULT '{}',
+    metadata    JSONB NOT NULL DEFAULT '{}',
+    created_at  TIMESTAMPTZ NOT NULL,
+    updated_at  TIMESTAMPTZ NOT NULL,
+    UNIQUE (user_id, name)
+);
+
+CREATE INDEX IF NOT EXISTS stashes_user_id_idx ON stashes (user_id);
+
+CREATE TABLE IF NOT EXISTS stash_balances (
+    stash_id                UUID NOT NULL REFERENCES stashes (pid) ON DELETE CASCADE,
+    amount                  NUMERIC(39, 0) NOT NULL,
+    asset_name              TEXT NOT NULL,
+    asset_symbol            TEXT NOT NULL,
+    asset_network           TEXT NOT NULL,
+    asset_address           TEXT,
+    asset_decimals          SMALLINT NOT NULL,
+    asset_display_decimals  SMALLINT NOT NULL,
+    PRIMARY KEY (stash_id, asset_symbol, asset_network)
+);
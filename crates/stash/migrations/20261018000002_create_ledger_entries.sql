@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS ledger_entries (
+    id                      BIGSERIAL PRIMARY KEY,
+    pid                     UUID NOT NULL UNIQUE,
+    stash_id                UUID NOT NULL REFERENCES stashes (pid),
+    entry_type              TEXT NOT NULL,
+    amount                  NUMERIC(39, 0) NOT NULL,
+    asset_name              TEXT NOT NULL,
+    asset_symbol            TEXT NOT NULL,
+    asset_network           TEXT NOT NULL,
+    asset_address           TEXT,
+    asset_decimals          SMALLINT NOT NULL,
+    asset_display_decimals  SMALLINT NOT NULL,
+    upstream_ref_id         UUID NOT NULL,
+    metadata                JSONB NOT NULL DEFAULT '{}',
+    created_at              TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS ledger_entries_stash_id_idx ON ledger_entries (stash_id);
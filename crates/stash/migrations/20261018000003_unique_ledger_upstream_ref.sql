@@ -0,0 +1 @@
+CREATE UNIQUE INDEX IF NOT EXISTS ledger_entries_upstream_ref_idx ON ledger_entries (stash_id, upstream_ref_id, entry_type);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS event_outbox (
+    id           BIGSERIAL PRIMARY KEY,
+    pid          UUID NOT NULL UNIQUE,
+    event_type   TEXT NOT NULL,
+    aggregate_id UUID NOT NULL,
+    payload      JSONB NOT NULL,
+    occurred_at  TIMESTAMPTZ NOT NULL,
+    published_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS event_outbox_pending_idx ON event_outbox (id) WHERE published_at IS NULL;
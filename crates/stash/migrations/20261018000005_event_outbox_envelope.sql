@@ -0,0 +1,3 @@
+ALTER TABLE event_outbox ADD COLUMN IF NOT EXISTS schema_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE event_outbox ADD COLUMN IF NOT EXISTS correlation_id TEXT;
+ALTER TABLE event_outbox ADD COLUMN IF NOT EXISTS causation_id TEXT;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS event_quarantine (
+    id             BIGSERIAL PRIMARY KEY,
+    pid            UUID NOT NULL UNIQUE,
+    handler        TEXT NOT NULL,
+    envelope       JSONB NOT NULL,
+    attempts       INTEGER NOT NULL,
+    last_error     TEXT NOT NULL,
+    quarantined_at TIMESTAMPTZ NOT NULL
+);
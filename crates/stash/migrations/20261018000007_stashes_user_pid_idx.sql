@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS stashes_user_id_pid_idx ON stashes (user_id, pid);
@@ -0,0 +1,5 @@
+ALTER TABLE event_outbox ADD COLUMN IF NOT EXISTS failed_at TIMESTAMPTZ;
+ALTER TABLE event_outbox ADD COLUMN IF NOT EXISTS last_error TEXT;
+
+DROP INDEX IF EXISTS event_outbox_pending_idx;
+CREATE INDEX IF NOT EXISTS event_outbox_pending_idx ON event_outbox (id) WHERE published_at IS NULL AND failed_at IS NULL;
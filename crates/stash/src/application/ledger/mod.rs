@@ -1,15 +1,18 @@
 use crate::{
     application::ledger::command::{ReadLedgerEntriesCommand, ReadLedgerEntryCommand, WriteLedgerEntryCommand},
     domain::{
-        events::LedgerEntryCreatedEvent,
+        events::{LedgerEntryCreatedEvent, StashBalanceUpdatedEvent},
         ledger_entry::entry::LedgerEntry,
-        repositories::{FindManyLedgerQueryBuilder, LedgerRepository},
+        repositories::{FindManyLedgerQueryBuilder, LedgerRepository, StashRepository},
     },
 };
 use di::injectable;
 use shared::infrastructure::{
-    messaging::EventBus,
-    types::{Result, error::Error},
+    messaging::outbox::{OutboxRelay, RecordedEvent},
+    types::{
+        Result,
+        error::{DomainError, Error},
+    },
 };
 use std::sync::Arc;
 
@@ -18,22 +21,75 @@ pub mod command;
 #[injectable]
 pub struct LedgerService {
     ledger_repo: Arc<dyn LedgerRepository>,
-    event_bus: Arc<dyn EventBus>,
+    stash_repo: Arc<dyn StashRepository>,
+    outbox_relay: Arc<OutboxRelay>,
 }
 
 impl LedgerService {
+    /// writes a ledger entry and moves the stash balance by its amount; the ledger is the only way balances change.
+    /// Writes are idempotent per (stash, upstream ref, entry type): a replay returns the entry already written.
+    /// A stash saved concurrently is reloaded and the entry applied again, so no balance change is lost.
     pub async fn write_ledger_entry(&self, command: WriteLedgerEntryCommand) -> Result<LedgerEntry> {
+        for _ in 0..Self::max_write_attempts() {
+            if let Some(entry) = self.try_write_ledger_entry(&command).await? {
+                return Ok(entry);
+            }
+        }
+
+        Err(Error::DomainError(DomainError::EntityConflict))
+    }
+
+    /// `None` when the stash was saved concurrently since it was loaded
+    async fn try_write_ledger_entry(&self, command: &WriteLedgerEntryCommand) -> Result<Option<LedgerEntry>> {
+        if let Some(entry) = self.find_replayed_entry(command).await? {
+            return Ok(Some(entry));
+        }
+
+        let mut stash = self
+            .stash_repo
+            .find_by_pid(&command.stash_id)
+            .await?
+            .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+
         let entry = LedgerEntry::new(&command.stash_id, &command.entry_type, &command.amount, &command.upstream_ref_id);
+        let new_balance = stash.apply_ledger_entry(&entry)?;
+        let events = vec![
+            RecordedEvent::record(LedgerEntryCreatedEvent::new(entry.get_stash_id(), entry.get_pid()))?,
+            RecordedEvent::record(StashBalanceUpdatedEvent::new(stash.get_pid(), &new_balance))?,
+        ];
 
-        self.ledger_repo.save(&entry).await?;
-        let ledger_entry_created_event = LedgerEntryCreatedEvent::new(entry.get_stash_id(), entry.get_pid());
-        self.event_bus.publish(ledger_entry_created_event).await?;
-        Ok(entry)
+        match self.stash_repo.save_with_ledger_entry(&stash, &entry, &events).await {
+            Ok(()) => {}
+            Err(Error::DomainError(DomainError::EntityConflict)) => return Ok(None),
+            // a concurrent write of the same movement won the race
+            Err(e) => {
+                return match (e, self.find_replayed_entry(command).await?) {
+                    (Error::DomainError(DomainError::EntityAlreadyExist), Some(entry)) => Ok(Some(entry)),
+                    (e, _) => Err(e),
+                };
+            }
+        }
+        self.outbox_relay.dispatch(events).await;
+        Ok(Some(entry))
+    }
+
+    /// returns the entry previously written for the command's upstream movement, failing when its amount differs
+    async fn find_replayed_entry(&self, command: &WriteLedgerEntryCommand) -> Result<Option<LedgerEntry>> {
+        let entry = self
+            .ledger_repo
+            .find_by_upstream_ref(&command.stash_id, &command.upstream_ref_id, &command.entry_type)
+            .await?;
+
+        match entry {
+            Some(entry) if entry.get_amount() != &command.amount => Err(Error::DomainError(DomainError::EntityConflict)),
+            entry => Ok(entry),
+        }
     }
 
     pub async fn read_ledger_entries(&self, command: ReadLedgerEntriesCommand) -> Result<Vec<LedgerEntry>> {
         let query = FindManyLedgerQueryBuilder::default()
             .user_id(command.user_id)
+            .stash_id(command.stash_id)
             .entry_type(command.entry_type)
             .limit(command.limit.unwrap_or(20))
             .page(command.page)
@@ -43,6 +99,11 @@ impl LedgerService {
         Ok(self.ledger_repo.find_many(query).await?)
     }
 
+    /// every attempt lost to a concurrent save lets another writer through, so this bounds concurrent writers per stash
+    fn max_write_attempts() -> u32 {
+        16
+    }
+
     pub async fn read_ledger_entry(&self, command: ReadLedgerEntryCommand) -> Result<Option<LedgerEntry>> {
         self.ledger_repo.find_by_pid(&command.entry_id).await
     }
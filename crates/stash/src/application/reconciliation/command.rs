@@ -0,0 +1,7 @@
+use shared::domain::value_objects::pid::Pid;
+
+pub struct ReconcileStashCommand {
+    pub stash_id: Pid,
+    /// overwrite drifted stash balances with the ledger sums
+    pub repair: bool,
+}
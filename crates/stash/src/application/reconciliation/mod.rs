@@ -0,0 +1,131 @@
+use crate::{
+    application::reconciliation::command::ReconcileStashCommand,
+    domain::{
+        events::{StashBalanceDriftDetectedEvent, StashBalanceUpdatedEvent},
+        ledger_entry::entry::LedgerEntry,
+        reconciliation::ReconciliationReport,
+        repositories::{FindManyLedgerQueryBuilder, LedgerRepository, StashRepository},
+        stash::stash::Stash,
+    },
+};
+use di::injectable;
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        messaging::outbox::{OutboxRelay, RecordedEvent},
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+use std::sync::Arc;
+
+pub mod command;
+
+#[injectable]
+pub struct ReconciliationService {
+    stash_repo: Arc<dyn StashRepository>,
+    ledger_repo: Arc<dyn LedgerRepository>,
+    outbox_relay: Arc<OutboxRelay>,
+}
+
+impl ReconciliationService {
+    /// recomputes the stash balances from its ledger and reports every asset that drifted.
+    /// Drift is reported and repaired only if the stash was not saved since it was loaded, otherwise the ledger is
+    /// summed again, so balances are never compared with entries of another version of the stash.
+    pub async fn reconcile_stash(&self, command: ReconcileStashCommand) -> Result<ReconciliationReport> {
+        for _ in 0..Self::max_repair_attempts() {
+            if let Some(report) = self.try_reconcile_stash(&command).await? {
+                return Ok(report);
+            }
+        }
+
+        Err(Error::DomainError(DomainError::EntityConflict))
+    }
+
+    /// `None` when the stash was saved concurrently since it was loaded
+    async fn try_reconcile_stash(&self, command: &ReconcileStashCommand) -> Result<Option<ReconciliationReport>> {
+        let mut stash = self
+            .stash_repo
+            .find_by_pid(&command.stash_id)
+            .await?
+            .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+
+        let entries = self.stash_entries(&command.stash_id).await?;
+        let mut report = ReconciliationReport::compare(&stash, &entries)?;
+        if !report.has_drift() {
+            return Ok(Some(report));
+        }
+        // a ledger write committed between loading the stash and its entries looks like drift
+        if !self.is_unchanged(&stash).await? {
+            return Ok(None);
+        }
+
+        report.repaired = command.repair && report.is_repairable();
+        let mut events = vec![RecordedEvent::record(StashBalanceDriftDetectedEvent::new(
+            stash.get_pid(),
+            &report.discrepancies,
+            report.repaired,
+        ))?];
+        if !report.repaired {
+            self.outbox_relay.record(events).await?;
+            return Ok(Some(report));
+        }
+
+        stash.repair_balances(&report);
+        for discrepancy in &report.discrepancies {
+            events.push(RecordedEvent::record(StashBalanceUpdatedEvent::new(
+                stash.get_pid(),
+                &discrepancy.computed,
+            ))?);
+        }
+        match self.stash_repo.save_with_events(&stash, &events).await {
+            Ok(()) => {}
+            Err(Error::DomainError(DomainError::EntityConflict)) => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        self.outbox_relay.dispatch(events).await;
+
+        Ok(Some(report))
+    }
+
+    /// whether `stash` is still stored at the version it was loaded at
+    async fn is_unchanged(&self, stash: &Stash) -> Result<bool> {
+        let stored = self.stash_repo.find_by_pid(stash.get_pid()).await?;
+        Ok(stored.is_some_and(|stored| stored.get_version() == stash.get_version()))
+    }
+
+    async fn stash_entries(&self, stash_id: &Pid) -> Result<Vec<LedgerEntry>> {
+        let mut entries = vec![];
+        let mut page: u16 = 1;
+        loop {
+            let query = FindManyLedgerQueryBuilder::default()
+                .user_id(None)
+                .stash_id(Some(stash_id.clone()))
+                .entry_type(None)
+                .limit(Self::page_size())
+                .page(page)
+                .build()
+                .map_err(|e| Error::BuilderError(e.to_string()))?;
+
+            let batch = self.ledger_repo.find_many(query).await?;
+            let is_last_page = batch.len() < usize::from(Self::page_size());
+            entries.extend(batch);
+            if is_last_page {
+                return Ok(entries);
+            }
+            page += 1;
+        }
+    }
+
+    /// every attempt lost to a concurrent ledger write sums the ledger again
+    fn max_repair_attempts() -> u32 {
+        16
+    }
+
+    /// ledger entries fetched per query while summing a stash
+    fn page_size() -> u16 {
+        500
+    }
+}
@@ -1,5 +1,6 @@
-use crate::domain::stash::{name::StashName, status::StashStatus, tag::Tag};
-use shared::domain::value_objects::{mula::Mula, pid::Pid};
+use crate::domain::stash::{name::StashName, tag::Tag};
+use shared::domain::value_objects::pid::Pid;
+use shared::domain::value_objects::stash_status::StashStatus;
 
 pub struct CreateStashCommand {
     pub user_id: Pid,
@@ -22,7 +23,10 @@ pub struct UpdateStashStatusCommand {
     pub new_status: StashStatus,
 }
 
-pub struct UpdateStashBalanceCommand {
-    pub stash_id: Pid,
-    pub new_balance: Mula,
+/// Moves every stash of a user that can make the transition to `new_status`
+pub struct UpdateUserStashesStatusCommand {
+    pub user_id: Pid,
+    pub new_status: StashStatus,
+    /// stashes loaded and saved per unit of work
+    pub page_size: u16,
 }
@@ -0,0 +1,49 @@
+use crate::domain::{
+    repositories::{StashCursorQueryBuilder, StashRepository},
+    stash::stash::Stash,
+};
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::types::{Result, error::Error},
+};
+use std::sync::Arc;
+
+/// Walks every stash of a user, one page at a time
+pub struct StashCursor {
+    stash_repo: Arc<dyn StashRepository>,
+    user_id: Pid,
+    page_size: u16,
+    after: Option<Pid>,
+    exhausted: bool,
+}
+
+impl StashCursor {
+    pub fn new(stash_repo: Arc<dyn StashRepository>, user_id: &Pid, page_size: u16) -> Self {
+        Self {
+            stash_repo,
+            user_id: user_id.to_owned(),
+            page_size: page_size.max(1),
+            after: None,
+            exhausted: false,
+        }
+    }
+
+    /// the next page of stashes, `None` once every stash was returned
+    pub async fn next_page(&mut self) -> Result<Option<Vec<Stash>>> {
+        if self.exhausted {
+            return Ok(None);
+        }
+
+        let query = StashCursorQueryBuilder::default()
+            .user_id(self.user_id.clone())
+            .after(self.after.clone())
+            .limit(self.page_size)
+            .build()
+            .map_err(|e| Error::BuilderError(e.to_string()))?;
+        let page = self.stash_repo.find_after(query).await?;
+
+        self.exhausted = page.len() < usize::from(self.page_size);
+        self.after = page.last().map(|stash| stash.get_pid().to_owned());
+        Ok((!page.is_empty()).then_some(page))
+    }
+}
@@ -1,27 +1,43 @@
 use crate::{
-    application::stash::command::{CreateStashCommand, GetStashCommand, GetStashesCommand, UpdateStashBalanceCommand, UpdateStashStatusCommand},
+    application::stash::{
+        command::{CreateStashCommand, GetStashCommand, GetStashesCommand, UpdateStashStatusCommand, UpdateUserStashesStatusCommand},
+        cursor::StashCursor,
+    },
     domain::{
-        events::{StashBalanceUpdatedEvent, StashCreatedEvent, StashStatusUpdatedEvent},
+        events::{StashClosureBlockedEvent, StashCreatedEvent},
         repositories::{FindManyStashQueryBuilder, StashRepository},
         stash::stash::Stash,
     },
 };
 use di::injectable;
-use shared::infrastructure::{
-    messaging::EventBus,
-    types::{
-        Result,
-        error::{DomainError, Error},
+use shared::{
+    domain::{events::stash::StashStatusUpdatedEvent, value_objects::pid::Pid},
+    infrastructure::{
+        messaging::outbox::{OutboxRelay, RecordedEvent},
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
     },
 };
 use std::sync::Arc;
 
 pub mod command;
+pub mod cursor;
+
+/// Outcome of moving every stash of a user to a new status
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserStashesStatusRun {
+    pub updated: usize,
+    /// stashes the domain refused to move, e.g. a stash with funds left that should be closed;
+    /// each one is recorded as a `StashClosureBlockedEvent`
+    pub skipped: Vec<Pid>,
+}
 
 #[injectable]
 pub struct StashService {
     stash_repo: Arc<dyn StashRepository>,
-    event_bus: Arc<dyn EventBus>,
+    outbox_relay: Arc<OutboxRelay>,
 }
 
 impl StashService {
@@ -40,12 +56,16 @@ impl StashService {
         self.stash_repo.find_many(query).await
     }
 
+    /// a cursor over every stash of `user_id`
+    pub fn get_user_stashes(&self, user_id: &Pid, page_size: u16) -> StashCursor {
+        StashCursor::new(Arc::clone(&self.stash_repo), user_id, page_size)
+    }
+
     pub async fn create_stash(&self, command: CreateStashCommand) -> Result<Stash> {
         self.assert_can_create_stash(&command).await?;
         let stash = Stash::new(&command.user_id, &command.name, &command.tags);
-        self.stash_repo.save(&stash).await?;
-        let stash_created_event = StashCreatedEvent::new(stash.get_pid(), stash.get_user_id());
-        self.event_bus.publish(stash_created_event).await?;
+        let stash_created_event = RecordedEvent::record(StashCreatedEvent::new(stash.get_pid(), stash.get_user_id()))?;
+        self.save_and_dispatch(&stash, vec![stash_created_event]).await?;
         Ok(stash)
     }
 
@@ -56,25 +76,69 @@ impl StashService {
             .await?
             .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
 
-        stash.update_status(&command.new_status);
-        self.stash_repo.save(&stash).await?;
-        let stash_status_updated_event = StashStatusUpdatedEvent::new(stash.get_pid(), stash.get_status());
-        self.event_bus.publish(stash_status_updated_event).await?;
+        let old_status = stash.update_status(&command.new_status)?;
+        let stash_status_updated_event = RecordedEvent::record(StashStatusUpdatedEvent::new(
+            stash.get_pid(),
+            stash.get_user_id(),
+            &old_status,
+            stash.get_status(),
+        ))?;
+        self.save_and_dispatch(&stash, vec![stash_status_updated_event]).await?;
         Ok(stash)
     }
 
-    pub async fn update_stash_balance(&self, command: UpdateStashBalanceCommand) -> Result<Stash> {
-        let mut stash = self
-            .stash_repo
-            .find_by_pid(&command.stash_id)
-            .await?
-            .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+    /// moves the user's stashes page by page, saving every page with its events as one unit of work;
+    /// stashes already in the target status, or closed for good, are left as they are.
+    /// Stashes refusing the transition are skipped and recorded as blocked, so one of them never holds back the rest
+    pub async fn update_user_stashes_status(&self, command: UpdateUserStashesStatusCommand) -> Result<UserStashesStatusRun> {
+        let mut cursor = self.get_user_stashes(&command.user_id, command.page_size);
+        let mut run = UserStashesStatusRun::default();
 
-        stash.update_balance(&command.new_balance);
-        self.stash_repo.save(&stash).await?;
-        let stash_balance_updated_event = StashBalanceUpdatedEvent::new(stash.get_pid(), &command.new_balance);
-        self.event_bus.publish(stash_balance_updated_event).await?;
-        Ok(stash)
+        while let Some(page) = cursor.next_page().await? {
+            let mut stashes = vec![];
+            let mut events = vec![];
+            let mut blocked = vec![];
+            for mut stash in page.into_iter().filter(|stash| stash.get_status().can_transition_to(&command.new_status)) {
+                let old_status = match stash.update_status(&command.new_status) {
+                    Ok(old_status) => old_status,
+                    Err(e) => {
+                        println!("skipping stash {}: {e}", stash.get_pid().to_string());
+                        blocked.push(RecordedEvent::record(StashClosureBlockedEvent::new(
+                            stash.get_pid(),
+                            stash.get_user_id(),
+                            &e.to_string(),
+                        ))?);
+                        run.skipped.push(stash.get_pid().to_owned());
+                        continue;
+                    }
+                };
+                events.push(RecordedEvent::record(StashStatusUpdatedEvent::new(
+                    stash.get_pid(),
+                    stash.get_user_id(),
+                    &old_status,
+                    stash.get_status(),
+                ))?);
+                stashes.push(stash);
+            }
+
+            if !blocked.is_empty() {
+                self.outbox_relay.record(blocked).await?;
+            }
+            if stashes.is_empty() {
+                continue;
+            }
+            self.stash_repo.save_all_with_events(&stashes, &events).await?;
+            self.outbox_relay.dispatch(events).await;
+            run.updated += stashes.len();
+        }
+
+        Ok(run)
+    }
+
+    async fn save_and_dispatch(&self, stash: &Stash, events: Vec<RecordedEvent>) -> Result<()> {
+        self.stash_repo.save_with_events(stash, &events).await?;
+        self.outbox_relay.dispatch(events).await;
+        Ok(())
     }
 
     async fn assert_can_create_stash(&self, command: &CreateStashCommand) -> Result<()> {
@@ -88,7 +152,7 @@ impl StashService {
         }
 
         if self.stash_repo.exists_with_name_for_user(&command.user_id, &command.name).await? {
-            return Err(Error::AssertError("stash with name already exist".to_string()));
+            return Err(Error::DomainError(DomainError::EntityAlreadyExist));
         }
 
         Ok(())
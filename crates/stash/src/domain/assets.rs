@@ -0,0 +1,26 @@
+use shared::{
+    domain::value_objects::asset::Asset,
+    infrastructure::types::{Result, error::Error},
+};
+
+/// The assets stashes can hold, keyed by symbol and network.
+///
+/// Amounts are scaled by the decimals of the registered asset, never by decimals a client sent.
+#[derive(Debug, Clone, Default)]
+pub struct AssetRegistry {
+    assets: Vec<Asset>,
+}
+
+impl AssetRegistry {
+    pub fn new(assets: Vec<Asset>) -> Self {
+        Self { assets }
+    }
+
+    /// the registered asset with `symbol` on `network`
+    pub fn resolve(&self, symbol: &str, network: &str) -> Result<&Asset> {
+        self.assets
+            .iter()
+            .find(|asset| asset.symbol == symbol && asset.network == network)
+            .ok_or_else(|| Error::AssertError(format!("unsupported asset: {symbol} on {network}")))
+    }
+}
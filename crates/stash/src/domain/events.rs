@@ -1,16 +1,22 @@
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use shared::{
     domain::value_objects::{date::Date, mula::Mula, pid::Pid},
-    infrastructure::messaging::event::DomainEvent,
+    infrastructure::messaging::{
+        context::EventContext,
+        event::{DomainEvent, TypedEvent},
+    },
 };
 
-use crate::domain::stash::status::StashStatus;
+use crate::domain::reconciliation::BalanceDiscrepancy;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct StashCreatedEvent {
     pub stash_id: Pid,
     pub user_id: Pid,
     pub created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
 }
 
 impl StashCreatedEvent {
@@ -20,13 +26,18 @@ impl StashCreatedEvent {
             stash_id: stash_id.to_owned(),
             user_id: user_id.to_owned(),
             created_at: Utc::now(),
+            context: EventContext::current(),
         })
     }
 }
 
+impl TypedEvent for StashCreatedEvent {
+    const EVENT_TYPE: &'static str = "StashCreated";
+}
+
 impl DomainEvent for StashCreatedEvent {
     fn event_type(&self) -> &str {
-        "StashCreated"
+        Self::EVENT_TYPE
     }
 
     fn aggregate_id(&self) -> Pid {
@@ -36,28 +47,43 @@ impl DomainEvent for StashCreatedEvent {
     fn occurred_at(&self) -> Date {
         self.created_at.clone()
     }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
 }
 
-#[derive(Debug)]
-pub struct StashStatusUpdatedEvent {
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StashBalanceUpdatedEvent {
     stash_id: Pid,
-    pub new_status: StashStatus,
+    pub new_balance: Mula,
     created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
 }
 
-impl StashStatusUpdatedEvent {
-    pub fn new(stash_id: &Pid, new_status: &StashStatus) -> Box<Self> {
+impl StashBalanceUpdatedEvent {
+    pub fn new(stash_id: &Pid, new_balance: &Mula) -> Box<Self> {
         Box::new(Self {
             stash_id: stash_id.to_owned(),
-            new_status: new_status.to_owned(),
+            new_balance: new_balance.to_owned(),
             created_at: Utc::now(),
+            context: EventContext::current(),
         })
     }
 }
 
-impl DomainEvent for StashStatusUpdatedEvent {
+impl TypedEvent for StashBalanceUpdatedEvent {
+    const EVENT_TYPE: &'static str = "StashBalanceUpdated";
+}
+
+impl DomainEvent for StashBalanceUpdatedEvent {
     fn event_type(&self) -> &str {
-        "StashStatusUpdated"
+        Self::EVENT_TYPE
     }
 
     fn aggregate_id(&self) -> Pid {
@@ -67,28 +93,43 @@ impl DomainEvent for StashStatusUpdatedEvent {
     fn occurred_at(&self) -> Date {
         self.created_at.clone()
     }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
 }
 
-#[derive(Debug)]
-pub struct StashBalanceUpdatedEvent {
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LedgerEntryCreatedEvent {
     stash_id: Pid,
-    pub new_balance: Mula,
+    pub entry_id: Pid,
     created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
 }
 
-impl StashBalanceUpdatedEvent {
-    pub fn new(stash_id: &Pid, new_balance: &Mula) -> Box<Self> {
+impl LedgerEntryCreatedEvent {
+    pub fn new(stash_id: &Pid, entry_id: &Pid) -> Box<Self> {
         Box::new(Self {
             stash_id: stash_id.to_owned(),
-            new_balance: new_balance.to_owned(),
+            entry_id: entry_id.to_owned(),
             created_at: Utc::now(),
+            context: EventContext::current(),
         })
     }
 }
 
-impl DomainEvent for StashBalanceUpdatedEvent {
+impl TypedEvent for LedgerEntryCreatedEvent {
+    const EVENT_TYPE: &'static str = "LedgerEntryCreated";
+}
+
+impl DomainEvent for LedgerEntryCreatedEvent {
     fn event_type(&self) -> &str {
-        "StashBalanceUpdated"
+        Self::EVENT_TYPE
     }
 
     fn aggregate_id(&self) -> Pid {
@@ -98,28 +139,45 @@ impl DomainEvent for StashBalanceUpdatedEvent {
     fn occurred_at(&self) -> Date {
         self.created_at.clone()
     }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
 }
 
-#[derive(Debug)]
-pub struct LedgerEntryCreatedEvent {
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StashBalanceDriftDetectedEvent {
     stash_id: Pid,
-    pub entry_id: Pid,
+    pub discrepancies: Vec<BalanceDiscrepancy>,
+    pub repaired: bool,
     created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
 }
 
-impl LedgerEntryCreatedEvent {
-    pub fn new(stash_id: &Pid, entry_id: &Pid) -> Box<Self> {
+impl StashBalanceDriftDetectedEvent {
+    pub fn new(stash_id: &Pid, discrepancies: &[BalanceDiscrepancy], repaired: bool) -> Box<Self> {
         Box::new(Self {
             stash_id: stash_id.to_owned(),
-            entry_id: entry_id.to_owned(),
+            discrepancies: discrepancies.to_vec(),
+            repaired,
             created_at: Utc::now(),
+            context: EventContext::current(),
         })
     }
 }
 
-impl DomainEvent for LedgerEntryCreatedEvent {
+impl TypedEvent for StashBalanceDriftDetectedEvent {
+    const EVENT_TYPE: &'static str = "StashBalanceDriftDetected";
+}
+
+impl DomainEvent for StashBalanceDriftDetectedEvent {
     fn event_type(&self) -> &str {
-        "LedgerEntryCreated"
+        Self::EVENT_TYPE
     }
 
     fn aggregate_id(&self) -> Pid {
@@ -127,6 +185,63 @@ impl DomainEvent for LedgerEntryCreatedEvent {
     }
 
     fn occurred_at(&self) -> Date {
-        self.created_at.clone()
+        self.created_at
+    }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
+    }
+}
+
+/// A stash stayed open while its user was deleted, because funds are left in it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StashClosureBlockedEvent {
+    stash_id: Pid,
+    pub user_id: Pid,
+    pub reason: String,
+    created_at: Date,
+    #[serde(skip)]
+    context: EventContext,
+}
+
+impl StashClosureBlockedEvent {
+    pub fn new(stash_id: &Pid, user_id: &Pid, reason: &str) -> Box<Self> {
+        Box::new(Self {
+            stash_id: stash_id.to_owned(),
+            user_id: user_id.to_owned(),
+            reason: reason.to_owned(),
+            created_at: Utc::now(),
+            context: EventContext::current(),
+        })
+    }
+}
+
+impl TypedEvent for StashClosureBlockedEvent {
+    const EVENT_TYPE: &'static str = "StashClosureBlocked";
+}
+
+impl DomainEvent for StashClosureBlockedEvent {
+    fn event_type(&self) -> &str {
+        Self::EVENT_TYPE
+    }
+
+    fn aggregate_id(&self) -> Pid {
+        self.stash_id.clone()
+    }
+
+    fn occurred_at(&self) -> Date {
+        self.created_at
+    }
+
+    fn context(&self) -> &EventContext {
+        &self.context
+    }
+
+    fn context_mut(&mut self) -> &mut EventContext {
+        &mut self.context
     }
 }
@@ -2,13 +2,15 @@ use std::collections::HashMap;
 
 use crate::domain::ledger_entry::entry_type::LedgerEntryType;
 use chrono::Utc;
+use derive_builder::Builder;
 use serde_json::Value;
 use shared::domain::value_objects::{date::Date, mula::Mula, pid::Pid};
 
 pub type LedgerEntryMetadata = HashMap<String, Value>;
 
-#[derive(Debug, Clone)]
-#[allow(unused)]
+/// `LedgerEntryBuilder` rehydrates an entry from persistence; use `LedgerEntry::new` for new entries
+#[derive(Debug, Clone, Builder)]
+#[builder(setter(into))]
 pub struct LedgerEntry {
     pid: Pid,
     stash_id: Pid,
@@ -51,4 +53,12 @@ impl LedgerEntry {
     pub fn get_upstream_ref_id(&self) -> &Pid {
         &self.upstream_ref_id
     }
+
+    pub fn get_metadata(&self) -> &LedgerEntryMetadata {
+        &self.metadata
+    }
+
+    pub fn get_created_at(&self) -> &Date {
+        &self.created_at
+    }
 }
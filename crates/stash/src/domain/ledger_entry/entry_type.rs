@@ -1,5 +1,27 @@
+use std::str::FromStr;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LedgerEntryType {
     DEBIT,
     CREDIT,
 }
+
+impl LedgerEntryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DEBIT => "DEBIT",
+            Self::CREDIT => "CREDIT",
+        }
+    }
+}
+
+impl FromStr for LedgerEntryType {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "DEBIT" => Self::DEBIT,
+            "CREDIT" => Self::CREDIT,
+            _ => return Err("invalid ledger entry type"),
+        })
+    }
+}
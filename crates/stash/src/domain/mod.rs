@@ -1,4 +1,6 @@
+pub mod assets;
 pub mod events;
 pub mod ledger_entry;
+pub mod reconciliation;
 pub mod repositories;
 pub mod stash;
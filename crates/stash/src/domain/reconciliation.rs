@@ -0,0 +1,88 @@
+use crate::domain::{
+    ledger_entry::{entry::LedgerEntry, entry_type::LedgerEntryType},
+    stash::stash::{Stash, StashError},
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use shared::domain::value_objects::{asset::Asset, date::Date, mula::Mula, pid::Pid};
+
+/// A balance recorded on the stash that does not match the sum of its ledger entries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceDiscrepancy {
+    pub asset: Asset,
+    /// balance held by the `Stash` aggregate
+    pub recorded: Mula,
+    /// credits minus debits of every ledger entry in the asset, zero when the ledger is overdrawn
+    pub computed: Mula,
+    /// debits in excess of credits; such a ledger is corrupt and can not be repaired into a balance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overdrawn_by: Option<Mula>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconciliationReport {
+    pub stash_id: Pid,
+    pub discrepancies: Vec<BalanceDiscrepancy>,
+    pub repaired: bool,
+    pub reconciled_at: Date,
+}
+
+impl ReconciliationReport {
+    /// compares the balances of `stash` against the sums of its ledger `entries`
+    pub fn compare(stash: &Stash, entries: &[LedgerEntry]) -> Result<Self, StashError> {
+        let mut assets: Vec<Asset> = stash.get_balances().iter().map(|b| b.get_asset().clone()).collect();
+        for entry in entries {
+            let asset = entry.get_amount().get_asset();
+            if !assets.contains(asset) {
+                assets.push(asset.clone());
+            }
+        }
+
+        let mut discrepancies = vec![];
+        for asset in assets {
+            let recorded = stash.get_balance(&asset);
+            let (computed, overdrawn_by) = Self::sum(&asset, entries)?;
+            if recorded != computed || overdrawn_by.is_some() {
+                discrepancies.push(BalanceDiscrepancy {
+                    asset,
+                    recorded,
+                    computed,
+                    overdrawn_by,
+                });
+            }
+        }
+
+        Ok(Self {
+            stash_id: stash.get_pid().clone(),
+            discrepancies,
+            repaired: false,
+            reconciled_at: Utc::now(),
+        })
+    }
+
+    pub fn has_drift(&self) -> bool {
+        !self.discrepancies.is_empty()
+    }
+
+    /// an overdrawn ledger has no balance to repair the stash to
+    pub fn is_repairable(&self) -> bool {
+        self.discrepancies.iter().all(|d| d.overdrawn_by.is_none())
+    }
+
+    /// the balance the ledger adds up to, or zero and the overdrawn amount when debits exceed credits
+    fn sum(asset: &Asset, entries: &[LedgerEntry]) -> Result<(Mula, Option<Mula>), StashError> {
+        let mut credits = Mula::zero(asset);
+        let mut debits = Mula::zero(asset);
+        for entry in entries.iter().filter(|e| e.get_amount().get_asset() == asset) {
+            match entry.get_type() {
+                LedgerEntryType::CREDIT => credits = credits.checked_add(entry.get_amount())?,
+                LedgerEntryType::DEBIT => debits = debits.checked_add(entry.get_amount())?,
+            }
+        }
+
+        match credits.checked_sub(&debits) {
+            Ok(balance) => Ok((balance, None)),
+            Err(_) => Ok((Mula::zero(asset), Some(debits.checked_sub(&credits)?))),
+        }
+    }
+}
@@ -1,6 +1,9 @@
 use async_trait::async_trait;
 use derive_builder::Builder;
-use shared::{domain::value_objects::pid::Pid, infrastructure::types::Result};
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{messaging::outbox::RecordedEvent, types::Result},
+};
 
 use crate::domain::{
     ledger_entry::{entry::LedgerEntry, entry_type::LedgerEntryType},
@@ -15,26 +18,49 @@ pub struct FindManyStashQuery {
     pub page: u16,
 }
 
+/// Keyset page over the stashes of one user, ordered by pid
+#[derive(Builder, Debug)]
+#[builder(setter(into))]
+pub struct StashCursorQuery {
+    pub user_id: Pid,
+    /// pid of the last stash of the previous page, `None` for the first page
+    #[builder(default)]
+    pub after: Option<Pid>,
+    pub limit: u16,
+}
+
 #[async_trait]
 pub trait StashRepository: Sync + Send {
     async fn find_by_pid(&self, pid: &Pid) -> Result<Option<Stash>>;
     async fn find_many(&self, query: FindManyStashQuery) -> Result<Vec<Stash>>;
+    /// next page of a user's stashes; unlike `find_many` pages, it stays put while stashes are updated
+    async fn find_after(&self, query: StashCursorQuery) -> Result<Vec<Stash>>;
     async fn exists_with_name_for_user(&self, user_id: &Pid, name: &StashName) -> Result<bool>;
     async fn save(&self, stash: &Stash) -> Result<()>;
+    /// saves `stash`, the ledger `entry` that moved its balance and the events describing the movement as a single unit of work
+    async fn save_with_ledger_entry(&self, stash: &Stash, entry: &LedgerEntry, events: &[RecordedEvent]) -> Result<()>;
+    /// saves `stash` and appends the events describing the change to the outbox as a single unit of work
+    async fn save_with_events(&self, stash: &Stash, events: &[RecordedEvent]) -> Result<()>;
+    /// saves every stash of `stashes` and appends `events` to the outbox as a single unit of work
+    async fn save_all_with_events(&self, stashes: &[Stash], events: &[RecordedEvent]) -> Result<()>;
 }
 
 #[derive(Builder, Default, Debug)]
 #[builder(setter(into))]
 pub struct FindManyLedgerQuery {
     pub user_id: Option<Pid>,
+    #[builder(default)]
+    pub stash_id: Option<Pid>,
     pub entry_type: Option<LedgerEntryType>,
     pub limit: u16,
     pub page: u16,
 }
 
 #[async_trait]
-pub trait LedgerRepository {
+pub trait LedgerRepository: Sync + Send {
     async fn find_by_pid(&self, pid: &Pid) -> Result<Option<LedgerEntry>>;
     async fn find_many(&self, query: FindManyLedgerQuery) -> Result<Vec<LedgerEntry>>;
+    /// finds the entry of `entry_type` written to `stash_id` for the upstream movement `upstream_ref_id`
+    async fn find_by_upstream_ref(&self, stash_id: &Pid, upstream_ref_id: &Pid, entry_type: &LedgerEntryType) -> Result<Option<LedgerEntry>>;
     async fn save(&self, entry: &LedgerEntry) -> Result<()>;
 }
@@ -1,4 +1,3 @@
 pub mod name;
 pub mod stash;
-pub mod status;
 pub mod tag;
@@ -1,12 +1,54 @@
-use crate::domain::stash::{name::StashName, status::StashStatus, tag::Tag};
+use crate::domain::{
+    ledger_entry::{entry::LedgerEntry, entry_type::LedgerEntryType},
+    reconciliation::ReconciliationReport,
+    stash::{name::StashName, tag::Tag},
+};
 use chrono::Utc;
+use derive_builder::Builder;
 use serde_json::Value;
-use shared::domain::value_objects::{date::Date, mula::Mula, pid::Pid};
+use shared::{
+    domain::value_objects::{
+        asset::Asset,
+        date::Date,
+        mula::{Mula, MulaError},
+        pid::Pid,
+        stash_status::StashStatus,
+    },
+    infrastructure::types::error::Error,
+};
 use std::collections::HashMap;
+use thiserror::Error;
 
 pub type StashMetadata = HashMap<String, Value>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum StashError {
+    #[error("Stash is not active, status: {0:?}")]
+    NotActive(StashStatus),
+    #[error("Ledger entry does not belong to this stash")]
+    ForeignLedgerEntry,
+    #[error("Insufficient balance")]
+    InsufficientBalance,
+    #[error("Invalid stash status transition from {from:?} to {to:?}")]
+    InvalidStatusTransition { from: StashStatus, to: StashStatus },
+    #[error("Stash can not be closed with a remaining balance of {0}")]
+    NonZeroBalance(String),
+    #[error(transparent)]
+    Mula(#[from] MulaError),
+}
+
+impl From<StashError> for Error {
+    fn from(e: StashError) -> Self {
+        match e {
+            StashError::InvalidStatusTransition { .. } | StashError::NonZeroBalance(_) => Error::InvalidState(e.to_string()),
+            _ => Error::AssertError(e.to_string()),
+        }
+    }
+}
+
+/// `StashBuilder` rehydrates a stash from persistence; use `Stash::new` for new stashes
+#[derive(Debug, Clone, Builder)]
+#[builder(setter(into))]
 pub struct Stash {
     pid: Pid,
     user_id: Pid,
@@ -15,9 +57,11 @@ pub struct Stash {
     tags: Vec<Tag>,
     balances: Vec<Mula>,
     metadata: StashMetadata,
-    #[allow(dead_code)]
     created_at: Date,
     updated_at: Date,
+    /// version the stash was loaded at, repositories refuse to overwrite a newer one
+    #[builder(default)]
+    version: u64,
 }
 
 impl Stash {
@@ -32,6 +76,7 @@ impl Stash {
             metadata: HashMap::new(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            version: 0,
         }
     }
 
@@ -63,17 +108,89 @@ impl Stash {
         &self.metadata
     }
 
+    pub fn get_created_at(&self) -> &Date {
+        &self.created_at
+    }
+
+    pub fn get_updated_at(&self) -> &Date {
+        &self.updated_at
+    }
+
+    pub fn get_version(&self) -> u64 {
+        self.version
+    }
+
+    /// moves the stash past the version it was stored at; repositories call it on the copy they keep
+    pub fn advance_version(&mut self) {
+        self.version += 1;
+    }
+
     pub fn update_name(&mut self, new_name: &StashName) {
         self.name = new_name.clone();
         self.updated_at = Utc::now();
     }
 
-    pub fn update_status(&mut self, new_status: &StashStatus) {
-        self.status = new_status.clone();
+    /// moves the stash to `new_status` and returns the status it left.
+    /// A stash can only be closed once every balance is zero.
+    pub fn update_status(&mut self, new_status: &StashStatus) -> Result<StashStatus, StashError> {
+        if !self.status.can_transition_to(new_status) {
+            return Err(StashError::InvalidStatusTransition {
+                from: self.status.clone(),
+                to: new_status.clone(),
+            });
+        }
+
+        if new_status == &StashStatus::CLOSED
+            && let Some(balance) = self.balances.iter().find(|b| !b.is_zero())
+        {
+            return Err(StashError::NonZeroBalance(balance.to_string()));
+        }
+
+        let old_status = std::mem::replace(&mut self.status, new_status.clone());
         self.updated_at = Utc::now();
+        Ok(old_status)
+    }
+
+    pub fn get_balance(&self, asset: &Asset) -> Mula {
+        self.balances
+            .iter()
+            .find(|b| b.get_asset() == asset)
+            .cloned()
+            .unwrap_or_else(|| Mula::zero(asset))
+    }
+
+    /// moves the balance of the entry's asset by the entry amount and returns the new balance.
+    /// Only active stashes accept entries and a debit can never overdraw the balance.
+    pub fn apply_ledger_entry(&mut self, entry: &LedgerEntry) -> Result<Mula, StashError> {
+        if entry.get_stash_id() != &self.pid {
+            return Err(StashError::ForeignLedgerEntry);
+        }
+
+        if self.status != StashStatus::ACTIVE {
+            return Err(StashError::NotActive(self.status.clone()));
+        }
+
+        let balance = self.get_balance(entry.get_amount().get_asset());
+        let new_balance = match entry.get_type() {
+            LedgerEntryType::CREDIT => balance.checked_add(entry.get_amount())?,
+            LedgerEntryType::DEBIT => balance.checked_sub(entry.get_amount()).map_err(|e| match e {
+                MulaError::Underflow => StashError::InsufficientBalance,
+                e => StashError::Mula(e),
+            })?,
+        };
+
+        self.update_balance(&new_balance);
+        Ok(new_balance)
+    }
+
+    /// overwrites the balances flagged by a reconciliation with the sums computed from the ledger
+    pub fn repair_balances(&mut self, report: &ReconciliationReport) {
+        for discrepancy in &report.discrepancies {
+            self.update_balance(&discrepancy.computed);
+        }
     }
 
-    pub fn update_balance(&mut self, new_balance: &Mula) {
+    fn update_balance(&mut self, new_balance: &Mula) {
         if let Some(balance) = self.balances.iter_mut().find(|b| b.get_asset().eq(new_balance.get_asset())) {
             *balance = new_balance.clone();
         } else {
@@ -1,2 +1,2 @@
-pub mod register;
+pub mod registry;
 pub mod user_status_updated;
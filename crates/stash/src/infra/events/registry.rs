@@ -0,0 +1,22 @@
+use shared::{
+    domain::events::{stash::StashStatusUpdatedEvent, user::UserStatusUpdatedEvent},
+    infrastructure::messaging::envelope::EventRegistry,
+};
+
+use crate::domain::events::{
+    LedgerEntryCreatedEvent, StashBalanceDriftDetectedEvent, StashBalanceUpdatedEvent, StashClosureBlockedEvent, StashCreatedEvent,
+};
+
+/// Registry of every event the stash services emit, plus the user events they consume
+pub fn event_registry() -> EventRegistry {
+    let mut registry = EventRegistry::default();
+    registry
+        .register::<StashCreatedEvent>(1)
+        .register::<StashStatusUpdatedEvent>(1)
+        .register::<StashBalanceUpdatedEvent>(1)
+        .register::<LedgerEntryCreatedEvent>(1)
+        .register::<StashBalanceDriftDetectedEvent>(1)
+        .register::<StashClosureBlockedEvent>(1)
+        .register::<UserStatusUpdatedEvent>(1);
+    registry
+}
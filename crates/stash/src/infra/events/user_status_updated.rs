@@ -3,23 +3,17 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use di::injectable;
 use shared::{
-    domain::{events::user::UserStatusUpdatedEvent, value_objects::user_status::UserStatus},
+    domain::{
+        events::user::UserStatusUpdatedEvent,
+        value_objects::{stash_status::StashStatus, user_status::UserStatus},
+    },
     infrastructure::{
-        messaging::{
-            EventHandler,
-            event::{DomainEvent, downcast_event},
-        },
+        messaging::{EventHandler, TypedEventHandler, retry::RetryPolicy},
         types::Result,
     },
 };
 
-use crate::{
-    application::stash::{
-        StashService,
-        command::{GetStashesCommand, UpdateStashStatusCommand},
-    },
-    domain::stash::status::StashStatus,
-};
+use crate::application::stash::{StashService, command::UpdateUserStashesStatusCommand};
 
 #[injectable(EventHandler)]
 pub struct OnUserStatusUpdated {
@@ -34,34 +28,33 @@ impl OnUserStatusUpdated {
             UserStatus::Deleted => StashStatus::CLOSED,
         }
     }
+
+    /// stashes moved per unit of work
+    fn page_size() -> u16 {
+        100
+    }
 }
 
 #[async_trait]
-impl EventHandler for OnUserStatusUpdated {
-    fn event_type(&self) -> &'static str {
-        "UserStatusUpdated"
+impl TypedEventHandler for OnUserStatusUpdated {
+    type Event = UserStatusUpdatedEvent;
+
+    /// stashes already moved are skipped, so a retry finishes the ones a failed attempt left behind
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            ..RetryPolicy::default()
+        }
     }
 
-    async fn handle(&self, event: Box<dyn DomainEvent>) -> Result<()> {
-        let event = downcast_event::<UserStatusUpdatedEvent>(&event);
-        let command = GetStashesCommand {
-            user_id: Some(event.user_id.clone()),
-            limit: Some(1000), // fetch all stashes
-            page: 1,
+    async fn handle(&self, event: &UserStatusUpdatedEvent) -> Result<()> {
+        let command = UpdateUserStashesStatusCommand {
+            user_id: event.user_id.clone(),
+            new_status: self.resolve_new_status(&event.new_status),
+            page_size: Self::page_size(),
         };
 
-        let user_stashes = self.stash_service.get_stashes(command).await?;
-        let new_status = self.resolve_new_status(&event.new_status);
-
-        for stash in user_stashes {
-            let command = UpdateStashStatusCommand {
-                stash_id: stash.get_pid().to_owned(),
-                new_status: new_status.clone(),
-            };
-
-            self.stash_service.update_stash_status(command).await?;
-        }
-
+        self.stash_service.update_user_stashes_status(command).await?;
         Ok(())
     }
 }
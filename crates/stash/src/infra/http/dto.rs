@@ -0,0 +1,237 @@
+use crate::{
+    application::{
+        ledger::command::{ReadLedgerEntriesCommand, WriteLedgerEntryCommand},
+        stash::command::{CreateStashCommand, GetStashesCommand},
+    },
+    domain::{
+        assets::AssetRegistry,
+        ledger_entry::{entry::LedgerEntry, entry_type::LedgerEntryType},
+        stash::{name::StashName, stash::Stash, tag::Tag},
+    },
+};
+use serde::{Deserialize, Serialize};
+use shared::{
+    domain::value_objects::{asset::Asset, date::Date, mula::Mula, pid::Pid, stash_status::StashStatus},
+    infrastructure::types::{Result, error::Error},
+};
+use std::str::FromStr;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateStashRequest {
+    pub name: StashName,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+}
+
+impl CreateStashRequest {
+    pub fn into_command(self, user_id: Pid) -> CreateStashCommand {
+        CreateStashCommand {
+            user_id,
+            name: self.name,
+            tags: self.tags,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListStashesQuery {
+    #[serde(default = "first_page")]
+    pub page: u16,
+    pub limit: Option<u16>,
+}
+
+impl ListStashesQuery {
+    pub fn into_command(self, user_id: Pid) -> GetStashesCommand {
+        GetStashesCommand {
+            user_id: Some(user_id),
+            page: self.page,
+            limit: page_size(self.limit),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateStashStatusRequest {
+    pub status: String,
+}
+
+impl UpdateStashStatusRequest {
+    pub fn status(&self) -> Result<StashStatus> {
+        StashStatus::from_str(&self.status).map_err(|e| Error::AssertError(e.to_owned()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WriteLedgerEntryRequest {
+    pub entry_type: String,
+    /// human readable decimal amount e.g. `"12.5"`
+    pub amount: String,
+    pub asset: AssetRefDto,
+    pub upstream_ref_id: Pid,
+}
+
+impl WriteLedgerEntryRequest {
+    pub fn into_command(self, stash_id: Pid, assets: &AssetRegistry) -> Result<WriteLedgerEntryCommand> {
+        let entry_type = LedgerEntryType::from_str(&self.entry_type).map_err(|e| Error::AssertError(e.to_owned()))?;
+        let asset = self.asset.resolve(assets)?;
+        let amount = Mula::parse(&self.amount, asset).map_err(|e| Error::AssertError(e.to_string()))?;
+
+        Ok(WriteLedgerEntryCommand {
+            stash_id,
+            entry_type,
+            amount,
+            upstream_ref_id: self.upstream_ref_id,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListLedgerEntriesQuery {
+    pub entry_type: Option<String>,
+    #[serde(default = "first_page")]
+    pub page: u16,
+    pub limit: Option<u16>,
+}
+
+impl ListLedgerEntriesQuery {
+    pub fn into_command(self, stash_id: Pid) -> Result<ReadLedgerEntriesCommand> {
+        let entry_type = self
+            .entry_type
+            .map(|entry_type| LedgerEntryType::from_str(&entry_type).map_err(|e| Error::AssertError(e.to_owned())))
+            .transpose()?;
+
+        Ok(ReadLedgerEntriesCommand {
+            user_id: None,
+            stash_id: Some(stash_id),
+            entry_type,
+            page: self.page,
+            limit: page_size(self.limit),
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssetDto {
+    pub name: String,
+    pub symbol: String,
+    pub network: String,
+    pub address: Option<String>,
+    pub decimals: u8,
+    pub display_decimals: u8,
+}
+
+/// Names a registered asset; decimals, when sent, must match the registered ones
+#[derive(Debug, Deserialize)]
+pub struct AssetRefDto {
+    pub symbol: String,
+    pub network: String,
+    pub decimals: Option<u8>,
+}
+
+impl AssetRefDto {
+    fn resolve(self, assets: &AssetRegistry) -> Result<&Asset> {
+        let asset = assets.resolve(&self.symbol, &self.network)?;
+        match self.decimals {
+            Some(decimals) if decimals != asset.decimals => Err(Error::AssertError(format!(
+                "{} on {} has {} decimals, got {decimals}",
+                asset.symbol, asset.network, asset.decimals
+            ))),
+            _ => Ok(asset),
+        }
+    }
+}
+
+impl From<&Asset> for AssetDto {
+    fn from(asset: &Asset) -> Self {
+        Self {
+            name: asset.name.clone(),
+            symbol: asset.symbol.clone(),
+            network: asset.network.clone(),
+            address: asset.address.as_ref().map(|address| address.to_string()),
+            decimals: asset.decimals,
+            display_decimals: asset.display_decimals,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MulaDto {
+    pub asset: AssetDto,
+    /// amount in the asset's smallest unit, as a string to survive JSON number precision
+    pub amount: String,
+    pub formatted: String,
+}
+
+impl From<&Mula> for MulaDto {
+    fn from(mula: &Mula) -> Self {
+        Self {
+            asset: AssetDto::from(mula.get_asset()),
+            amount: mula.get_amount().to_string(),
+            formatted: mula.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StashResponse {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub status: &'static str,
+    pub tags: Vec<String>,
+    pub balances: Vec<MulaDto>,
+    pub created_at: Date,
+    pub updated_at: Date,
+}
+
+impl From<&Stash> for StashResponse {
+    fn from(stash: &Stash) -> Self {
+        Self {
+            id: stash.get_pid().to_string(),
+            user_id: stash.get_user_id().to_string(),
+            name: stash.get_name().to_string(),
+            status: stash.get_status().as_str(),
+            tags: stash.get_tags().iter().map(|tag| tag.to_string()).collect(),
+            balances: stash.get_balances().iter().map(MulaDto::from).collect(),
+            created_at: *stash.get_created_at(),
+            updated_at: *stash.get_updated_at(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LedgerEntryResponse {
+    pub id: String,
+    pub stash_id: String,
+    pub entry_type: &'static str,
+    pub amount: MulaDto,
+    pub upstream_ref_id: String,
+    pub created_at: Date,
+}
+
+impl From<&LedgerEntry> for LedgerEntryResponse {
+    fn from(entry: &LedgerEntry) -> Self {
+        Self {
+            id: entry.get_pid().to_string(),
+            stash_id: entry.get_stash_id().to_string(),
+            entry_type: entry.get_type().as_str(),
+            amount: MulaDto::from(entry.get_amount()),
+            upstream_ref_id: entry.get_upstream_ref_id().to_string(),
+            created_at: *entry.get_created_at(),
+        }
+    }
+}
+
+fn first_page() -> u16 {
+    1
+}
+
+/// the requested `limit` within `1..=max_page_size`
+fn page_size(limit: Option<u16>) -> Option<u16> {
+    limit.map(|limit| limit.clamp(1, max_page_size()))
+}
+
+/// largest page a listing returns, whatever the request asks for
+fn max_page_size() -> u16 {
+    100
+}
@@ -0,0 +1,61 @@
+use crate::{
+    application::ledger::command::ReadLedgerEntryCommand,
+    infra::http::{
+        StashApi,
+        dto::{LedgerEntryResponse, ListLedgerEntriesQuery, WriteLedgerEntryRequest},
+        stash::owned_stash,
+    },
+};
+use axum::{extract::State, http::StatusCode};
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        http::{
+            auth::{AuthenticatedService, AuthenticatedUser},
+            extract::{Json, Path, Query},
+        },
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+use std::sync::Arc;
+
+/// ledger entries record funds another service moved, so users can read but never write them
+pub(super) async fn write_ledger_entry(
+    State(api): State<Arc<StashApi>>,
+    _: AuthenticatedService,
+    Path(stash_id): Path<Pid>,
+    Json(request): Json<WriteLedgerEntryRequest>,
+) -> Result<(StatusCode, Json<LedgerEntryResponse>)> {
+    let command = request.into_command(stash_id, &api.asset_registry)?;
+    let entry = api.ledger_service.write_ledger_entry(command).await?;
+    Ok((StatusCode::CREATED, Json(LedgerEntryResponse::from(&entry))))
+}
+
+pub(super) async fn list_ledger_entries(
+    State(api): State<Arc<StashApi>>,
+    AuthenticatedUser { user_id, .. }: AuthenticatedUser,
+    Path(stash_id): Path<Pid>,
+    Query(query): Query<ListLedgerEntriesQuery>,
+) -> Result<Json<Vec<LedgerEntryResponse>>> {
+    owned_stash(&api, &user_id, &stash_id).await?;
+    let entries = api.ledger_service.read_ledger_entries(query.into_command(stash_id)?).await?;
+    Ok(Json(entries.iter().map(LedgerEntryResponse::from).collect()))
+}
+
+pub(super) async fn get_ledger_entry(
+    State(api): State<Arc<StashApi>>,
+    AuthenticatedUser { user_id, .. }: AuthenticatedUser,
+    Path(entry_id): Path<Pid>,
+) -> Result<Json<LedgerEntryResponse>> {
+    let entry = api
+        .ledger_service
+        .read_ledger_entry(ReadLedgerEntryCommand { entry_id })
+        .await?
+        .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+
+    owned_stash(&api, &user_id, entry.get_stash_id()).await?;
+    Ok(Json(LedgerEntryResponse::from(&entry)))
+}
@@ -0,0 +1,46 @@
+use crate::{
+    application::{ledger::LedgerService, stash::StashService},
+    domain::assets::AssetRegistry,
+};
+use axum::{
+    Extension, Router, middleware,
+    routing::{get, patch},
+};
+use di::injectable;
+use shared::infrastructure::http::{
+    auth::{ServiceTokenVerifier, TokenVerifier},
+    correlation,
+};
+use std::sync::Arc;
+
+pub mod dto;
+mod ledger;
+mod stash;
+
+/// HTTP entrypoint of the stash bounded context
+#[injectable]
+pub struct StashApi {
+    stash_service: Arc<StashService>,
+    ledger_service: Arc<LedgerService>,
+    asset_registry: Arc<AssetRegistry>,
+    token_verifier: Arc<dyn TokenVerifier>,
+    service_token_verifier: Arc<dyn ServiceTokenVerifier>,
+}
+
+impl StashApi {
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route("/stashes", get(stash::list_stashes).post(stash::create_stash))
+            .route("/stashes/{stash_id}", get(stash::get_stash))
+            .route("/stashes/{stash_id}/status", patch(stash::update_stash_status))
+            .route(
+                "/stashes/{stash_id}/ledger",
+                get(ledger::list_ledger_entries).post(ledger::write_ledger_entry),
+            )
+            .route("/ledger/{entry_id}", get(ledger::get_ledger_entry))
+            .layer(Extension(Arc::clone(&self.token_verifier)))
+            .layer(Extension(Arc::clone(&self.service_token_verifier)))
+            .layer(middleware::from_fn(correlation::correlate))
+            .with_state(self)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    application::stash::command::{GetStashCommand, UpdateStashStatusCommand},
+    domain::stash::stash::Stash,
+    infra::http::{
+        StashApi,
+        dto::{CreateStashRequest, ListStashesQuery, StashResponse, UpdateStashStatusRequest},
+    },
+};
+use axum::{extract::State, http::StatusCode};
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        http::{
+            auth::AuthenticatedUser,
+            extract::{Json, Path, Query},
+        },
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+use std::sync::Arc;
+
+pub(super) async fn create_stash(
+    State(api): State<Arc<StashApi>>,
+    AuthenticatedUser { user_id, .. }: AuthenticatedUser,
+    Json(request): Json<CreateStashRequest>,
+) -> Result<(StatusCode, Json<StashResponse>)> {
+    let stash = api.stash_service.create_stash(request.into_command(user_id)).await?;
+    Ok((StatusCode::CREATED, Json(StashResponse::from(&stash))))
+}
+
+pub(super) async fn list_stashes(
+    State(api): State<Arc<StashApi>>,
+    AuthenticatedUser { user_id, .. }: AuthenticatedUser,
+    Query(query): Query<ListStashesQuery>,
+) -> Result<Json<Vec<StashResponse>>> {
+    let stashes = api.stash_service.get_stashes(query.into_command(user_id)).await?;
+    Ok(Json(stashes.iter().map(StashResponse::from).collect()))
+}
+
+pub(super) async fn get_stash(
+    State(api): State<Arc<StashApi>>,
+    AuthenticatedUser { user_id, .. }: AuthenticatedUser,
+    Path(stash_id): Path<Pid>,
+) -> Result<Json<StashResponse>> {
+    let stash = owned_stash(&api, &user_id, &stash_id).await?;
+    Ok(Json(StashResponse::from(&stash)))
+}
+
+pub(super) async fn update_stash_status(
+    State(api): State<Arc<StashApi>>,
+    AuthenticatedUser { user_id, .. }: AuthenticatedUser,
+    Path(stash_id): Path<Pid>,
+    Json(request): Json<UpdateStashStatusRequest>,
+) -> Result<Json<StashResponse>> {
+    owned_stash(&api, &user_id, &stash_id).await?;
+    let command = UpdateStashStatusCommand {
+        stash_id,
+        new_status: request.status()?,
+    };
+
+    let stash = api.stash_service.update_stash_status(command).await?;
+    Ok(Json(StashResponse::from(&stash)))
+}
+
+/// the stash when it belongs to `user_id`; someone else's stash is reported as not found
+pub(super) async fn owned_stash(api: &StashApi, user_id: &Pid, stash_id: &Pid) -> Result<Stash> {
+    api.stash_service
+        .get_stash(GetStashCommand { stash_id: stash_id.clone() })
+        .await?
+        .filter(|stash| stash.get_user_id() == user_id)
+        .ok_or(Error::DomainError(DomainError::EntityNotFound))
+}
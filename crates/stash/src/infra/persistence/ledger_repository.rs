@@ -0,0 +1,100 @@
+use crate::{
+    domain::{
+        ledger_entry::{entry::LedgerEntry, entry_type::LedgerEntryType},
+        repositories::{FindManyLedgerQuery, LedgerRepository},
+    },
+    infra::persistence::{db_error, offset, rows::LedgerEntryRow},
+};
+use async_trait::async_trait;
+use di::injectable;
+use shared::{domain::value_objects::pid::Pid, infrastructure::types::Result};
+use sqlx::{PgExecutor, PgPool, types::Json};
+use std::sync::Arc;
+
+const SELECT_ENTRY: &str = "SELECT pid, stash_id, entry_type, amount::text AS amount, asset_name, asset_symbol, asset_network, asset_address, \
+     asset_decimals, asset_display_decimals, upstream_ref_id, metadata, created_at FROM ledger_entries";
+
+#[injectable(LedgerRepository)]
+pub struct PgLedgerRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PgLedgerRepository {
+    /// ledger entries are immutable, inserting an existing entry is a no-op
+    pub(super) async fn insert<'e>(executor: impl PgExecutor<'e>, entry: &LedgerEntry) -> Result<()> {
+        let amount = entry.get_amount();
+        let asset = amount.get_asset();
+
+        sqlx::query(
+            "INSERT INTO ledger_entries (pid, stash_id, entry_type, amount, asset_name, asset_symbol, asset_network, asset_address, \
+             asset_decimals, asset_display_decimals, upstream_ref_id, metadata, created_at) \
+             VALUES ($1, $2, $3, $4::numeric, $5, $6, $7, $8, $9, $10, $11, $12, $13) ON CONFLICT (pid) DO NOTHING",
+        )
+        .bind(entry.get_pid().as_uuid())
+        .bind(entry.get_stash_id().as_uuid())
+        .bind(entry.get_type().as_str())
+        .bind(amount.get_amount().to_string())
+        .bind(&asset.name)
+        .bind(&asset.symbol)
+        .bind(&asset.network)
+        .bind(asset.address.as_ref().map(|a| a.to_string()))
+        .bind(i16::from(asset.decimals))
+        .bind(i16::from(asset.display_decimals))
+        .bind(entry.get_upstream_ref_id().as_uuid())
+        .bind(Json(entry.get_metadata()))
+        .bind(entry.get_created_at())
+        .execute(executor)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LedgerRepository for PgLedgerRepository {
+    async fn find_by_pid(&self, pid: &Pid) -> Result<Option<LedgerEntry>> {
+        let row: Option<LedgerEntryRow> = sqlx::query_as(&format!("{SELECT_ENTRY} WHERE pid = $1"))
+            .bind(pid.as_uuid())
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        row.map(LedgerEntry::try_from).transpose()
+    }
+
+    async fn find_many(&self, query: FindManyLedgerQuery) -> Result<Vec<LedgerEntry>> {
+        let rows: Vec<LedgerEntryRow> = sqlx::query_as(&format!(
+            "{SELECT_ENTRY} WHERE ($1::uuid IS NULL OR stash_id IN (SELECT pid FROM stashes WHERE user_id = $1)) \
+             AND ($2::uuid IS NULL OR stash_id = $2) AND ($3::text IS NULL OR entry_type = $3) ORDER BY created_at, id LIMIT $4 OFFSET $5"
+        ))
+        .bind(query.user_id.as_ref().map(|id| id.as_uuid()))
+        .bind(query.stash_id.as_ref().map(|id| id.as_uuid()))
+        .bind(query.entry_type.as_ref().map(|t| t.as_str()))
+        .bind(i64::from(query.limit))
+        .bind(offset(query.page, query.limit))
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(LedgerEntry::try_from).collect()
+    }
+
+    async fn find_by_upstream_ref(&self, stash_id: &Pid, upstream_ref_id: &Pid, entry_type: &LedgerEntryType) -> Result<Option<LedgerEntry>> {
+        let row: Option<LedgerEntryRow> = sqlx::query_as(&format!(
+            "{SELECT_ENTRY} WHERE stash_id = $1 AND upstream_ref_id = $2 AND entry_type = $3"
+        ))
+        .bind(stash_id.as_uuid())
+        .bind(upstream_ref_id.as_uuid())
+        .bind(entry_type.as_str())
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(LedgerEntry::try_from).transpose()
+    }
+
+    async fn save(&self, entry: &LedgerEntry) -> Result<()> {
+        Self::insert(&*self.pool, entry).await
+    }
+}
@@ -1 +1,40 @@
+use shared::infrastructure::types::{
+    Result,
+    error::{DomainError, Error},
+};
+use sqlx::{PgPool, migrate::Migrator, postgres::PgPoolOptions};
+
+pub mod ledger_repository;
+pub mod outbox;
+pub mod quarantine;
+mod rows;
 pub mod stash_repository;
+
+/// Migrations for the stash schema, embedded at compile time from `crates/stash/migrations`
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Connects to postgres and brings the stash schema up to date
+pub async fn connect(database_url: &str) -> Result<PgPool> {
+    let pool = PgPoolOptions::new().connect(database_url).await.map_err(db_error)?;
+    MIGRATOR.run(&pool).await.map_err(|e| {
+        println!("failed to run stash migrations: {e}");
+        Error::ServiceError
+    })?;
+
+    Ok(pool)
+}
+
+pub(crate) fn db_error(e: sqlx::Error) -> Error {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => Error::DomainError(DomainError::EntityAlreadyExist),
+        _ => {
+            println!("database error: {e}");
+            Error::ServiceError
+        }
+    }
+}
+
+/// offset of the first row of a 1-based `page`
+pub(crate) fn offset(page: u16, limit: u16) -> i64 {
+    i64::from(page.saturating_sub(1)) * i64::from(limit)
+}
@@ -0,0 +1,122 @@
+use crate::infra::persistence::db_error;
+use async_trait::async_trait;
+use chrono::Utc;
+use di::injectable;
+use serde_json::Value;
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{
+        messaging::{envelope::EventEnvelope, outbox::Outbox},
+        types::Result,
+    },
+};
+use sqlx::{FromRow, PgConnection, PgExecutor, PgPool, types::Uuid};
+use std::sync::Arc;
+
+#[derive(FromRow)]
+struct OutboxRow {
+    pid: Uuid,
+    event_type: String,
+    schema_version: i32,
+    aggregate_id: Uuid,
+    correlation_id: Option<String>,
+    causation_id: Option<String>,
+    payload: Value,
+    occurred_at: Date,
+}
+
+impl From<OutboxRow> for EventEnvelope {
+    fn from(row: OutboxRow) -> Self {
+        Self {
+            event_id: Pid::from(row.pid),
+            event_type: row.event_type,
+            schema_version: row.schema_version as u32,
+            aggregate_id: Pid::from(row.aggregate_id),
+            correlation_id: row.correlation_id,
+            causation_id: row.causation_id,
+            payload: row.payload,
+            occurred_at: row.occurred_at,
+        }
+    }
+}
+
+#[injectable(Outbox)]
+pub struct PgOutbox {
+    pool: Arc<PgPool>,
+}
+
+impl PgOutbox {
+    /// inserts `envelopes` on the transaction a repository saves its aggregate on
+    pub(super) async fn insert(connection: &mut PgConnection, envelopes: &[EventEnvelope]) -> Result<()> {
+        for envelope in envelopes {
+            Self::insert_one(&mut *connection, envelope).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn insert_one<'e>(executor: impl PgExecutor<'e>, envelope: &EventEnvelope) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO event_outbox (pid, event_type, schema_version, aggregate_id, correlation_id, causation_id, payload, occurred_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(envelope.event_id.as_uuid())
+        .bind(&envelope.event_type)
+        .bind(envelope.schema_version as i32)
+        .bind(envelope.aggregate_id.as_uuid())
+        .bind(&envelope.correlation_id)
+        .bind(&envelope.causation_id)
+        .bind(&envelope.payload)
+        .bind(envelope.occurred_at)
+        .execute(executor)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Outbox for PgOutbox {
+    async fn append(&self, envelopes: &[EventEnvelope]) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        Self::insert(&mut tx, envelopes).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn pending(&self, limit: u32) -> Result<Vec<EventEnvelope>> {
+        let rows: Vec<OutboxRow> = sqlx::query_as(
+            "SELECT pid, event_type, schema_version, aggregate_id, correlation_id, causation_id, payload, occurred_at \
+             FROM event_outbox WHERE published_at IS NULL AND failed_at IS NULL ORDER BY id LIMIT $1",
+        )
+        .bind(i64::from(limit))
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(rows.into_iter().map(EventEnvelope::from).collect())
+    }
+
+    async fn mark_published(&self, id: &Pid) -> Result<()> {
+        sqlx::query("UPDATE event_outbox SET published_at = $1 WHERE pid = $2 AND published_at IS NULL")
+            .bind(Utc::now())
+            .bind(id.as_uuid())
+            .execute(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: &Pid, error: &str) -> Result<()> {
+        sqlx::query("UPDATE event_outbox SET failed_at = $1, last_error = $2 WHERE pid = $3 AND published_at IS NULL")
+            .bind(Utc::now())
+            .bind(error)
+            .bind(id.as_uuid())
+            .execute(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+}
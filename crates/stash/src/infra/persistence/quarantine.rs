@@ -0,0 +1,100 @@
+use crate::infra::persistence::db_error;
+use async_trait::async_trait;
+use di::injectable;
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{
+        messaging::{
+            envelope::EventEnvelope,
+            quarantine::{QuarantineStore, QuarantinedEvent},
+        },
+        types::Result,
+    },
+};
+use sqlx::{
+    FromRow, PgPool,
+    types::{Json, Uuid},
+};
+use std::sync::Arc;
+
+#[derive(FromRow)]
+struct QuarantineRow {
+    pid: Uuid,
+    handler: String,
+    envelope: Json<EventEnvelope>,
+    attempts: i32,
+    last_error: String,
+    quarantined_at: Date,
+}
+
+impl From<QuarantineRow> for QuarantinedEvent {
+    fn from(row: QuarantineRow) -> Self {
+        Self {
+            pid: Pid::from(row.pid),
+            handler: row.handler,
+            envelope: row.envelope.0,
+            attempts: row.attempts as u32,
+            last_error: row.last_error,
+            quarantined_at: row.quarantined_at,
+        }
+    }
+}
+
+#[injectable(QuarantineStore)]
+pub struct PgQuarantineStore {
+    pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl QuarantineStore for PgQuarantineStore {
+    async fn put(&self, event: &QuarantinedEvent) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO event_quarantine (pid, handler, envelope, attempts, last_error, quarantined_at) \
+             VALUES ($1, $2, $3, $4, $5, $6) \
+             ON CONFLICT (pid) DO UPDATE SET attempts = EXCLUDED.attempts, last_error = EXCLUDED.last_error",
+        )
+        .bind(event.pid.as_uuid())
+        .bind(&event.handler)
+        .bind(Json(&event.envelope))
+        .bind(event.attempts as i32)
+        .bind(&event.last_error)
+        .bind(event.quarantined_at)
+        .execute(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn list(&self, limit: u32) -> Result<Vec<QuarantinedEvent>> {
+        let rows: Vec<QuarantineRow> =
+            sqlx::query_as("SELECT pid, handler, envelope, attempts, last_error, quarantined_at FROM event_quarantine ORDER BY id LIMIT $1")
+                .bind(i64::from(limit))
+                .fetch_all(&*self.pool)
+                .await
+                .map_err(db_error)?;
+
+        Ok(rows.into_iter().map(QuarantinedEvent::from).collect())
+    }
+
+    async fn find(&self, id: &Pid) -> Result<Option<QuarantinedEvent>> {
+        let row: Option<QuarantineRow> =
+            sqlx::query_as("SELECT pid, handler, envelope, attempts, last_error, quarantined_at FROM event_quarantine WHERE pid = $1")
+                .bind(id.as_uuid())
+                .fetch_optional(&*self.pool)
+                .await
+                .map_err(db_error)?;
+
+        Ok(row.map(QuarantinedEvent::from))
+    }
+
+    async fn remove(&self, id: &Pid) -> Result<()> {
+        sqlx::query("DELETE FROM event_quarantine WHERE pid = $1")
+            .bind(id.as_uuid())
+            .execute(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use crate::domain::{
+    ledger_entry::{
+        entry::{LedgerEntry, LedgerEntryBuilder, LedgerEntryMetadata},
+        entry_type::LedgerEntryType,
+    },
+    stash::{
+        name::StashName,
+        stash::{Stash, StashBuilder, StashMetadata},
+        tag::Tag,
+    },
+};
+use shared::{
+    domain::value_objects::{asset::Asset, date::Date, mula::Mula, pid::Pid, stash_status::StashStatus, wallet_address::WalletAddress},
+    infrastructure::types::{Result, error::Error},
+};
+use sqlx::{
+    FromRow,
+    types::{Json, Uuid},
+};
+use std::str::FromStr;
+
+#[derive(FromRow)]
+pub(super) struct StashRow {
+    pub pid: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub status: String,
+    pub tags: Vec<String>,
+    pub metadata: Json<StashMetadata>,
+    pub created_at: Date,
+    pub updated_at: Date,
+    pub version: i64,
+}
+
+impl StashRow {
+    pub fn into_stash(self, balances: Vec<Mula>) -> Result<Stash> {
+        let tags = self
+            .tags
+            .iter()
+            .map(|t| Tag::from_str(t))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Error::ParseError)?;
+
+        StashBuilder::default()
+            .pid(self.pid)
+            .user_id(self.user_id)
+            .name(StashName::from_str(&self.name).map_err(|_| Error::ParseError)?)
+            .status(StashStatus::from_str(&self.status).map_err(|_| Error::ParseError)?)
+            .tags(tags)
+            .balances(balances)
+            .metadata(self.metadata.0)
+            .created_at(self.created_at)
+            .updated_at(self.updated_at)
+            .version(self.version as u64)
+            .build()
+            .map_err(|e| Error::BuilderError(e.to_string()))
+    }
+}
+
+/// Asset columns shared by `stash_balances` and `ledger_entries`
+#[derive(FromRow)]
+pub(super) struct AssetColumns {
+    pub asset_name: String,
+    pub asset_symbol: String,
+    pub asset_network: String,
+    pub asset_address: Option<String>,
+    pub asset_decimals: i16,
+    pub asset_display_decimals: i16,
+}
+
+impl AssetColumns {
+    fn into_mula(self, amount: &str) -> Result<Mula> {
+        let address = match self.asset_address {
+            Some(address) => Some(WalletAddress::from_str(&address).map_err(|_| Error::ParseError)?),
+            None => None,
+        };
+
+        let asset = Asset {
+            name: self.asset_name,
+            symbol: self.asset_symbol,
+            network: self.asset_network,
+            address,
+            decimals: u8::try_from(self.asset_decimals).map_err(|_| Error::ParseError)?,
+            display_decimals: u8::try_from(self.asset_display_decimals).map_err(|_| Error::ParseError)?,
+        };
+        let amount = amount.parse::<u128>().map_err(|_| Error::ParseError)?;
+
+        Ok(Mula::new(amount, &asset))
+    }
+}
+
+#[derive(FromRow)]
+pub(super) struct BalanceRow {
+    pub stash_id: Uuid,
+    pub amount: String,
+    #[sqlx(flatten)]
+    pub asset: AssetColumns,
+}
+
+impl BalanceRow {
+    pub fn into_mula(self) -> Result<Mula> {
+        self.asset.into_mula(&self.amount)
+    }
+}
+
+#[derive(FromRow)]
+pub(super) struct LedgerEntryRow {
+    pub pid: Uuid,
+    pub stash_id: Uuid,
+    pub entry_type: String,
+    pub amount: String,
+    #[sqlx(flatten)]
+    pub asset: AssetColumns,
+    pub upstream_ref_id: Uuid,
+    pub metadata: Json<LedgerEntryMetadata>,
+    pub created_at: Date,
+}
+
+impl TryFrom<LedgerEntryRow> for LedgerEntry {
+    type Error = Error;
+    fn try_from(row: LedgerEntryRow) -> Result<Self> {
+        LedgerEntryBuilder::default()
+            .pid(Pid::from(row.pid))
+            .stash_id(Pid::from(row.stash_id))
+            .entry_type(LedgerEntryType::from_str(&row.entry_type).map_err(|_| Error::ParseError)?)
+            .amount(row.asset.into_mula(&row.amount)?)
+            .upstream_ref_id(Pid::from(row.upstream_ref_id))
+            .metadata(row.metadata.0)
+            .created_at(row.created_at)
+            .build()
+            .map_err(|e| Error::BuilderError(e.to_string()))
+    }
+}
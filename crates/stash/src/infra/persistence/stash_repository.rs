@@ -1 +1,216 @@
+use crate::{
+    domain::{
+        ledger_entry::entry::LedgerEntry,
+        repositories::{FindManyStashQuery, StashCursorQuery, StashRepository},
+        stash::{name::StashName, stash::Stash},
+    },
+    infra::persistence::{
+        db_error,
+        ledger_repository::PgLedgerRepository,
+        offset,
+        outbox::PgOutbox,
+        rows::{BalanceRow, StashRow},
+    },
+};
+use async_trait::async_trait;
+use di::injectable;
+use shared::{
+    domain::value_objects::{mula::Mula, pid::Pid},
+    infrastructure::{
+        messaging::{envelope::EventEnvelope, outbox::RecordedEvent},
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+use sqlx::{
+    PgPool, Postgres, Transaction,
+    types::{Json, Uuid},
+};
+use std::{collections::HashMap, sync::Arc};
 
+const SELECT_STASH: &str = "SELECT pid, user_id, name, status, tags, metadata, created_at, updated_at, version FROM stashes";
+const SELECT_BALANCE: &str = "SELECT stash_id, amount::text AS amount, asset_name, asset_symbol, asset_network, asset_address, \
+     asset_decimals, asset_display_decimals FROM stash_balances";
+
+#[injectable(StashRepository)]
+pub struct PgStashRepository {
+    pool: Arc<PgPool>,
+}
+
+impl PgStashRepository {
+    async fn find_balances(&self, stash_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<Mula>>> {
+        let rows: Vec<BalanceRow> = sqlx::query_as(&format!("{SELECT_BALANCE} WHERE stash_id = ANY($1) ORDER BY asset_symbol, asset_network"))
+            .bind(stash_ids)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        let mut balances: HashMap<Uuid, Vec<Mula>> = HashMap::new();
+        for row in rows {
+            let stash_id = row.stash_id;
+            balances.entry(stash_id).or_default().push(row.into_mula()?);
+        }
+
+        Ok(balances)
+    }
+
+    async fn hydrate(&self, rows: Vec<StashRow>) -> Result<Vec<Stash>> {
+        let stash_ids = rows.iter().map(|r| r.pid).collect::<Vec<_>>();
+        let mut balances = self.find_balances(&stash_ids).await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let stash_balances = balances.remove(&row.pid).unwrap_or_default();
+                row.into_stash(stash_balances)
+            })
+            .collect()
+    }
+
+    /// inserts or updates `stash`, failing with `EntityConflict` when it was saved since it was loaded
+    async fn upsert(tx: &mut Transaction<'_, Postgres>, stash: &Stash) -> Result<()> {
+        let tags = stash.get_tags().iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        let saved = sqlx::query(
+            "INSERT INTO stashes (pid, user_id, name, status, tags, metadata, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+             ON CONFLICT (pid) DO UPDATE SET name = EXCLUDED.name, status = EXCLUDED.status, tags = EXCLUDED.tags, \
+             metadata = EXCLUDED.metadata, updated_at = EXCLUDED.updated_at, version = stashes.version + 1 \
+             WHERE stashes.version = $9",
+        )
+        .bind(stash.get_pid().as_uuid())
+        .bind(stash.get_user_id().as_uuid())
+        .bind(stash.get_name().to_string())
+        .bind(stash.get_status().as_str())
+        .bind(tags)
+        .bind(Json(stash.get_metadata()))
+        .bind(stash.get_created_at())
+        .bind(stash.get_updated_at())
+        .bind(stash.get_version() as i64)
+        .execute(&mut **tx)
+        .await
+        .map_err(db_error)?;
+
+        if saved.rows_affected() == 0 {
+            return Err(Error::DomainError(DomainError::EntityConflict));
+        }
+
+        Self::replace_balances(tx, stash).await
+    }
+
+    async fn replace_balances(tx: &mut Transaction<'_, Postgres>, stash: &Stash) -> Result<()> {
+        sqlx::query("DELETE FROM stash_balances WHERE stash_id = $1")
+            .bind(stash.get_pid().as_uuid())
+            .execute(&mut **tx)
+            .await
+            .map_err(db_error)?;
+
+        for balance in stash.get_balances() {
+            let asset = balance.get_asset();
+            sqlx::query(
+                "INSERT INTO stash_balances (stash_id, amount, asset_name, asset_symbol, asset_network, asset_address, asset_decimals, \
+                 asset_display_decimals) VALUES ($1, $2::numeric, $3, $4, $5, $6, $7, $8)",
+            )
+            .bind(stash.get_pid().as_uuid())
+            .bind(balance.get_amount().to_string())
+            .bind(&asset.name)
+            .bind(&asset.symbol)
+            .bind(&asset.network)
+            .bind(asset.address.as_ref().map(|a| a.to_string()))
+            .bind(i16::from(asset.decimals))
+            .bind(i16::from(asset.display_decimals))
+            .execute(&mut **tx)
+            .await
+            .map_err(db_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StashRepository for PgStashRepository {
+    async fn find_by_pid(&self, pid: &Pid) -> Result<Option<Stash>> {
+        let row: Option<StashRow> = sqlx::query_as(&format!("{SELECT_STASH} WHERE pid = $1"))
+            .bind(pid.as_uuid())
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        match row {
+            Some(row) => Ok(self.hydrate(vec![row]).await?.pop()),
+            None => Ok(None),
+        }
+    }
+
+    async fn find_many(&self, query: FindManyStashQuery) -> Result<Vec<Stash>> {
+        let rows: Vec<StashRow> = sqlx::query_as(&format!(
+            "{SELECT_STASH} WHERE ($1::uuid IS NULL OR user_id = $1) ORDER BY created_at, id LIMIT $2 OFFSET $3"
+        ))
+        .bind(query.user_id.as_ref().map(|id| id.as_uuid()))
+        .bind(i64::from(query.limit))
+        .bind(offset(query.page, query.limit))
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        self.hydrate(rows).await
+    }
+
+    async fn find_after(&self, query: StashCursorQuery) -> Result<Vec<Stash>> {
+        let rows: Vec<StashRow> = sqlx::query_as(&format!(
+            "{SELECT_STASH} WHERE user_id = $1 AND ($2::uuid IS NULL OR pid > $2) ORDER BY pid LIMIT $3"
+        ))
+        .bind(query.user_id.as_uuid())
+        .bind(query.after.as_ref().map(|id| id.as_uuid()))
+        .bind(i64::from(query.limit))
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        self.hydrate(rows).await
+    }
+
+    async fn exists_with_name_for_user(&self, user_id: &Pid, name: &StashName) -> Result<bool> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM stashes WHERE user_id = $1 AND name = $2)")
+            .bind(user_id.as_uuid())
+            .bind(name.to_string())
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(db_error)
+    }
+
+    async fn save(&self, stash: &Stash) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        Self::upsert(&mut tx, stash).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn save_with_ledger_entry(&self, stash: &Stash, entry: &LedgerEntry, events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        Self::upsert(&mut tx, stash).await?;
+        PgLedgerRepository::insert(&mut *tx, entry).await?;
+        PgOutbox::insert(&mut tx, &envelopes).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn save_with_events(&self, stash: &Stash, events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        Self::upsert(&mut tx, stash).await?;
+        PgOutbox::insert(&mut tx, &envelopes).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn save_all_with_events(&self, stashes: &[Stash], events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        for stash in stashes {
+            Self::upsert(&mut tx, stash).await?;
+        }
+        PgOutbox::insert(&mut tx, &envelopes).await?;
+        tx.commit().await.map_err(db_error)
+    }
+}
@@ -0,0 +1,368 @@
+use crate::utils::{
+    bootstrap::{bootstrap, bootstrap_with_dispatch},
+    prepare::prepare_stash,
+};
+use async_trait::async_trait;
+use shared::{
+    domain::{
+        events::{stash::StashStatusUpdatedEvent, user::UserStatusUpdatedEvent},
+        value_objects::{asset::Asset, mula::Mula, pid::Pid, stash_status::StashStatus, user_status::UserStatus},
+    },
+    infrastructure::{
+        messaging::{
+            EventBus, EventHandler, TypedEventHandler,
+            context::EventContext,
+            envelope::EventEnvelope,
+            event::DomainEvent,
+            memory::DispatchMode,
+            outbox::{Outbox, RecordedEvent},
+            workers::spawn_workers,
+        },
+        types::{Result, error::Error},
+    },
+};
+use stash::{
+    application::{
+        ledger::{LedgerService, command::WriteLedgerEntryCommand},
+        stash::{
+            StashService,
+            command::{CreateStashCommand, GetStashCommand, UpdateStashStatusCommand},
+        },
+    },
+    domain::{
+        events::{LedgerEntryCreatedEvent, StashBalanceDriftDetectedEvent, StashBalanceUpdatedEvent, StashClosureBlockedEvent, StashCreatedEvent},
+        ledger_entry::entry_type::LedgerEntryType,
+        reconciliation::BalanceDiscrepancy,
+        repositories::StashRepository,
+        stash::{name::StashName, stash::Stash},
+    },
+    infra::events::registry::event_registry,
+};
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+mod utils {
+    pub mod auth;
+    pub mod bootstrap;
+    pub mod prepare;
+    pub mod repositories;
+}
+
+#[tokio::test]
+async fn pauses_stash_when_user_is_suspended() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let stash_service = provider.get_required::<StashService>();
+    let stash = prepare_stash(&provider).await?;
+    let event = UserStatusUpdatedEvent::new(stash.get_user_id(), &UserStatus::Active, &UserStatus::Suspended);
+
+    // Act
+    event_bus.publish(event).await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    let command = GetStashCommand {
+        stash_id: stash.get_pid().to_owned(),
+    };
+    let stash = stash_service.get_stash(command).await?.unwrap();
+    assert!(failures.is_empty(), "handlers must not fail: {failures:?}");
+    assert_eq!(stash.get_status(), &StashStatus::PAUSED, "Stash status must be `PAUSED`");
+    let status_updated_event = StashStatusUpdatedEvent::new(stash.get_pid(), stash.get_user_id(), &StashStatus::ACTIVE, stash.get_status());
+    assert!(event_bus.published(status_updated_event).await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pauses_stash_when_user_is_suspended_with_spawned_dispatch() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_with_dispatch(DispatchMode::Spawned).await;
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let stash_service = provider.get_required::<StashService>();
+    let stash = prepare_stash(&provider).await?;
+    let event = UserStatusUpdatedEvent::new(stash.get_user_id(), &UserStatus::Active, &UserStatus::Suspended);
+
+    // Act
+    event_bus.publish(event).await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    let command = GetStashCommand {
+        stash_id: stash.get_pid().to_owned(),
+    };
+    let stash = stash_service.get_stash(command).await?.unwrap();
+    assert!(failures.is_empty(), "handlers must not fail: {failures:?}");
+    assert_eq!(stash.get_status(), &StashStatus::PAUSED, "Stash status must be `PAUSED`");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pauses_every_stash_when_user_has_more_than_one_page() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let stash_repo = provider.get_required::<dyn StashRepository>();
+    let stash_service = provider.get_required::<StashService>();
+    let user_id = Pid::new();
+    for i in 0..1050 {
+        let name = StashName::from_str(&format!("Stash {i}")).unwrap();
+        stash_repo.save(&Stash::new(&user_id, &name, &vec![])).await?;
+    }
+    let event = UserStatusUpdatedEvent::new(&user_id, &UserStatus::Active, &UserStatus::Suspended);
+
+    // Act
+    event_bus.publish(event).await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "handlers must not fail: {failures:?}");
+    let mut cursor = stash_service.get_user_stashes(&user_id, 400);
+    let mut stashes = vec![];
+    while let Some(page) = cursor.next_page().await? {
+        stashes.extend(page);
+    }
+    assert_eq!(stashes.len(), 1050, "cursor must return every stash of the user once");
+    let active = stashes.iter().filter(|s| s.get_status() != &StashStatus::PAUSED).count();
+    assert_eq!(active, 0, "stashes beyond the first page must be `PAUSED` too");
+
+    Ok(())
+}
+
+/// Records the stashes whose closure was blocked
+#[derive(Default)]
+struct ClosureBlockedRecorder {
+    stash_ids: Mutex<Vec<Pid>>,
+}
+
+#[async_trait]
+impl TypedEventHandler for ClosureBlockedRecorder {
+    type Event = StashClosureBlockedEvent;
+
+    async fn handle(&self, event: &StashClosureBlockedEvent) -> Result<()> {
+        self.stash_ids.lock().unwrap().push(event.aggregate_id());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn deleting_user_closes_empty_stashes_and_skips_funded_ones() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let recorder = Arc::new(ClosureBlockedRecorder::default());
+    event_bus.subscribe(recorder.clone()).await?;
+    let stash_service = provider.get_required::<StashService>();
+    let ledger_service = provider.get_required::<LedgerService>();
+    let user_id = Pid::new();
+    let create_stash = |name: &str| CreateStashCommand {
+        user_id: user_id.clone(),
+        name: StashName::from_str(name).unwrap(),
+        tags: vec![],
+    };
+    let funded = stash_service.create_stash(create_stash("Funded")).await?;
+    let empty = stash_service.create_stash(create_stash("Empty")).await?;
+    let credit = WriteLedgerEntryCommand {
+        stash_id: funded.get_pid().to_owned(),
+        entry_type: LedgerEntryType::CREDIT,
+        amount: Mula::new(10, &Asset::usdt()),
+        upstream_ref_id: Pid::new(),
+    };
+    ledger_service.write_ledger_entry(credit).await?;
+
+    // Act
+    let event = UserStatusUpdatedEvent::new(&user_id, &UserStatus::Active, &UserStatus::Deleted);
+    event_bus.publish(event).await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "a funded stash must not fail the handler: {failures:?}");
+    let funded = stash_service
+        .get_stash(GetStashCommand {
+            stash_id: funded.get_pid().to_owned(),
+        })
+        .await?
+        .unwrap();
+    let empty = stash_service
+        .get_stash(GetStashCommand {
+            stash_id: empty.get_pid().to_owned(),
+        })
+        .await?
+        .unwrap();
+    assert_eq!(funded.get_status(), &StashStatus::ACTIVE, "funded stash must stay open");
+    assert_eq!(empty.get_status(), &StashStatus::CLOSED, "empty stash must be closed");
+    let blocked = recorder.stash_ids.lock().unwrap().clone();
+    assert_eq!(blocked, vec![funded.get_pid().to_owned()], "funded stash must be recorded as blocked");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn closed_stash_stays_closed_when_user_is_reactivated() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let stash_service = provider.get_required::<StashService>();
+    let stash = prepare_stash(&provider).await?;
+    let command = UpdateStashStatusCommand {
+        stash_id: stash.get_pid().to_owned(),
+        new_status: StashStatus::CLOSED,
+    };
+    stash_service.update_stash_status(command).await?;
+    let event = UserStatusUpdatedEvent::new(stash.get_user_id(), &UserStatus::Suspended, &UserStatus::Active);
+
+    // Act
+    event_bus.publish(event).await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    let command = GetStashCommand {
+        stash_id: stash.get_pid().to_owned(),
+    };
+    let stash = stash_service.get_stash(command).await?.unwrap();
+    assert!(failures.is_empty(), "handlers must not fail: {failures:?}");
+    assert_eq!(stash.get_status(), &StashStatus::CLOSED, "Stash status must stay `CLOSED`");
+
+    Ok(())
+}
+
+/// Records the context of every stash status change it sees
+#[derive(Default)]
+struct StatusChangeRecorder {
+    contexts: Mutex<Vec<EventContext>>,
+}
+
+#[async_trait]
+impl TypedEventHandler for StatusChangeRecorder {
+    type Event = StashStatusUpdatedEvent;
+
+    async fn handle(&self, event: &StashStatusUpdatedEvent) -> Result<()> {
+        self.contexts.lock().unwrap().push(event.context().clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn links_stash_status_change_to_the_user_status_change_causing_it() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let recorder = Arc::new(StatusChangeRecorder::default());
+    event_bus.subscribe(recorder.clone()).await?;
+    let stash = prepare_stash(&provider).await?;
+    let event_id = Pid::new();
+
+    // Act
+    EventContext::correlated("request-1")
+        .scope(async {
+            let event = UserStatusUpdatedEvent::new(stash.get_user_id(), &UserStatus::Active, &UserStatus::Suspended);
+            event_bus.publish_with_id(&event_id, event).await
+        })
+        .await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "handlers must not fail: {failures:?}");
+    let contexts = recorder.contexts.lock().unwrap();
+    assert_eq!(
+        *contexts,
+        vec![EventContext {
+            correlation_id: Some("request-1".to_owned()),
+            causation_id: Some(event_id.to_string()),
+        }],
+        "stash status change must share the correlation and name its cause"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_mismatched_event_instead_of_panicking() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let handler = provider
+        .get_all::<dyn EventHandler>()
+        .find(|handler| handler.event_type() == "UserStatusUpdated")
+        .unwrap();
+    let event: Arc<dyn DomainEvent> = Arc::from(StashCreatedEvent::new(&Pid::new(), &Pid::new()) as Box<dyn DomainEvent>);
+
+    // Act
+    let result = handler.handle(event).await;
+
+    // Assert
+    assert!(
+        matches!(
+            &result,
+            Err(Error::EventMismatch { expected: "UserStatusUpdated", actual }) if actual == "StashCreated"
+        ),
+        "mismatched event must be rejected, got: {result:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn round_trips_every_stash_event_through_the_registry() -> Result<()> {
+    // Arrange
+    let registry = event_registry();
+    let stash_id = Pid::new();
+    let balance = Mula::new(u128::MAX, &Asset::usdt());
+    let discrepancy = BalanceDiscrepancy {
+        asset: Asset::usdt(),
+        recorded: balance.clone(),
+        computed: Mula::zero(&Asset::usdt()),
+        overdrawn_by: Some(Mula::new(1, &Asset::usdt())),
+    };
+    let events: Vec<Box<dyn DomainEvent>> = vec![
+        StashCreatedEvent::new(&stash_id, &Pid::new()),
+        StashStatusUpdatedEvent::new(&stash_id, &Pid::new(), &StashStatus::ACTIVE, &StashStatus::PAUSED),
+        StashBalanceUpdatedEvent::new(&stash_id, &balance),
+        LedgerEntryCreatedEvent::new(&stash_id, &Pid::new()),
+        StashBalanceDriftDetectedEvent::new(&stash_id, &[discrepancy], true),
+        StashClosureBlockedEvent::new(&stash_id, &Pid::new(), "funds left"),
+    ];
+
+    for event in events {
+        // Act
+        let envelope = EventEnvelope::seal(&*event)?;
+        let received: EventEnvelope = serde_json::from_str(&serde_json::to_string(&envelope).unwrap()).unwrap();
+        let decoded = registry.decode(&received)?;
+
+        // Assert
+        assert_eq!(decoded.event_type(), event.event_type());
+        assert_eq!(decoded.aggregate_id(), stash_id);
+        assert_eq!(decoded.payload().unwrap(), envelope.payload, "{} must round trip", event.event_type());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn spawned_workers_relay_events_left_in_the_outbox() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let outbox = provider.get_required::<dyn Outbox>();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let recorded = RecordedEvent::record(StashCreatedEvent::new(&Pid::new(), &Pid::new()))?;
+    outbox.append(&[recorded.envelope]).await?;
+
+    // Act
+    let workers = spawn_workers(&provider);
+    let relayed = tokio::time::timeout(Duration::from_secs(5), async {
+        while !outbox.pending(10).await.unwrap().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    workers.iter().for_each(|worker| worker.abort());
+
+    // Assert
+    assert!(relayed.is_ok(), "pending event must be relayed by the outbox worker");
+    assert!(event_bus.published(StashCreatedEvent::new(&Pid::new(), &Pid::new())).await);
+
+    Ok(())
+}
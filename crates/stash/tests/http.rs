@@ -0,0 +1,419 @@
+use crate::utils::bootstrap::bootstrap;
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{http::correlation::CORRELATION_ID, types::Result},
+};
+use stash::infra::http::StashApi;
+use tower::ServiceExt;
+
+mod utils {
+    pub mod auth;
+    pub mod bootstrap;
+    pub mod repositories;
+}
+
+async fn router() -> Router {
+    let provider = bootstrap().await;
+    provider.get_required::<StashApi>().router()
+}
+
+async fn send(router: &Router, user_id: Option<&Pid>, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    send_with_token(router, user_id.map(Pid::to_string), method, uri, body).await
+}
+
+/// writes a ledger entry the way the services moving funds do
+async fn write_entry(router: &Router, uri: &str, body: Value) -> (StatusCode, Value) {
+    send_with_token(router, Some("service:payments".to_owned()), Method::POST, uri, Some(body)).await
+}
+
+async fn send_with_token(router: &Router, token: Option<String>, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder().method(method).uri(uri).header("content-type", "application/json");
+    let request = match token {
+        Some(token) => request.header("authorization", format!("Bearer {token}")),
+        None => request,
+    };
+    let request = match body {
+        Some(body) => request.body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, body)
+}
+
+async fn create_stash(router: &Router, user_id: &Pid) -> Value {
+    let body = json!({ "name": "General", "tags": ["personal"] });
+    let (status, stash) = send(router, Some(user_id), Method::POST, "/stashes", Some(body)).await;
+    assert_eq!(status, StatusCode::CREATED, "stash must be created: {stash}");
+    stash
+}
+
+fn usdt() -> Value {
+    json!({ "symbol": "USDT", "network": "ethereum", "decimals": 18 })
+}
+
+#[tokio::test]
+async fn can_create_and_get_stash() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let user_id = Pid::new();
+
+    // Act
+    let stash = create_stash(&router, &user_id).await;
+    let uri = format!("/stashes/{}", stash["id"].as_str().unwrap());
+    let (status, fetched) = send(&router, Some(&user_id), Method::GET, &uri, None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched["user_id"], user_id.to_string(), "user id must match");
+    assert_eq!(fetched["name"], "General", "name must match");
+    assert_eq!(fetched["status"], "ACTIVE", "status must be `ACTIVE`");
+    assert_eq!(fetched["tags"], json!(["personal"]), "tags must match");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_list_stashes_of_user() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let user_id = Pid::new();
+    create_stash(&router, &user_id).await;
+    create_stash(&router, &Pid::new()).await;
+
+    // Act
+    let (status, stashes) = send(&router, Some(&user_id), Method::GET, "/stashes?page=1&limit=10", None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    let stashes = stashes.as_array().unwrap();
+    assert_eq!(stashes.len(), 1, "only the user's stash must be listed");
+    assert_eq!(stashes[0]["user_id"], user_id.to_string());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_stash_is_not_found() -> Result<()> {
+    // Arrange
+    let router = router().await;
+
+    // Act
+    let uri = format!("/stashes/{}", Pid::new().to_string());
+    let (status, body) = send(&router, Some(&Pid::new()), Method::GET, &uri, None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "entity_not_found");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn duplicate_stash_name_is_rejected() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let user_id = Pid::new();
+    create_stash(&router, &user_id).await;
+
+    // Act
+    let (status, body) = send(&router, Some(&user_id), Method::POST, "/stashes", Some(json!({ "name": "General" }))).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "entity_already_exist");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_change_stash_status() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let user_id = Pid::new();
+    let stash = create_stash(&router, &user_id).await;
+    let uri = format!("/stashes/{}/status", stash["id"].as_str().unwrap());
+
+    // Act
+    let (closed_status, closed) = send(&router, Some(&user_id), Method::PATCH, &uri, Some(json!({ "status": "CLOSED" }))).await;
+    let (reopen_status, reopen) = send(&router, Some(&user_id), Method::PATCH, &uri, Some(json!({ "status": "ACTIVE" }))).await;
+
+    // Assert
+    assert_eq!(closed_status, StatusCode::OK);
+    assert_eq!(closed["status"], "CLOSED", "status must be `CLOSED`");
+    assert_eq!(reopen_status, StatusCode::CONFLICT, "closed stash must not be reopened");
+    assert_eq!(reopen["code"], "invalid_state");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_write_and_read_ledger_entries() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let user_id = Pid::new();
+    let stash = create_stash(&router, &user_id).await;
+    let stash_id = stash["id"].as_str().unwrap();
+    let uri = format!("/stashes/{stash_id}/ledger");
+    let body = json!({
+        "entry_type": "CREDIT",
+        "amount": "12.5",
+        "asset": usdt(),
+        "upstream_ref_id": Pid::new().to_string()
+    });
+
+    // Act
+    let user = Some(&user_id);
+    let (write_status, entry) = write_entry(&router, &uri, body).await;
+    let (list_status, entries) = send(&router, user, Method::GET, &format!("{uri}?entry_type=CREDIT"), None).await;
+    let (get_status, fetched) = send(&router, user, Method::GET, &format!("/ledger/{}", entry["id"].as_str().unwrap()), None).await;
+    let (_, stash) = send(&router, user, Method::GET, &format!("/stashes/{stash_id}"), None).await;
+
+    // Assert
+    assert_eq!(write_status, StatusCode::CREATED);
+    assert_eq!(entry["amount"]["amount"], "12500000000000000000", "amount must be in the smallest unit");
+    assert_eq!(entry["amount"]["formatted"], "12.5000000000 USDT");
+    assert_eq!(list_status, StatusCode::OK);
+    assert_eq!(entries.as_array().unwrap().len(), 1, "entry must be listed");
+    assert_eq!(get_status, StatusCode::OK);
+    assert_eq!(fetched["id"], entry["id"]);
+    assert_eq!(stash["balances"][0]["amount"], "12500000000000000000", "balance must follow the ledger");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn overdrawing_debit_is_rejected() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let user_id = Pid::new();
+    let stash = create_stash(&router, &user_id).await;
+    let uri = format!("/stashes/{}/ledger", stash["id"].as_str().unwrap());
+    let body = json!({
+        "entry_type": "DEBIT",
+        "amount": "1",
+        "asset": usdt(),
+        "upstream_ref_id": Pid::new().to_string()
+    });
+
+    // Act
+    let (status, body) = write_entry(&router, &uri, body).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "assertion_failed");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unregistered_asset_or_mismatching_decimals_are_rejected() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let user_id = Pid::new();
+    let stash = create_stash(&router, &user_id).await;
+    let uri = format!("/stashes/{}/ledger", stash["id"].as_str().unwrap());
+    let credit = |asset: Value| {
+        json!({
+            "entry_type": "CREDIT",
+            "amount": "1",
+            "asset": asset,
+            "upstream_ref_id": Pid::new().to_string()
+        })
+    };
+
+    // Act
+    let unknown = credit(json!({ "symbol": "USDT", "network": "tron" }));
+    let (unknown_status, unknown) = write_entry(&router, &uri, unknown).await;
+    let mismatching = credit(json!({ "symbol": "USDT", "network": "ethereum", "decimals": 6 }));
+    let (mismatching_status, mismatching) = write_entry(&router, &uri, mismatching).await;
+
+    // Assert
+    assert_eq!(
+        unknown_status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "unregistered asset must be rejected: {unknown}"
+    );
+    assert_eq!(
+        mismatching_status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "decimals must match the registered asset: {mismatching}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn users_can_not_write_ledger_entries() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let user_id = Pid::new();
+    let stash = create_stash(&router, &user_id).await;
+    let stash_id = stash["id"].as_str().unwrap();
+    let body = json!({
+        "entry_type": "CREDIT",
+        "amount": "1000",
+        "asset": usdt(),
+        "upstream_ref_id": Pid::new().to_string()
+    });
+
+    // Act
+    let (status, _) = send(&router, Some(&user_id), Method::POST, &format!("/stashes/{stash_id}/ledger"), Some(body)).await;
+    let (_, stash) = send(&router, Some(&user_id), Method::GET, &format!("/stashes/{stash_id}"), None).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::UNAUTHORIZED, "a user token must not write ledger entries");
+    assert_eq!(stash["balances"], json!([]), "balance must not change: {stash}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn listings_are_capped_at_the_max_page_size() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let user_id = Pid::new();
+    let stash = create_stash(&router, &user_id).await;
+    let uri = format!("/stashes/{}/ledger", stash["id"].as_str().unwrap());
+    for _ in 0..101 {
+        let body = json!({
+            "entry_type": "CREDIT",
+            "amount": "1",
+            "asset": usdt(),
+            "upstream_ref_id": Pid::new().to_string()
+        });
+        let (status, entry) = write_entry(&router, &uri, body).await;
+        assert_eq!(status, StatusCode::CREATED, "entry must be written: {entry}");
+    }
+
+    // Act
+    let (oversized_status, oversized) = send(&router, Some(&user_id), Method::GET, &format!("{uri}?limit=60000"), None).await;
+    let (empty_status, empty) = send(&router, Some(&user_id), Method::GET, &format!("{uri}?limit=0"), None).await;
+
+    // Assert
+    assert_eq!(oversized_status, StatusCode::OK);
+    assert_eq!(oversized.as_array().unwrap().len(), 100, "page must be capped");
+    assert_eq!(empty_status, StatusCode::OK);
+    assert_eq!(empty.as_array().unwrap().len(), 1, "page must hold at least one entry");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn requests_without_a_token_are_unauthorized() -> Result<()> {
+    // Arrange
+    let router = router().await;
+
+    // Act
+    let (list_status, _) = send(&router, None, Method::GET, "/stashes", None).await;
+    let (create_status, _) = send(&router, None, Method::POST, "/stashes", Some(json!({ "name": "General" }))).await;
+
+    // Assert
+    assert_eq!(list_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(create_status, StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stashes_of_other_users_are_not_found() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let owner = Pid::new();
+    let intruder = Pid::new();
+    let stash = create_stash(&router, &owner).await;
+    let stash_id = stash["id"].as_str().unwrap();
+
+    // Act
+    let (get_status, _) = send(&router, Some(&intruder), Method::GET, &format!("/stashes/{stash_id}"), None).await;
+    let (list_status, listed) = send(&router, Some(&intruder), Method::GET, "/stashes", None).await;
+    let status = Some(json!({ "status": "CLOSED" }));
+    let (patch_status, _) = send(&router, Some(&intruder), Method::PATCH, &format!("/stashes/{stash_id}/status"), status).await;
+    let (ledger_status, _) = send(&router, Some(&intruder), Method::GET, &format!("/stashes/{stash_id}/ledger"), None).await;
+
+    // Assert
+    assert_eq!(get_status, StatusCode::NOT_FOUND);
+    assert_eq!(list_status, StatusCode::OK);
+    assert!(listed.as_array().unwrap().is_empty(), "other users' stashes must not be listed");
+    assert_eq!(patch_status, StatusCode::NOT_FOUND);
+    assert_eq!(ledger_status, StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn malformed_requests_are_answered_as_json() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let user_id = Pid::new();
+
+    // Act
+    let (path_status, path) = send(&router, Some(&user_id), Method::GET, "/stashes/not-a-pid", None).await;
+    let (body_status, body) = send(&router, Some(&user_id), Method::POST, "/stashes", Some(json!({ "tags": 1 }))).await;
+
+    // Assert
+    assert_eq!(path_status, StatusCode::BAD_REQUEST);
+    assert_eq!(path["code"], "invalid_request");
+    assert_eq!(body_status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_request");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn echoes_correlation_id_or_starts_one() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let uri = format!("/stashes/{}", Pid::new().to_string());
+    let correlated = Request::get(&uri).header(&CORRELATION_ID, "request-1").body(Body::empty()).unwrap();
+    let uncorrelated = Request::get(&uri).body(Body::empty()).unwrap();
+
+    // Act
+    let correlated = router.clone().oneshot(correlated).await.unwrap();
+    let uncorrelated = router.oneshot(uncorrelated).await.unwrap();
+
+    // Assert
+    assert_eq!(
+        correlated.headers()[&CORRELATION_ID],
+        "request-1",
+        "caller's correlation id must be echoed"
+    );
+    let started = uncorrelated.headers().get(&CORRELATION_ID).expect("a correlation id must be started");
+    assert!(!started.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn replaces_invalid_correlation_id() -> Result<()> {
+    // Arrange
+    let router = router().await;
+    let uri = format!("/stashes/{}", Pid::new().to_string());
+    let oversized = "a".repeat(129);
+    let invalid = ["request 1", "request/1", "request;1", oversized.as_str()];
+
+    for correlation_id in invalid {
+        let request = Request::get(&uri).header(&CORRELATION_ID, correlation_id).body(Body::empty()).unwrap();
+
+        // Act
+        let response = router.clone().oneshot(request).await.unwrap();
+
+        // Assert
+        let started = response.headers().get(&CORRELATION_ID).expect("a correlation id must be started");
+        assert_ne!(started, correlation_id, "invalid correlation id must not be echoed");
+        assert!(!started.is_empty());
+    }
+
+    Ok(())
+}
@@ -1,17 +1,46 @@
-use crate::utils::bootstrap::bootstrap;
+use crate::utils::{bootstrap::bootstrap, prepare::prepare_stash};
 use insta::{assert_debug_snapshot, with_settings};
 use shared::{
     configure_insta,
-    domain::value_objects::{asset::Asset, mula::Mula, pid::Pid},
-    infrastructure::{messaging::EventBus, types::Result},
+    domain::value_objects::{asset::Asset, mula::Mula, pid::Pid, stash_status::StashStatus},
+    infrastructure::{
+        messaging::EventBus,
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
     testing::insta_filters::redactions::cleanup_model_generics,
 };
 use stash::{
-    application::ledger::{LedgerService, command::WriteLedgerEntryCommand},
-    domain::{events::LedgerEntryCreatedEvent, ledger_entry::entry_type::LedgerEntryType},
+    application::{
+        ledger::{LedgerService, command::WriteLedgerEntryCommand},
+        stash::{
+            StashService,
+            command::{GetStashCommand, UpdateStashStatusCommand},
+        },
+    },
+    domain::{
+        events::{LedgerEntryCreatedEvent, StashBalanceUpdatedEvent},
+        ledger_entry::entry_type::LedgerEntryType,
+    },
 };
 
-mod utils;
+mod utils {
+    pub mod auth;
+    pub mod bootstrap;
+    pub mod prepare;
+    pub mod repositories;
+}
+
+fn write_command(stash_id: &Pid, entry_type: LedgerEntryType, amount: u128) -> WriteLedgerEntryCommand {
+    WriteLedgerEntryCommand {
+        stash_id: stash_id.clone(),
+        entry_type,
+        amount: Mula::new(amount, &Asset::usdt()),
+        upstream_ref_id: Pid::new(),
+    }
+}
 
 #[tokio::test]
 async fn can_write_ledger_entry() -> Result<()> {
@@ -20,7 +49,8 @@ async fn can_write_ledger_entry() -> Result<()> {
     let provider = bootstrap().await;
     let ledger_service = provider.get_required::<LedgerService>();
     let event_bus = provider.get_required::<dyn EventBus>();
-    let stash_id = Pid::new();
+    let stash = prepare_stash(&provider).await?;
+    let stash_id = stash.get_pid().to_owned();
     let amount = Mula::new(10, &Asset::usdt());
     let upstream_ref_id = Pid::new();
     let command = WriteLedgerEntryCommand {
@@ -49,3 +79,175 @@ async fn can_write_ledger_entry() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn ledger_entries_move_stash_balance() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let ledger_service = provider.get_required::<LedgerService>();
+    let stash_service = provider.get_required::<StashService>();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let stash = prepare_stash(&provider).await?;
+
+    // Act
+    ledger_service
+        .write_ledger_entry(write_command(stash.get_pid(), LedgerEntryType::CREDIT, 10))
+        .await?;
+    ledger_service
+        .write_ledger_entry(write_command(stash.get_pid(), LedgerEntryType::DEBIT, 4))
+        .await?;
+
+    // Assert
+    let command = GetStashCommand {
+        stash_id: stash.get_pid().to_owned(),
+    };
+    let stash = stash_service.get_stash(command).await?.unwrap();
+    let expected_balance = Mula::new(6, &Asset::usdt());
+    assert_eq!(
+        stash.get_balance(&Asset::usdt()),
+        expected_balance,
+        "balance must be credits minus debits"
+    );
+    let balance_event = StashBalanceUpdatedEvent::new(stash.get_pid(), &expected_balance);
+    assert!(event_bus.published(balance_event).await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cannot_overdraw_stash() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let ledger_service = provider.get_required::<LedgerService>();
+    let stash_service = provider.get_required::<StashService>();
+    let stash = prepare_stash(&provider).await?;
+    ledger_service
+        .write_ledger_entry(write_command(stash.get_pid(), LedgerEntryType::CREDIT, 10))
+        .await?;
+
+    // Act
+    let result = ledger_service
+        .write_ledger_entry(write_command(stash.get_pid(), LedgerEntryType::DEBIT, 11))
+        .await;
+
+    // Assert
+    let command = GetStashCommand {
+        stash_id: stash.get_pid().to_owned(),
+    };
+    let stash = stash_service.get_stash(command).await?.unwrap();
+    assert!(matches!(result, Err(Error::AssertError(_))), "overdrawing debit must be rejected");
+    assert_eq!(
+        stash.get_balance(&Asset::usdt()),
+        Mula::new(10, &Asset::usdt()),
+        "balance must be untouched"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cannot_write_ledger_entry_to_inactive_stash() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let ledger_service = provider.get_required::<LedgerService>();
+    let stash_service = provider.get_required::<StashService>();
+    let stash = prepare_stash(&provider).await?;
+    let command = UpdateStashStatusCommand {
+        stash_id: stash.get_pid().to_owned(),
+        new_status: StashStatus::PAUSED,
+    };
+    stash_service.update_stash_status(command).await?;
+
+    // Act
+    let result = ledger_service
+        .write_ledger_entry(write_command(stash.get_pid(), LedgerEntryType::CREDIT, 10))
+        .await;
+
+    // Assert
+    assert!(matches!(result, Err(Error::AssertError(_))), "paused stash must reject ledger entries");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn replayed_ledger_entry_is_written_once() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let ledger_service = provider.get_required::<LedgerService>();
+    let stash_service = provider.get_required::<StashService>();
+    let stash = prepare_stash(&provider).await?;
+    let command = write_command(stash.get_pid(), LedgerEntryType::CREDIT, 10);
+    let upstream_ref_id = command.upstream_ref_id.clone();
+    let entry = ledger_service.write_ledger_entry(command).await?;
+
+    // Act
+    let replay = WriteLedgerEntryCommand {
+        upstream_ref_id,
+        ..write_command(stash.get_pid(), LedgerEntryType::CREDIT, 10)
+    };
+    let replayed_entry = ledger_service.write_ledger_entry(replay).await?;
+
+    // Assert
+    let command = GetStashCommand {
+        stash_id: stash.get_pid().to_owned(),
+    };
+    let stash = stash_service.get_stash(command).await?.unwrap();
+    assert_eq!(replayed_entry.get_pid(), entry.get_pid(), "replay must return the existing entry");
+    assert_eq!(stash.get_balance(&Asset::usdt()), Mula::new(10, &Asset::usdt()), "balance must move once");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn replayed_ledger_entry_with_different_amount_conflicts() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let ledger_service = provider.get_required::<LedgerService>();
+    let stash = prepare_stash(&provider).await?;
+    let command = write_command(stash.get_pid(), LedgerEntryType::CREDIT, 10);
+    let upstream_ref_id = command.upstream_ref_id.clone();
+    ledger_service.write_ledger_entry(command).await?;
+
+    // Act
+    let replay = WriteLedgerEntryCommand {
+        upstream_ref_id,
+        ..write_command(stash.get_pid(), LedgerEntryType::CREDIT, 11)
+    };
+    let result = ledger_service.write_ledger_entry(replay).await;
+
+    // Assert
+    assert!(
+        matches!(result, Err(Error::DomainError(DomainError::EntityConflict))),
+        "replay with a different amount must conflict"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_credits_are_all_applied() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let ledger_service = provider.get_required::<LedgerService>();
+    let stash_service = provider.get_required::<StashService>();
+    let stash = prepare_stash(&provider).await?;
+
+    // Act
+    let writes = (0..8).map(|_| {
+        let ledger_service = ledger_service.clone();
+        let command = write_command(stash.get_pid(), LedgerEntryType::CREDIT, 5);
+        tokio::spawn(async move { ledger_service.write_ledger_entry(command).await })
+    });
+    for write in writes.collect::<Vec<_>>() {
+        write.await.unwrap()?;
+    }
+
+    // Assert
+    let command = GetStashCommand {
+        stash_id: stash.get_pid().to_owned(),
+    };
+    let stash = stash_service.get_stash(command).await?.unwrap();
+    assert_eq!(stash.get_balance(&Asset::usdt()), Mula::new(40, &Asset::usdt()), "no credit may be lost");
+
+    Ok(())
+}
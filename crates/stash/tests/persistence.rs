@@ -0,0 +1,369 @@
+use crate::utils::database::bootstrap_postgres;
+use shared::{
+    domain::value_objects::{asset::Asset, mula::Mula, pid::Pid, stash_status::StashStatus},
+    infrastructure::{
+        messaging::{
+            envelope::EventEnvelope,
+            outbox::{Outbox, OutboxRelay, RecordedEvent},
+            quarantine::{QuarantineStore, QuarantinedEvent},
+        },
+        types::{Result, error::Error},
+    },
+};
+use stash::{
+    application::{
+        ledger::{
+            LedgerService,
+            command::{ReadLedgerEntriesCommand, ReadLedgerEntryCommand, WriteLedgerEntryCommand},
+        },
+        stash::{
+            StashService,
+            command::{CreateStashCommand, GetStashCommand, GetStashesCommand, UpdateUserStashesStatusCommand},
+        },
+    },
+    domain::{
+        events::StashCreatedEvent,
+        ledger_entry::entry_type::LedgerEntryType,
+        repositories::StashRepository,
+        stash::{name::StashName, stash::Stash, tag::Tag},
+    },
+};
+use std::str::FromStr;
+
+mod utils {
+    pub mod database;
+}
+
+fn create_stash_command(user_id: &Pid, name: &str) -> CreateStashCommand {
+    CreateStashCommand {
+        user_id: user_id.clone(),
+        name: StashName::from_str(name).unwrap(),
+        tags: vec![Tag::from_str("personal").unwrap(), Tag::from_str("savings").unwrap()],
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs a postgres database at DATABASE_URL"]
+async fn can_save_and_find_stash_in_postgres() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_postgres().await;
+    let stash_service = provider.get_required::<StashService>();
+    let stash = stash_service.create_stash(create_stash_command(&Pid::new(), "General")).await?;
+    let ledger_service = provider.get_required::<LedgerService>();
+    let balance = Mula::new(u128::MAX, &Asset::usdt());
+    let command = WriteLedgerEntryCommand {
+        stash_id: stash.get_pid().to_owned(),
+        entry_type: LedgerEntryType::CREDIT,
+        amount: balance.clone(),
+        upstream_ref_id: Pid::new(),
+    };
+    ledger_service.write_ledger_entry(command).await?;
+
+    // Act
+    let command = GetStashCommand {
+        stash_id: stash.get_pid().to_owned(),
+    };
+    let found = stash_service.get_stash(command).await?.unwrap();
+
+    // Assert
+    assert_eq!(found.get_user_id(), stash.get_user_id(), "user id must match");
+    assert_eq!(found.get_name(), stash.get_name(), "name must match");
+    assert_eq!(found.get_status(), &StashStatus::ACTIVE, "status must be `ACTIVE`");
+    assert_eq!(found.get_tags().len(), 2, "tags must be persisted");
+    assert_eq!(found.get_balances(), &vec![balance], "balances must be persisted");
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a postgres database at DATABASE_URL"]
+async fn can_paginate_stashes_of_user_in_postgres() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_postgres().await;
+    let stash_service = provider.get_required::<StashService>();
+    let user_id = Pid::new();
+    for name in ["First", "Second", "Third"] {
+        stash_service.create_stash(create_stash_command(&user_id, name)).await?;
+    }
+    stash_service.create_stash(create_stash_command(&Pid::new(), "Other")).await?;
+
+    // Act
+    let first_page = stash_service
+        .get_stashes(GetStashesCommand {
+            user_id: Some(user_id.clone()),
+            page: 1,
+            limit: Some(2),
+        })
+        .await?;
+    let second_page = stash_service
+        .get_stashes(GetStashesCommand {
+            user_id: Some(user_id.clone()),
+            page: 2,
+            limit: Some(2),
+        })
+        .await?;
+
+    // Assert
+    let names = first_page
+        .iter()
+        .chain(second_page.iter())
+        .map(|s| s.get_name().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(first_page.len(), 2, "first page must be full");
+    assert_eq!(
+        names,
+        vec!["First", "Second", "Third"],
+        "pages must hold every stash of the user in order"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a postgres database at DATABASE_URL"]
+async fn updates_every_stash_of_user_page_by_page_in_postgres() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_postgres().await;
+    let stash_service = provider.get_required::<StashService>();
+    let user_id = Pid::new();
+    for name in ["First", "Second", "Third"] {
+        stash_service.create_stash(create_stash_command(&user_id, name)).await?;
+    }
+    let other = stash_service.create_stash(create_stash_command(&Pid::new(), "Other")).await?;
+
+    // Act
+    let command = UpdateUserStashesStatusCommand {
+        user_id: user_id.clone(),
+        new_status: StashStatus::PAUSED,
+        page_size: 2,
+    };
+    let run = stash_service.update_user_stashes_status(command).await?;
+
+    // Assert
+    assert_eq!(run.updated, 3, "every stash of the user must be updated");
+    let mut cursor = stash_service.get_user_stashes(&user_id, 2);
+    let mut stashes = vec![];
+    while let Some(page) = cursor.next_page().await? {
+        stashes.extend(page);
+    }
+    assert_eq!(stashes.len(), 3, "cursor must return every stash of the user once");
+    assert!(stashes.iter().all(|s| s.get_status() == &StashStatus::PAUSED), "stashes must be `PAUSED`");
+    let command = GetStashCommand {
+        stash_id: other.get_pid().to_owned(),
+    };
+    let other = stash_service.get_stash(command).await?.unwrap();
+    assert_eq!(other.get_status(), &StashStatus::ACTIVE, "other users' stashes must be left alone");
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a postgres database at DATABASE_URL"]
+async fn can_save_and_filter_ledger_entries_in_postgres() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_postgres().await;
+    let stash_service = provider.get_required::<StashService>();
+    let ledger_service = provider.get_required::<LedgerService>();
+    let user_id = Pid::new();
+    let stash = stash_service.create_stash(create_stash_command(&user_id, "General")).await?;
+    let amount = Mula::new(10, &Asset::usdt());
+    let mut written = vec![];
+    for entry_type in [LedgerEntryType::CREDIT, LedgerEntryType::DEBIT, LedgerEntryType::CREDIT] {
+        let command = WriteLedgerEntryCommand {
+            stash_id: stash.get_pid().to_owned(),
+            entry_type,
+            amount: amount.clone(),
+            upstream_ref_id: Pid::new(),
+        };
+        written.push(ledger_service.write_ledger_entry(command).await?);
+    }
+
+    // Act
+    let command = ReadLedgerEntryCommand {
+        entry_id: written[0].get_pid().to_owned(),
+    };
+    let entry = ledger_service.read_ledger_entry(command).await?.unwrap();
+    let credits = ledger_service
+        .read_ledger_entries(ReadLedgerEntriesCommand {
+            user_id: Some(user_id.clone()),
+            stash_id: None,
+            entry_type: Some(LedgerEntryType::CREDIT),
+            page: 1,
+            limit: None,
+        })
+        .await?;
+
+    // Assert
+    assert_eq!(entry.get_stash_id(), stash.get_pid(), "stash id must match");
+    assert_eq!(entry.get_amount(), &amount, "amount must match");
+    assert_eq!(
+        entry.get_upstream_ref_id(),
+        written[0].get_upstream_ref_id(),
+        "upstream_ref_id must match"
+    );
+    assert_eq!(credits.len(), 2, "only the user's credits must be returned");
+    let stash = stash_service
+        .get_stash(GetStashCommand {
+            stash_id: stash.get_pid().to_owned(),
+        })
+        .await?
+        .unwrap();
+    assert_eq!(stash.get_balance(&Asset::usdt()), amount, "balance must be persisted with the entries");
+    assert!(credits.iter().all(|e| e.get_type() == &LedgerEntryType::CREDIT));
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a postgres database at DATABASE_URL"]
+async fn relays_stash_events_from_postgres_outbox() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_postgres().await;
+    let outbox = provider.get_required::<dyn Outbox>();
+    let relay = provider.get_required::<OutboxRelay>();
+    let stash = Stash::new(&Pid::new(), &StashName::from_str("Outbox").unwrap(), &vec![]);
+    let recorded = RecordedEvent::record(StashCreatedEvent::new(stash.get_pid(), stash.get_user_id()))?;
+    let event_id = recorded.envelope.event_id.clone();
+
+    // Act
+    provider
+        .get_required::<dyn StashRepository>()
+        .save_with_events(&stash, &[recorded])
+        .await?;
+    let stored = outbox.pending(1000).await?;
+    relay.relay_pending(1000).await?;
+
+    // Assert
+    assert!(
+        stored.iter().any(|envelope| envelope.event_id == event_id),
+        "event must be stored with the stash"
+    );
+    let pending = outbox.pending(1000).await?;
+    assert!(
+        !pending.iter().any(|envelope| envelope.event_id == event_id),
+        "relayed event must be marked published"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a postgres database at DATABASE_URL"]
+async fn sets_aside_undecodable_events_in_postgres_outbox() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_postgres().await;
+    let outbox = provider.get_required::<dyn Outbox>();
+    let relay = provider.get_required::<OutboxRelay>();
+    let mut poison = RecordedEvent::record(StashCreatedEvent::new(&Pid::new(), &Pid::new()))?.envelope;
+    poison.event_type = "Unknown".to_owned();
+    let good = RecordedEvent::record(StashCreatedEvent::new(&Pid::new(), &Pid::new()))?.envelope;
+    let ids = [poison.event_id.clone(), good.event_id.clone()];
+    outbox.append(&[poison, good]).await?;
+
+    // Act
+    relay.relay_pending(1000).await?;
+
+    // Assert
+    let pending = outbox.pending(1000).await?;
+    assert!(
+        !pending.iter().any(|envelope| ids.contains(&envelope.event_id)),
+        "undecodable event must be set aside and the one behind it relayed"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a postgres database at DATABASE_URL"]
+async fn stores_ledger_events_with_the_entry_in_postgres() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_postgres().await;
+    let stash_service = provider.get_required::<StashService>();
+    let ledger_service = provider.get_required::<LedgerService>();
+    let stash = stash_service.create_stash(create_stash_command(&Pid::new(), "Ledger events")).await?;
+    let command = WriteLedgerEntryCommand {
+        stash_id: stash.get_pid().to_owned(),
+        entry_type: LedgerEntryType::CREDIT,
+        amount: Mula::new(10, &Asset::usdt()),
+        upstream_ref_id: Pid::new(),
+    };
+
+    // Act
+    ledger_service.write_ledger_entry(command).await?;
+
+    // Assert
+    let event_types: Vec<String> = sqlx::query_scalar("SELECT event_type FROM event_outbox WHERE aggregate_id = $1 ORDER BY id")
+        .bind(stash.get_pid().as_uuid())
+        .fetch_all(&*provider.get_required::<sqlx::PgPool>())
+        .await
+        .unwrap();
+    assert_eq!(
+        event_types,
+        vec!["StashCreated", "LedgerEntryCreated", "StashBalanceUpdated"],
+        "ledger events must be stored with the entry"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore = "needs a postgres database at DATABASE_URL"]
+async fn quarantines_events_in_postgres() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_postgres().await;
+    let quarantine = provider.get_required::<dyn QuarantineStore>();
+    let envelope = EventEnvelope::seal(&*StashCreatedEvent::new(&Pid::new(), &Pid::new()))?;
+    let mut quarantined = QuarantinedEvent::new("OnStashCreated", envelope, 3, &Error::ServiceError);
+
+    // Act
+    quarantine.put(&quarantined).await?;
+    quarantined.attempts += 1;
+    quarantined.last_error = "still failing".to_owned();
+    quarantine.put(&quarantined).await?;
+    let found = quarantine.find(&quarantined.pid).await?;
+    quarantine.remove(&quarantined.pid).await?;
+
+    // Assert
+    let found = found.expect("quarantined event must be stored");
+    assert_eq!(found.envelope, quarantined.envelope, "envelope must survive the store");
+    assert_eq!(found.handler, "OnStashCreated");
+    assert_eq!(found.attempts, 4, "put must update a quarantined event");
+    assert_eq!(found.last_error, "still failing");
+    assert!(quarantine.find(&quarantined.pid).await?.is_none(), "removed event must be gone");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[ignore = "needs a postgres database at DATABASE_URL"]
+async fn concurrent_credits_are_all_applied_in_postgres() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_postgres().await;
+    let ledger_service = provider.get_required::<LedgerService>();
+    let stash_service = provider.get_required::<StashService>();
+    let stash = stash_service.create_stash(create_stash_command(&Pid::new(), "Concurrent")).await?;
+
+    // Act
+    let writes = (0..8).map(|_| {
+        let ledger_service = ledger_service.clone();
+        let command = WriteLedgerEntryCommand {
+            stash_id: stash.get_pid().to_owned(),
+            entry_type: LedgerEntryType::CREDIT,
+            amount: Mula::new(5, &Asset::usdt()),
+            upstream_ref_id: Pid::new(),
+        };
+        tokio::spawn(async move { ledger_service.write_ledger_entry(command).await })
+    });
+    for write in writes.collect::<Vec<_>>() {
+        write.await.unwrap()?;
+    }
+
+    // Assert
+    let command = GetStashCommand {
+        stash_id: stash.get_pid().to_owned(),
+    };
+    let stash = stash_service.get_stash(command).await?.unwrap();
+    assert_eq!(stash.get_balance(&Asset::usdt()), Mula::new(40, &Asset::usdt()), "no credit may be lost");
+
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use crate::utils::{bootstrap::bootstrap, prepare::prepare_stash};
+use shared::{
+    domain::value_objects::{asset::Asset, mula::Mula, pid::Pid},
+    infrastructure::{
+        messaging::EventBus,
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+use stash::{
+    application::{
+        ledger::{LedgerService, command::WriteLedgerEntryCommand},
+        reconciliation::{ReconciliationService, command::ReconcileStashCommand},
+        stash::{StashService, command::GetStashCommand},
+    },
+    domain::{
+        events::StashBalanceDriftDetectedEvent,
+        ledger_entry::{entry::LedgerEntry, entry_type::LedgerEntryType},
+        repositories::LedgerRepository,
+    },
+};
+
+mod utils {
+    pub mod auth;
+    pub mod bootstrap;
+    pub mod prepare;
+    pub mod repositories;
+}
+
+/// writes an entry straight to the ledger, leaving the stash balance untouched
+async fn write_untracked_entry(provider: &di::ServiceProvider, stash_id: &Pid, entry_type: LedgerEntryType, amount: u128) -> Result<()> {
+    let ledger_repo = provider.get_required::<dyn LedgerRepository>();
+    let entry = LedgerEntry::new(stash_id, &entry_type, &Mula::new(amount, &Asset::usdt()), &Pid::new());
+    ledger_repo.save(&entry).await
+}
+
+#[tokio::test]
+async fn reconciled_stash_has_no_drift() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let ledger_service = provider.get_required::<LedgerService>();
+    let reconciliation_service = provider.get_required::<ReconciliationService>();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let stash = prepare_stash(&provider).await?;
+    let stash_id = stash.get_pid().to_owned();
+    for (entry_type, amount) in [(LedgerEntryType::CREDIT, 100), (LedgerEntryType::DEBIT, 40)] {
+        let command = WriteLedgerEntryCommand {
+            stash_id: stash_id.clone(),
+            entry_type,
+            amount: Mula::new(amount, &Asset::usdt()),
+            upstream_ref_id: Pid::new(),
+        };
+        ledger_service.write_ledger_entry(command).await?;
+    }
+
+    // Act
+    let report = reconciliation_service
+        .reconcile_stash(ReconcileStashCommand {
+            stash_id: stash_id.clone(),
+            repair: false,
+        })
+        .await?;
+
+    // Assert
+    assert!(!report.has_drift(), "balances written through the ledger must not drift");
+    assert!(!report.repaired, "nothing must be repaired");
+    let drift_event = StashBalanceDriftDetectedEvent::new(&stash_id, &[], false);
+    assert!(!event_bus.published(drift_event).await);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_ledger_writes_are_not_reported_as_drift() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let ledger_service = provider.get_required::<LedgerService>();
+    let reconciliation_service = provider.get_required::<ReconciliationService>();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let stash = prepare_stash(&provider).await?;
+    let stash_id = stash.get_pid().to_owned();
+
+    // Act
+    let writes = {
+        let stash_id = stash_id.clone();
+        tokio::spawn(async move {
+            for _ in 0..200 {
+                let command = WriteLedgerEntryCommand {
+                    stash_id: stash_id.clone(),
+                    entry_type: LedgerEntryType::CREDIT,
+                    amount: Mula::new(1, &Asset::usdt()),
+                    upstream_ref_id: Pid::new(),
+                };
+                ledger_service.write_ledger_entry(command).await?;
+            }
+            Ok::<_, Error>(())
+        })
+    };
+    let mut reports = vec![];
+    while !writes.is_finished() {
+        let command = ReconcileStashCommand {
+            stash_id: stash_id.clone(),
+            repair: false,
+        };
+        reports.push(reconciliation_service.reconcile_stash(command).await?);
+    }
+    writes.await.unwrap()?;
+
+    // Assert
+    let drifted = reports.iter().filter(|report| report.has_drift()).count();
+    assert_eq!(drifted, 0, "ledger writes racing a reconciliation must not look like drift");
+    let drift_event = StashBalanceDriftDetectedEvent::new(&stash_id, &[], false);
+    assert!(!event_bus.published(drift_event).await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn drift_is_reported_without_repair() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let reconciliation_service = provider.get_required::<ReconciliationService>();
+    let stash_service = provider.get_required::<StashService>();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let stash = prepare_stash(&provider).await?;
+    let stash_id = stash.get_pid().to_owned();
+    write_untracked_entry(&provider, &stash_id, LedgerEntryType::CREDIT, 25).await?;
+
+    // Act
+    let report = reconciliation_service
+        .reconcile_stash(ReconcileStashCommand {
+            stash_id: stash_id.clone(),
+            repair: false,
+        })
+        .await?;
+
+    // Assert
+    assert_eq!(report.discrepancies.len(), 1, "one asset must drift");
+    let discrepancy = &report.discrepancies[0];
+    assert_eq!(
+        discrepancy.recorded,
+        Mula::zero(&Asset::usdt()),
+        "recorded balance must be the stash balance"
+    );
+    assert_eq!(
+        discrepancy.computed,
+        Mula::new(25, &Asset::usdt()),
+        "computed balance must be the ledger sum"
+    );
+    assert!(!report.repaired, "drift must not be repaired");
+    let drift_event = StashBalanceDriftDetectedEvent::new(&stash_id, &report.discrepancies, false);
+    assert!(event_bus.published(drift_event).await);
+
+    let stash = stash_service.get_stash(GetStashCommand { stash_id }).await?.unwrap();
+    assert_eq!(
+        stash.get_balance(&Asset::usdt()),
+        Mula::zero(&Asset::usdt()),
+        "balance must be left untouched"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn drift_is_repaired_from_ledger() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let reconciliation_service = provider.get_required::<ReconciliationService>();
+    let stash_service = provider.get_required::<StashService>();
+    let stash = prepare_stash(&provider).await?;
+    let stash_id = stash.get_pid().to_owned();
+    write_untracked_entry(&provider, &stash_id, LedgerEntryType::CREDIT, 25).await?;
+
+    // Act
+    let report = reconciliation_service
+        .reconcile_stash(ReconcileStashCommand {
+            stash_id: stash_id.clone(),
+            repair: true,
+        })
+        .await?;
+
+    // Assert
+    assert!(report.repaired, "drift must be repaired");
+    let stash = stash_service.get_stash(GetStashCommand { stash_id: stash_id.clone() }).await?.unwrap();
+    assert_eq!(
+        stash.get_balance(&Asset::usdt()),
+        Mula::new(25, &Asset::usdt()),
+        "balance must match the ledger"
+    );
+
+    let report = reconciliation_service
+        .reconcile_stash(ReconcileStashCommand { stash_id, repair: false })
+        .await?;
+    assert!(!report.has_drift(), "repaired stash must not drift");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn overdrawn_ledger_is_reported_and_not_repaired() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let reconciliation_service = provider.get_required::<ReconciliationService>();
+    let stash_service = provider.get_required::<StashService>();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let stash = prepare_stash(&provider).await?;
+    let stash_id = stash.get_pid().to_owned();
+    write_untracked_entry(&provider, &stash_id, LedgerEntryType::CREDIT, 10).await?;
+    write_untracked_entry(&provider, &stash_id, LedgerEntryType::DEBIT, 25).await?;
+
+    // Act
+    let report = reconciliation_service
+        .reconcile_stash(ReconcileStashCommand {
+            stash_id: stash_id.clone(),
+            repair: true,
+        })
+        .await?;
+
+    // Assert
+    assert_eq!(report.discrepancies.len(), 1, "overdrawn asset must be reported");
+    let discrepancy = &report.discrepancies[0];
+    assert_eq!(discrepancy.overdrawn_by, Some(Mula::new(15, &Asset::usdt())));
+    assert!(!report.repaired, "overdrawn ledger must not be repaired");
+    let drift_event = StashBalanceDriftDetectedEvent::new(&stash_id, &report.discrepancies, false);
+    assert!(event_bus.published(drift_event).await);
+
+    let stash = stash_service.get_stash(GetStashCommand { stash_id }).await?.unwrap();
+    assert_eq!(
+        stash.get_balance(&Asset::usdt()),
+        Mula::zero(&Asset::usdt()),
+        "balance must be left untouched"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reconciling_unknown_stash_fails() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let reconciliation_service = provider.get_required::<ReconciliationService>();
+
+    // Act
+    let result = reconciliation_service
+        .reconcile_stash(ReconcileStashCommand {
+            stash_id: Pid::new(),
+            repair: false,
+        })
+        .await;
+
+    // Assert
+    assert!(matches!(result, Err(Error::DomainError(DomainError::EntityNotFound))));
+
+    Ok(())
+}
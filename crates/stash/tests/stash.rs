@@ -1,22 +1,39 @@
 use insta::{assert_debug_snapshot, with_settings};
 use shared::{
     configure_insta,
-    domain::value_objects::{asset::Asset, mula::Mula, pid::Pid},
-    infrastructure::types::Result,
+    domain::{
+        events::stash::StashStatusUpdatedEvent,
+        value_objects::{asset::Asset, mula::Mula, pid::Pid, stash_status::StashStatus},
+    },
+    infrastructure::{
+        messaging::EventBus,
+        types::{Result, error::Error},
+    },
     testing::insta_filters::redactions::cleanup_model_generics,
 };
 use stash::{
-    application::stash::{
-        StashService,
-        command::{CreateStashCommand, UpdateStashBalanceCommand, UpdateStashStatusCommand},
+    application::{
+        ledger::{LedgerService, command::WriteLedgerEntryCommand},
+        stash::{
+            StashService,
+            command::{CreateStashCommand, UpdateStashStatusCommand},
+        },
+    },
+    domain::{
+        ledger_entry::entry_type::LedgerEntryType,
+        stash::{name::StashName, tag::Tag},
     },
-    domain::stash::{name::StashName, status::StashStatus, tag::Tag},
 };
 use std::str::FromStr;
 
 use crate::utils::{bootstrap::bootstrap, prepare::prepare_stash};
 
-mod utils;
+mod utils {
+    pub mod auth;
+    pub mod bootstrap;
+    pub mod prepare;
+    pub mod repositories;
+}
 
 #[tokio::test]
 async fn can_create_stash() -> Result<()> {
@@ -64,24 +81,92 @@ async fn can_update_stash_status() -> Result<()> {
 }
 
 #[tokio::test]
-#[cfg(feature = "testing")]
-async fn can_update_stash_balance() -> Result<()> {
+async fn can_pause_and_resume_stash() -> Result<()> {
     // Arrange
     let provider = bootstrap().await;
     let stash_service = provider.get_required::<StashService>();
+    let event_bus = provider.get_required::<dyn EventBus>();
     let stash = prepare_stash(&provider).await?;
-    let new_balance = Mula::new(10, &Asset::usdt());
-    let command = UpdateStashBalanceCommand {
-        stash_id: stash.get_pid().to_owned(),
-        new_balance: new_balance.clone(),
-    };
+    let stash_id = stash.get_pid().to_owned();
+
+    // Act
+    let paused = stash_service
+        .update_stash_status(UpdateStashStatusCommand {
+            stash_id: stash_id.clone(),
+            new_status: StashStatus::PAUSED,
+        })
+        .await?;
+    let resumed = stash_service
+        .update_stash_status(UpdateStashStatusCommand {
+            stash_id: stash_id.clone(),
+            new_status: StashStatus::ACTIVE,
+        })
+        .await?;
+
+    // Assert
+    assert_eq!(paused.get_status(), &StashStatus::PAUSED, "Stash status must be `PAUSED`");
+    assert_eq!(resumed.get_status(), &StashStatus::ACTIVE, "Stash status must be `ACTIVE`");
+    let status_updated_event = StashStatusUpdatedEvent::new(&stash_id, resumed.get_user_id(), &StashStatus::PAUSED, &StashStatus::ACTIVE);
+    assert!(event_bus.published(status_updated_event).await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn closed_stash_can_not_be_reopened() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let stash_service = provider.get_required::<StashService>();
+    let stash = prepare_stash(&provider).await?;
+    let stash_id = stash.get_pid().to_owned();
+    stash_service
+        .update_stash_status(UpdateStashStatusCommand {
+            stash_id: stash_id.clone(),
+            new_status: StashStatus::CLOSED,
+        })
+        .await?;
+
+    // Act
+    let result = stash_service
+        .update_stash_status(UpdateStashStatusCommand {
+            stash_id,
+            new_status: StashStatus::ACTIVE,
+        })
+        .await;
+
+    // Assert
+    assert!(matches!(result, Err(Error::InvalidState(_))), "reopening must fail, got: {result:?}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stash_with_balance_can_not_be_closed() -> Result<()> {
+    // Arrange
+    let provider = bootstrap().await;
+    let stash_service = provider.get_required::<StashService>();
+    let ledger_service = provider.get_required::<LedgerService>();
+    let stash = prepare_stash(&provider).await?;
+    let stash_id = stash.get_pid().to_owned();
+    ledger_service
+        .write_ledger_entry(WriteLedgerEntryCommand {
+            stash_id: stash_id.clone(),
+            entry_type: LedgerEntryType::CREDIT,
+            amount: Mula::new(10, &Asset::usdt()),
+            upstream_ref_id: Pid::new(),
+        })
+        .await?;
 
     // Act
-    let stash = stash_service.update_stash_balance(command).await?;
-    let balance = stash.get_balances().iter().find(|b| b.get_asset() == new_balance.get_asset()).unwrap();
+    let result = stash_service
+        .update_stash_status(UpdateStashStatusCommand {
+            stash_id,
+            new_status: StashStatus::CLOSED,
+        })
+        .await;
 
     // Assert
-    assert_eq!(balance, &new_balance, "Balance must equal to new balance");
+    assert!(matches!(result, Err(Error::InvalidState(_))), "closing must fail, got: {result:?}");
 
     Ok(())
 }
@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        http::auth::{AuthenticatedService, AuthenticatedUser, ServiceTokenVerifier, TokenVerifier},
+        types::{Result, error::Error},
+    },
+};
+
+/// Accepts the user's pid as its bearer token
+pub struct StubTokenVerifier;
+
+#[async_trait]
+impl TokenVerifier for StubTokenVerifier {
+    async fn verify(&self, token: &str) -> Result<AuthenticatedUser> {
+        let user_id: Pid = token.parse().map_err(|_| Error::Unauthorized)?;
+        Ok(AuthenticatedUser {
+            session_id: user_id.clone(),
+            user_id,
+        })
+    }
+}
+
+/// Accepts `service:<name>` as the bearer token of the service `name`
+pub struct StubServiceTokenVerifier;
+
+#[async_trait]
+impl ServiceTokenVerifier for StubServiceTokenVerifier {
+    async fn verify(&self, token: &str) -> Result<AuthenticatedService> {
+        let name = token.strip_prefix("service:").ok_or(Error::Unauthorized)?;
+        Ok(AuthenticatedService { name: name.to_owned() })
+    }
+}
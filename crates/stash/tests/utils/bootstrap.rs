@@ -1,19 +1,54 @@
-use di::{Injectable, ServiceCollection, ServiceProvider};
-use shared::infrastructure::messaging::memory::InMemoryEventBus;
+use di::{Injectable, ServiceCollection, ServiceProvider, singleton, singleton_as_self};
+use shared::{
+    domain::value_objects::asset::Asset,
+    infrastructure::{
+        http::auth::{ServiceTokenVerifier, TokenVerifier},
+        messaging::{
+            EventBus,
+            envelope::EventRegistry,
+            memory::{DispatchMode, InMemoryEventBus},
+            outbox::{OutboxRelay, memory::InMemoryOutbox},
+            quarantine::{QuarantineReplayer, memory::InMemoryQuarantineStore},
+            subscriber::EventSubscriber,
+        },
+    },
+};
 use stash::{
-    application::{ledger::LedgerService, stash::StashService},
-    infra::events::{register::EventSubscriber, user_status_updated::OnUserStatusUpdated},
+    application::{ledger::LedgerService, reconciliation::ReconciliationService, stash::StashService},
+    domain::assets::AssetRegistry,
+    infra::{
+        events::{registry::event_registry, user_status_updated::OnUserStatusUpdated},
+        http::StashApi,
+    },
 };
+use std::sync::Arc;
 
-use crate::utils::repositories::{StubLedgerRepository, StubStashRepository};
+use crate::utils::{
+    auth::{StubServiceTokenVerifier, StubTokenVerifier},
+    repositories::{StubLedgerRepository, StubStashRepository},
+};
 
 pub async fn bootstrap() -> ServiceProvider {
+    bootstrap_with_dispatch(DispatchMode::Inline).await
+}
+
+pub async fn bootstrap_with_dispatch(mode: DispatchMode) -> ServiceProvider {
     let provider = ServiceCollection::new()
         .add(StashService::singleton())
         .add(LedgerService::singleton())
+        .add(ReconciliationService::singleton())
+        .add(StashApi::singleton())
+        .add(singleton_as_self::<AssetRegistry>().from(|_| Arc::new(AssetRegistry::new(vec![Asset::usdt()]))))
+        .add(singleton::<dyn TokenVerifier, StubTokenVerifier>().from(|_| Arc::new(StubTokenVerifier)))
+        .add(singleton::<dyn ServiceTokenVerifier, StubServiceTokenVerifier>().from(|_| Arc::new(StubServiceTokenVerifier)))
         .add(StubStashRepository::singleton())
         .add(StubLedgerRepository::singleton())
-        .add(InMemoryEventBus::singleton())
+        .add(InMemoryOutbox::singleton())
+        .add(OutboxRelay::singleton())
+        .add(InMemoryQuarantineStore::singleton())
+        .add(QuarantineReplayer::singleton())
+        .add(singleton_as_self::<EventRegistry>().from(|_| Arc::new(event_registry())))
+        .add(singleton::<dyn EventBus, InMemoryEventBus>().from(move |_| Arc::new(InMemoryEventBus::new(mode))))
         .add(EventSubscriber::singleton())
         .add(OnUserStatusUpdated::singleton())
         .build_provider()
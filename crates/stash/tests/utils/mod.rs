@@ -1,3 +0,0 @@
-pub mod bootstrap;
-pub mod prepare;
-pub mod repositories;
@@ -1,16 +1,31 @@
 use async_trait::async_trait;
 use di::injectable;
-use shared::{domain::value_objects::pid::Pid, infrastructure::types::Result};
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        messaging::{
+            envelope::EventEnvelope,
+            outbox::{Outbox, RecordedEvent},
+        },
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
 use stash::domain::{
-    ledger_entry::entry::LedgerEntry,
-    repositories::{FindManyLedgerQuery, FindManyStashQuery, LedgerRepository, StashRepository},
+    ledger_entry::{entry::LedgerEntry, entry_type::LedgerEntryType},
+    repositories::{FindManyLedgerQuery, FindManyStashQuery, LedgerRepository, StashCursorQuery, StashRepository},
     stash::{name::StashName, stash::Stash},
 };
+use std::sync::Arc;
 use tokio::sync::Mutex;
 
 #[injectable(StashRepository)]
 pub struct StubStashRepository {
     stashes: Mutex<Vec<Stash>>,
+    ledger_repo: Arc<dyn LedgerRepository>,
+    outbox: Arc<dyn Outbox>,
 }
 
 #[async_trait]
@@ -21,9 +36,29 @@ impl StashRepository for StubStashRepository {
         Ok(stash)
     }
 
-    async fn find_many(&self, _query: FindManyStashQuery) -> Result<Vec<Stash>> {
+    async fn find_many(&self, query: FindManyStashQuery) -> Result<Vec<Stash>> {
         let stashes = self.stashes.lock().await;
-        Ok(stashes.clone())
+        let stashes = stashes
+            .iter()
+            .filter(|s| query.user_id.as_ref().is_none_or(|id| s.get_user_id() == id))
+            .skip(usize::from(query.page.saturating_sub(1)) * usize::from(query.limit))
+            .take(usize::from(query.limit))
+            .cloned()
+            .collect();
+        Ok(stashes)
+    }
+
+    async fn find_after(&self, query: StashCursorQuery) -> Result<Vec<Stash>> {
+        let stashes = self.stashes.lock().await;
+        let mut stashes: Vec<Stash> = stashes
+            .iter()
+            .filter(|s| s.get_user_id() == &query.user_id)
+            .filter(|s| query.after.as_ref().is_none_or(|after| s.get_pid().as_uuid() > after.as_uuid()))
+            .cloned()
+            .collect();
+        stashes.sort_by_key(|s| *s.get_pid().as_uuid());
+        stashes.truncate(usize::from(query.limit));
+        Ok(stashes)
     }
 
     async fn exists_with_name_for_user(&self, user_id: &Pid, name: &StashName) -> Result<bool> {
@@ -33,11 +68,46 @@ impl StashRepository for StubStashRepository {
     }
 
     async fn save(&self, stash: &Stash) -> Result<()> {
-        let mut stashs = self.stashes.lock().await;
-        stashs.retain(|s| s.get_pid() != stash.get_pid());
-        stashs.push(stash.clone());
-        Ok(())
+        let mut stashes = self.stashes.lock().await;
+        store(&mut stashes, stash)
+    }
+
+    /// keeps the stashes locked until the entry is saved too, so no reader sees one without the other
+    async fn save_with_ledger_entry(&self, stash: &Stash, entry: &LedgerEntry, events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        let mut stashes = self.stashes.lock().await;
+        store(&mut stashes, stash)?;
+        self.ledger_repo.save(entry).await?;
+        self.outbox.append(&envelopes).await
+    }
+
+    async fn save_with_events(&self, stash: &Stash, events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        self.save(stash).await?;
+        self.outbox.append(&envelopes).await
     }
+
+    async fn save_all_with_events(&self, stashes: &[Stash], events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        for stash in stashes {
+            self.save(stash).await?;
+        }
+        self.outbox.append(&envelopes).await
+    }
+}
+
+/// replaces `stash` in `stashes` unless a newer version was stored meanwhile
+fn store(stashes: &mut Vec<Stash>, stash: &Stash) -> Result<()> {
+    let mut stored = stash.clone();
+    if let Some(existing) = stashes.iter().find(|s| s.get_pid() == stash.get_pid()) {
+        if existing.get_version() != stash.get_version() {
+            return Err(Error::DomainError(DomainError::EntityConflict));
+        }
+        stored.advance_version();
+    }
+    stashes.retain(|s| s.get_pid() != stash.get_pid());
+    stashes.push(stored);
+    Ok(())
 }
 
 #[injectable(LedgerRepository)]
@@ -54,9 +124,25 @@ impl LedgerRepository for StubLedgerRepository {
     }
 
     async fn find_many(&self, query: FindManyLedgerQuery) -> Result<Vec<LedgerEntry>> {
-        println!("{:?}", query);
         let entries = self.entries.lock().await;
-        Ok(entries.clone())
+        let entries = entries
+            .iter()
+            .filter(|e| query.stash_id.as_ref().is_none_or(|id| e.get_stash_id() == id))
+            .filter(|e| query.entry_type.as_ref().is_none_or(|t| e.get_type() == t))
+            .skip(usize::from(query.page.saturating_sub(1)) * usize::from(query.limit))
+            .take(usize::from(query.limit))
+            .cloned()
+            .collect();
+        Ok(entries)
+    }
+
+    async fn find_by_upstream_ref(&self, stash_id: &Pid, upstream_ref_id: &Pid, entry_type: &LedgerEntryType) -> Result<Option<LedgerEntry>> {
+        let entries = self.entries.lock().await;
+        let entry = entries
+            .iter()
+            .find(|e| e.get_stash_id() == stash_id && e.get_upstream_ref_id() == upstream_ref_id && e.get_type() == entry_type)
+            .cloned();
+        Ok(entry)
     }
 
     async fn save(&self, entry: &LedgerEntry) -> Result<()> {
@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS users (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    pid             TEXT NOT NULL UNIQUE,
+    email           TEXT NOT NULL UNIQUE,
+    status          TEXT NOT NULL,
+    created_at      TEXT NOT NULL,
+    last_login_at   TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    pid         TEXT NOT NULL UNIQUE,
+    user_id     TEXT NOT NULL REFERENCES users (pid),
+    code        TEXT NOT NULL,
+    activated   INTEGER NOT NULL DEFAULT 0,
+    expires_at  TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS sessions_user_id_idx ON sessions (user_id);
+
+CREATE TABLE IF NOT EXISTS profiles (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    pid             TEXT NOT NULL UNIQUE,
+    user_id         TEXT NOT NULL UNIQUE REFERENCES users (pid),
+    display_name    TEXT NOT NULL,
+    wallet_address  TEXT NOT NULL
+);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    pid         TEXT NOT NULL UNIQUE,
+    session_id  TEXT NOT NULL REFERENCES sessions (pid),
+    user_id     TEXT NOT NULL REFERENCES users (pid),
+    secret_hash TEXT NOT NULL,
+    parent_id   TEXT REFERENCES refresh_tokens (pid),
+    used_at     TEXT,
+    revoked_at  TEXT,
+    expires_at  TEXT NOT NULL,
+    created_at  TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS refresh_tokens_session_id_idx ON refresh_tokens (session_id);
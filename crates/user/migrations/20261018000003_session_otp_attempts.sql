@@ -0,0 +1,2 @@
+ALTER TABLE sessions ADD COLUMN failed_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sessions ADD COLUMN locked INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS mail_outbox (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    pid             TEXT NOT NULL UNIQUE,
+    email           TEXT NOT NULL,
+    status          TEXT NOT NULL,
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TEXT NOT NULL,
+    last_error      TEXT,
+    sent_at         TEXT,
+    created_at      TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS mail_outbox_status_next_attempt_at_idx ON mail_outbox (status, next_attempt_at);
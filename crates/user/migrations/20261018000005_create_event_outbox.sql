@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS event_outbox (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    pid          TEXT NOT NULL UNIQUE,
+    event_type   TEXT NOT NULL,
+    aggregate_id TEXT NOT NULL,
+    payload      TEXT NOT NULL,
+    occurred_at  TEXT NOT NULL,
+    published_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS event_outbox_published_at_idx ON event_outbox (published_at);
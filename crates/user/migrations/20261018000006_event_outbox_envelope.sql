@@ -0,0 +1,3 @@
+ALTER TABLE event_outbox ADD COLUMN schema_version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE event_outbox ADD COLUMN correlation_id TEXT;
+ALTER TABLE event_outbox ADD COLUMN causation_id TEXT;
@@ -0,0 +1,2 @@
+ALTER TABLE event_outbox ADD COLUMN failed_at TEXT;
+ALTER TABLE event_outbox ADD COLUMN last_error TEXT;
@@ -1,16 +1,14 @@
 use crate::{
-    application::{mailing::MailingService, session::SessionManagementService, user::UserManagementService},
-    domain::value_objects::email::EmailAddress,
-    infrastructure::auth::jwt_service::JWTService,
+    application::{mailing::MailingService, refresh_token::RefreshTokenService, session::SessionManagementService, user::UserManagementService},
+    domain::{entities::refresh_token::RefreshToken, value_objects::email::EmailAddress},
+    infrastructure::{auth::jwt_service::JWTService, config::Config},
 };
 use di::injectable;
 use shared::{
-    domain::{
-        events::user::{SessionActivatedEvent, SessionTerminatedEvent},
-        value_objects::pid::Pid,
-    },
+    domain::value_objects::pid::Pid,
     infrastructure::{
-        messaging::EventBus,
+        mailing::template::Locale,
+        rate_limiting::RateLimiter,
         types::{
             Result,
             error::{DomainError, Error},
@@ -19,21 +17,41 @@ use shared::{
 };
 use std::sync::Arc;
 
+/// Short lived access token with the refresh token that renews it
+#[derive(Debug, Clone)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
 #[injectable]
 pub struct AuthenticationService {
     session_service: Arc<SessionManagementService>,
+    refresh_token_service: Arc<RefreshTokenService>,
     user_service: Arc<UserManagementService>,
     mail_service: Arc<MailingService>,
     jwt_service: Arc<JWTService>,
-    event_bus: Arc<dyn EventBus>,
+    rate_limiter: Arc<dyn RateLimiter>,
+    config: Arc<Config>,
 }
 
 impl AuthenticationService {
-    pub async fn create_new_session(&self, email: &EmailAddress) -> Result<Pid> {
+    /// mails a new OTP code to `email`; rate limited per address and per user.
+    /// Users signing up are mailed in `locale`, or the configured default locale when it is missing
+    pub async fn create_new_session(&self, email: &EmailAddress, locale: Option<Locale>) -> Result<Pid> {
+        let email_key = format!("otp:email:{}", email.to_string().to_lowercase());
+        self.rate_limiter.acquire(&email_key, &self.config.auth.otp_email_rate_limit).await?;
+
         let user = match self.user_service.get_user_by_email(&email).await? {
             Some(user) => user,
-            None => self.user_service.create_user(&email).await?,
+            None => {
+                let locale = locale.unwrap_or(self.config.mailing.locale);
+                self.user_service.create_user(&email, locale).await?
+            }
         };
+
+        let user_key = format!("otp:user:{}", user.get_pid().to_string());
+        self.rate_limiter.acquire(&user_key, &self.config.auth.otp_user_rate_limit).await?;
         self.session_service.expire_unused_session(&user.get_pid()).await?;
         let session = self.session_service.create_session(&user.get_pid()).await?;
         self.mail_service.send_authentication_mail(&user, &session).await?;
@@ -41,23 +59,69 @@ impl AuthenticationService {
         Ok(session.get_pid().to_owned())
     }
 
-    pub async fn activate_session(&self, session_id: &Pid, code: &str) -> Result<String> {
-        let mut session = match self.session_service.get_session_by_id(session_id).await? {
-            Some(session) => session,
-            None => return Err(Error::DomainError(DomainError::EntityNotFound)),
+    /// every submitted code takes one of the session's attempts before it is compared,
+    /// so concurrent guesses can not exceed `max_otp_attempts`
+    pub async fn activate_session(&self, session_id: &Pid, code: &str) -> Result<TokenPair> {
+        if self.session_service.get_session_by_id(session_id).await?.is_none() {
+            return Err(Error::DomainError(DomainError::EntityNotFound));
+        }
+
+        let max_attempts = self.config.auth.max_otp_attempts;
+        let Some(mut session) = self.session_service.claim_attempt(session_id, max_attempts).await? else {
+            return Err(Error::DomainError(DomainError::EntityInvalid));
         };
 
-        if session.has_expired() || !session.is_valid_code(code) || session.activated() {
+        if !session.is_valid_code(code) {
+            self.session_service.record_failed_attempt(session_id, max_attempts).await?;
             return Err(Error::DomainError(DomainError::EntityInvalid));
         }
 
         self.session_service.activate_session(&mut session).await?;
         let user = self.user_service.update_user_last_login(session.get_user_id()).await?;
-        let token = self.jwt_service.generate_token(&user)?;
-        let session_activated_event = SessionActivatedEvent::new(session.get_user_id(), session.get_pid());
-        self.event_bus.publish(session_activated_event).await?;
+        let access_token = self.jwt_service.generate_token(&user, &session)?;
+        let refresh_token = self.refresh_token_service.issue(&session, None).await?;
 
-        Ok(token)
+        Ok(TokenPair { access_token, refresh_token })
+    }
+
+    /// exchanges a refresh token for a new pair, spending the presented token.
+    /// Presenting an already rotated token revokes the whole session chain.
+    pub async fn rotate(&self, refresh_token: &str) -> Result<TokenPair> {
+        let (token_id, secret) = RefreshToken::parse(refresh_token).ok_or(Error::Unauthorized)?;
+        let token = self
+            .refresh_token_service
+            .get_refresh_token_by_id(&token_id)
+            .await?
+            .filter(|token| token.matches(&secret))
+            .ok_or(Error::Unauthorized)?;
+
+        if token.is_revoked() || token.has_expired() {
+            return Err(Error::Unauthorized);
+        }
+
+        if !self.refresh_token_service.mark_used(&token).await? {
+            println!("refresh token reuse detected, revoking session: {}", token.get_session_id().to_string());
+            self.terminate_session(token.get_session_id()).await?;
+            return Err(Error::Unauthorized);
+        }
+
+        let session = self
+            .session_service
+            .get_session_by_id(token.get_session_id())
+            .await?
+            .filter(|session| session.activated() && !session.has_expired())
+            .ok_or(Error::Unauthorized)?;
+
+        let user = self
+            .user_service
+            .get_user_by_pid(session.get_user_id())
+            .await?
+            .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+
+        let access_token = self.jwt_service.generate_token(&user, &session)?;
+        let refresh_token = self.refresh_token_service.issue(&session, Some(token.get_pid())).await?;
+
+        Ok(TokenPair { access_token, refresh_token })
     }
 
     pub async fn is_valid_session(&self, session_id: &Pid) -> Result<bool> {
@@ -70,6 +134,14 @@ impl AuthenticationService {
         Ok(false)
     }
 
+    /// terminates `session_id` on behalf of `user_id`; sessions of other users are reported as not found
+    pub async fn logout(&self, user_id: &Pid, session_id: &Pid) -> Result<()> {
+        match self.session_service.get_session_by_id(session_id).await? {
+            Some(session) if session.get_user_id() == user_id => self.terminate_session(session_id).await,
+            _ => Err(Error::DomainError(DomainError::EntityNotFound)),
+        }
+    }
+
     pub async fn terminate_session(&self, session_id: &Pid) -> Result<()> {
         let mut session = self
             .session_service
@@ -77,9 +149,8 @@ impl AuthenticationService {
             .await?
             .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
 
+        self.refresh_token_service.revoke_chain(session.get_pid()).await?;
         self.session_service.expire_session(&mut session).await?;
-        let session_terminated_event = SessionTerminatedEvent::new(session.get_user_id(), session.get_pid());
-        self.event_bus.publish(session_terminated_event).await?;
 
         Ok(())
     }
@@ -0,0 +1,114 @@
+use crate::{
+    domain::{
+        entities::outbound_mail::{OutboundMail, OutboundMailStatus},
+        repositories::MailQueueRepository,
+    },
+    infrastructure::config::Config,
+};
+use chrono::Utc;
+use di::injectable;
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        mailing::{Email, Mailer},
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+/// Outcome of one pass over the due mails
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MailQueueRun {
+    pub sent: usize,
+    pub retried: usize,
+    pub dead_lettered: usize,
+}
+
+/// Durable outbox in front of the `Mailer`.
+///
+/// Mails are stored first and delivered by a background worker, so a slow or failing
+/// provider never fails the request that produced the mail. Delivery is at least once:
+/// a mail is only marked sent after the mailer accepted it.
+#[injectable]
+pub struct MailQueueService {
+    mail_queue_repo: Arc<dyn MailQueueRepository>,
+    mailer: Arc<dyn Mailer>,
+    config: Arc<Config>,
+}
+
+impl MailQueueService {
+    pub async fn enqueue(&self, email: Email) -> Result<Pid> {
+        let mail = OutboundMail::new(email);
+        self.mail_queue_repo.save(&mail).await?;
+        Ok(mail.get_pid().to_owned())
+    }
+
+    /// delivers one batch of due mails claimed for this worker, rescheduling or dead-lettering the ones that fail
+    pub async fn process_due(&self) -> Result<MailQueueRun> {
+        let queue = &self.config.mailing.queue;
+        let mut run = MailQueueRun::default();
+
+        for mut mail in self.mail_queue_repo.claim_due(queue.batch_size, queue.lease()).await? {
+            match self.mailer.mail(mail.get_email()).await {
+                Ok(_) => {
+                    mail.mark_sent();
+                    run.sent += 1;
+                }
+                Err(e) => {
+                    let backoff = queue.backoff(mail.get_attempts() + 1);
+                    mail.record_failure(format!("{e:?}"), queue.max_attempts, backoff);
+                    match mail.get_status() {
+                        OutboundMailStatus::DeadLettered => run.dead_lettered += 1,
+                        _ => run.retried += 1,
+                    }
+                }
+            }
+            self.mail_queue_repo.save(&mail).await?;
+        }
+
+        Ok(run)
+    }
+
+    pub async fn get_dead_letters(&self, limit: u32) -> Result<Vec<OutboundMail>> {
+        self.mail_queue_repo.find_dead_letters(limit).await
+    }
+
+    /// gives a dead letter a fresh attempt budget; it is picked up by the next worker run
+    pub async fn requeue(&self, mail_id: &Pid) -> Result<OutboundMail> {
+        let mut mail = self
+            .mail_queue_repo
+            .find_by_pid(mail_id)
+            .await?
+            .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+
+        mail.requeue()?;
+        self.mail_queue_repo.save(&mail).await?;
+        Ok(mail)
+    }
+
+    /// deletes the mails sent longer than `sent_retention_seconds` ago, returns how many
+    pub async fn purge_sent(&self) -> Result<u64> {
+        let sent_before = Utc::now() - self.config.mailing.queue.sent_retention();
+        self.mail_queue_repo.purge_sent(&sent_before).await
+    }
+
+    /// runs `process_due` and `purge_sent` every `poll_interval_seconds` until the returned task is aborted
+    pub fn spawn_worker(self: Arc<Self>) -> JoinHandle<()> {
+        let interval = Duration::from_secs(self.config.mailing.queue.poll_interval_seconds);
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.process_due().await {
+                    println!("mail queue run failed: {:?}", e);
+                }
+                if let Err(e) = self.purge_sent().await {
+                    println!("mail queue purge failed: {:?}", e);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+}
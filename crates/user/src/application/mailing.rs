@@ -1,27 +1,69 @@
-use crate::domain::{aggregates::user::User, entities::session::Session};
+use crate::{
+    application::mail_queue::MailQueueService,
+    domain::{
+        aggregates::user::User,
+        entities::{profile::Profile, session::Session},
+    },
+    infrastructure::config::Config,
+};
 use di::injectable;
-use shared::infrastructure::{
-    mailing::{Email, Mailer},
-    types::Result,
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        mailing::{
+            Email,
+            template::{MailTemplate, TemplateContext},
+        },
+        types::Result,
+    },
 };
 use std::sync::Arc;
 
 #[injectable]
 pub struct MailingService {
-    mailer: Arc<dyn Mailer>,
+    mail_queue: Arc<MailQueueService>,
+    config: Arc<Config>,
 }
 
 impl MailingService {
     pub async fn send_authentication_mail(&self, user: &User, session: &Session) -> Result<()> {
+        let context = TemplateContext::new().with("code", session.get_code().to_string());
+        self.send(user, MailTemplate::Otp, &context).await
+    }
+
+    pub async fn send_welcome_mail(&self, user: &User) -> Result<()> {
+        let context = TemplateContext::new().with("email", user.get_email().to_string());
+        self.send(user, MailTemplate::Welcome, &context).await
+    }
+
+    pub async fn send_profile_created_mail(&self, user: &User, profile: &Profile) -> Result<()> {
+        let context = TemplateContext::new()
+            .with("display_name", profile.get_display_name().to_string())
+            .with("wallet_address", profile.get_wallet_address().to_string());
+        self.send(user, MailTemplate::ProfileCreated, &context).await
+    }
+
+    pub async fn send_stash_status_changed_mail(&self, user: &User, stash_id: &Pid, old_status: &str, new_status: &str) -> Result<()> {
+        let context = TemplateContext::new()
+            .with("stash_id", stash_id.to_string())
+            .with("old_status", old_status)
+            .with("new_status", new_status);
+        self.send(user, MailTemplate::StashStatusChanged, &context).await
+    }
+
+    /// renders `template` in the locale of `user` and queues it, the mail queue worker delivers it
+    async fn send(&self, user: &User, template: MailTemplate, context: &TemplateContext) -> Result<()> {
+        let rendered = template.render(user.get_locale(), context)?;
         let email = Email {
-            from: "noreply@stash.it".to_string(),
+            from: self.config.mailing.sender.clone(),
             to: user.get_email().to_string(),
-            subject: "OTP Request".to_owned(),
-            html: format!("You OTP code is {}", session.get_code().to_string()),
+            subject: rendered.subject,
+            html: rendered.html,
+            text: rendered.text,
             ..Default::default()
         };
 
-        self.mailer.mail(&email).await?;
+        self.mail_queue.enqueue(email).await?;
         Ok(())
     }
 }
@@ -1,4 +1,6 @@
 pub mod auth;
+pub mod mail_queue;
 pub mod mailing;
+pub mod refresh_token;
 pub mod session;
 pub mod user;
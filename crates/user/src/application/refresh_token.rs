@@ -0,0 +1,34 @@
+use crate::domain::{
+    entities::{refresh_token::RefreshToken, session::Session},
+    repositories::RefreshTokenRepository,
+};
+use di::injectable;
+use shared::{domain::value_objects::pid::Pid, infrastructure::types::Result};
+use std::sync::Arc;
+
+#[injectable]
+pub struct RefreshTokenService {
+    refresh_token_repo: Arc<dyn RefreshTokenRepository>,
+}
+
+impl RefreshTokenService {
+    /// issues the next token of the session chain and returns its opaque value
+    pub async fn issue(&self, session: &Session, parent_id: Option<&Pid>) -> Result<String> {
+        let (token, value) = RefreshToken::issue(session, parent_id);
+        self.refresh_token_repo.save(&token).await?;
+        Ok(value)
+    }
+
+    pub async fn get_refresh_token_by_id(&self, token_id: &Pid) -> Result<Option<RefreshToken>> {
+        self.refresh_token_repo.find_by_pid(token_id).await
+    }
+
+    /// spends `token`; `false` when it was already spent, i.e. it is being reused
+    pub async fn mark_used(&self, token: &RefreshToken) -> Result<bool> {
+        self.refresh_token_repo.mark_used(token.get_pid()).await
+    }
+
+    pub async fn revoke_chain(&self, session_id: &Pid) -> Result<()> {
+        self.refresh_token_repo.revoke_for_session(session_id).await
+    }
+}
@@ -1,11 +1,24 @@
 use crate::domain::{entities::session::Session, repositories::SessionRepository};
 use di::injectable;
-use shared::{domain::value_objects::pid::Pid, infrastructure::types::Result};
+use shared::{
+    domain::{
+        events::user::{OtpVerificationFailedEvent, SessionActivatedEvent, SessionTerminatedEvent},
+        value_objects::pid::Pid,
+    },
+    infrastructure::{
+        messaging::outbox::{OutboxRelay, RecordedEvent},
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
 use std::sync::Arc;
 
 #[injectable]
 pub struct SessionManagementService {
     session_repo: Arc<dyn SessionRepository>,
+    outbox_relay: Arc<OutboxRelay>,
 }
 
 impl SessionManagementService {
@@ -25,11 +38,52 @@ impl SessionManagementService {
 
     pub async fn expire_session(&self, session: &mut Session) -> Result<()> {
         session.expire();
-        self.session_repo.save(&session).await
+        let session_terminated_event = RecordedEvent::record(SessionTerminatedEvent::new(session.get_user_id(), session.get_pid()))?;
+        self.save_and_dispatch(session, vec![session_terminated_event]).await
     }
 
+    /// the session with one more attempt counted, `None` when it takes no more attempts
+    pub async fn claim_attempt(&self, session_id: &Pid, max_attempts: u8) -> Result<Option<Session>> {
+        self.session_repo.claim_attempt(session_id, max_attempts).await
+    }
+
+    /// the session as it is after keeping its claimed attempt as a wrong code
+    pub async fn record_failed_attempt(&self, session_id: &Pid, max_attempts: u8) -> Result<Session> {
+        let record = |session: &Session| {
+            let otp_verification_failed_event = OtpVerificationFailedEvent::new(
+                session.get_user_id(),
+                session.get_pid(),
+                session.get_failed_attempts(),
+                session.is_locked(),
+            );
+            Ok(vec![RecordedEvent::record(otp_verification_failed_event)?])
+        };
+
+        let (session, events) = self
+            .session_repo
+            .record_failed_attempt(session_id, max_attempts, &record)
+            .await?
+            .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+        self.outbox_relay.dispatch(events).await;
+        Ok(session)
+    }
+
+    /// fails when the session was activated or locked since its attempt was claimed
     pub async fn activate_session(&self, session: &mut Session) -> Result<()> {
         session.activate();
-        self.session_repo.save(&session).await
+        let session_activated_event = RecordedEvent::record(SessionActivatedEvent::new(session.get_user_id(), session.get_pid()))?;
+        let events = vec![session_activated_event];
+        if !self.session_repo.activate_with_events(session, &events).await? {
+            return Err(Error::DomainError(DomainError::EntityInvalid));
+        }
+
+        self.outbox_relay.dispatch(events).await;
+        Ok(())
+    }
+
+    async fn save_and_dispatch(&self, session: &Session, events: Vec<RecordedEvent>) -> Result<()> {
+        self.session_repo.save_with_events(session, &events).await?;
+        self.outbox_relay.dispatch(events).await;
+        Ok(())
     }
 }
@@ -14,7 +14,8 @@ use shared::{
         value_objects::pid::Pid,
     },
     infrastructure::{
-        messaging::EventBus,
+        mailing::template::Locale,
+        messaging::outbox::{OutboxRelay, RecordedEvent},
         types::{
             Result,
             error::{DomainError, Error},
@@ -29,7 +30,7 @@ pub mod command;
 pub struct UserManagementService {
     user_repo: Arc<dyn UserRepository>,
     profile_repo: Arc<dyn ProfileRepository>,
-    event_bus: Arc<dyn EventBus>,
+    outbox_relay: Arc<OutboxRelay>,
 }
 
 impl UserManagementService {
@@ -41,11 +42,14 @@ impl UserManagementService {
         self.user_repo.find_by_pid(user_id).await
     }
 
-    pub(crate) async fn create_user(&self, email: &EmailAddress) -> Result<User> {
-        let user = User::new(email.clone());
-        self.user_repo.save(&user).await?;
-        let user_created_event = UserCreatedEvent::new(user.get_pid());
-        self.event_bus.publish(user_created_event).await?;
+    pub async fn get_user_profile(&self, user_id: &Pid) -> Result<Option<Profile>> {
+        self.profile_repo.find_by_user_id(user_id).await
+    }
+
+    pub(crate) async fn create_user(&self, email: &EmailAddress, locale: Locale) -> Result<User> {
+        let user = User::new(email.clone(), locale);
+        let user_created_event = RecordedEvent::record(UserCreatedEvent::new(user.get_pid()))?;
+        self.save_user_and_dispatch(&user, vec![user_created_event]).await?;
         Ok(user)
     }
 
@@ -59,10 +63,8 @@ impl UserManagementService {
 
         let old_status = user.get_status().clone();
         user.update_status(&command.new_status);
-        self.user_repo.save(&user).await?;
-
-        let user_status_updated_event = UserStatusUpdatedEvent::new(user.get_pid(), &old_status, user.get_status());
-        self.event_bus.publish(user_status_updated_event).await?;
+        let user_status_updated_event = RecordedEvent::record(UserStatusUpdatedEvent::new(user.get_pid(), &old_status, user.get_status()))?;
+        self.save_user_and_dispatch(&user, vec![user_status_updated_event]).await?;
         return Ok(user);
     }
 
@@ -88,10 +90,16 @@ impl UserManagementService {
         }
 
         let profile = Profile::new(&user.get_pid(), &command.display_name, &command.wallet_address);
-        self.profile_repo.save(&profile).await?;
-        let event = ProfileCreatedEvent::new(&command.user_id, profile.get_pid());
-        self.event_bus.publish(event).await?;
+        let events = vec![RecordedEvent::record(ProfileCreatedEvent::new(&command.user_id, profile.get_pid()))?];
+        self.profile_repo.save_with_events(&profile, &events).await?;
+        self.outbox_relay.dispatch(events).await;
 
         Ok(profile)
     }
+
+    async fn save_user_and_dispatch(&self, user: &User, events: Vec<RecordedEvent>) -> Result<()> {
+        self.user_repo.save_with_events(user, &events).await?;
+        self.outbox_relay.dispatch(events).await;
+        Ok(())
+    }
 }
@@ -1,26 +1,34 @@
 use crate::domain::value_objects::email::EmailAddress;
 use chrono::Utc;
-use shared::domain::value_objects::{date::Date, pid::Pid, user_status::UserStatus};
+use derive_builder::Builder;
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid, user_status::UserStatus},
+    infrastructure::mailing::template::Locale,
+};
 
-#[derive(Debug, Clone)]
+/// `UserBuilder` is meant for repositories loading a stored user
+#[derive(Debug, Clone, Builder)]
+#[builder(setter(into))]
 pub struct User {
     pid: Pid,
     email: EmailAddress,
     status: UserStatus,
-    #[allow(dead_code)]
+    /// language the user is mailed in
+    locale: Locale,
     created_at: Date,
     last_login_at: Date,
 }
 
 impl User {
-    /// create a new user
-    pub fn new(email: EmailAddress) -> Self {
+    /// create a new user mailed in `locale`
+    pub fn new(email: EmailAddress, locale: Locale) -> Self {
         let pid = Pid::new();
 
         Self {
             pid,
             email,
             status: UserStatus::PendingProfile,
+            locale,
             created_at: Utc::now(),
             last_login_at: Utc::now(),
         }
@@ -50,4 +58,16 @@ impl User {
     pub fn get_status(&self) -> &UserStatus {
         &self.status
     }
+
+    pub fn get_locale(&self) -> Locale {
+        self.locale
+    }
+
+    pub fn get_created_at(&self) -> &Date {
+        &self.created_at
+    }
+
+    pub fn get_last_login_at(&self) -> &Date {
+        &self.last_login_at
+    }
 }
@@ -1,2 +1,4 @@
+pub mod outbound_mail;
 pub mod profile;
+pub mod refresh_token;
 pub mod session;
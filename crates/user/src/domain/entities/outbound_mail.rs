@@ -0,0 +1,149 @@
+use chrono::{TimeDelta, Utc};
+use derive_builder::Builder;
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{
+        mailing::Email,
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutboundMailStatus {
+    /// waiting for its next delivery attempt
+    Pending,
+    Sent,
+    /// gave up after the maximum number of attempts, kept until requeued
+    DeadLettered,
+}
+
+impl OutboundMailStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "Pending",
+            Self::Sent => "Sent",
+            Self::DeadLettered => "DeadLettered",
+        }
+    }
+}
+
+impl FromStr for OutboundMailStatus {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "Pending" => Ok(Self::Pending),
+            "Sent" => Ok(Self::Sent),
+            "DeadLettered" => Ok(Self::DeadLettered),
+            _ => Err(Error::ParseError),
+        }
+    }
+}
+
+/// An `Email` waiting in the outbox for the mail queue worker to deliver it
+#[derive(Debug, Clone, Builder)]
+#[builder(setter(into))]
+pub struct OutboundMail {
+    pid: Pid,
+    email: Email,
+    status: OutboundMailStatus,
+    attempts: u32,
+    next_attempt_at: Date,
+    last_error: Option<String>,
+    sent_at: Option<Date>,
+    created_at: Date,
+}
+
+impl OutboundMail {
+    pub fn new(email: Email) -> Self {
+        Self {
+            pid: Pid::new(),
+            email,
+            status: OutboundMailStatus::Pending,
+            attempts: 0,
+            next_attempt_at: Utc::now(),
+            last_error: None,
+            sent_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.status == OutboundMailStatus::Pending && self.next_attempt_at.le(&Utc::now())
+    }
+
+    /// holds the mail for the worker delivering it, it is not due again before `lease` elapsed
+    pub fn claim(&mut self, lease: TimeDelta) {
+        self.next_attempt_at = Utc::now() + lease;
+    }
+
+    /// drops the body along the way, it may hold a one time code nobody needs once delivered
+    pub fn mark_sent(&mut self) {
+        self.attempts += 1;
+        self.status = OutboundMailStatus::Sent;
+        self.sent_at = Some(Utc::now());
+        self.email.text.clear();
+        self.email.html.clear();
+    }
+
+    /// schedules the next attempt after `backoff`, or dead-letters the mail once `max_attempts` failed
+    pub fn record_failure(&mut self, error: String, max_attempts: u32, backoff: TimeDelta) {
+        self.attempts += 1;
+        self.last_error = Some(error);
+        if self.attempts >= max_attempts {
+            self.status = OutboundMailStatus::DeadLettered;
+        } else {
+            self.next_attempt_at = Utc::now() + backoff;
+        }
+    }
+
+    /// puts a dead letter back into the queue with a fresh attempt budget, keeping its last error
+    pub fn requeue(&mut self) -> Result<()> {
+        if self.status != OutboundMailStatus::DeadLettered {
+            return Err(Error::DomainError(DomainError::EntityInvalid));
+        }
+
+        self.status = OutboundMailStatus::Pending;
+        self.attempts = 0;
+        self.next_attempt_at = Utc::now();
+        Ok(())
+    }
+}
+
+/// Getters
+impl OutboundMail {
+    pub fn get_pid(&self) -> &Pid {
+        &self.pid
+    }
+
+    pub fn get_email(&self) -> &Email {
+        &self.email
+    }
+
+    pub fn get_status(&self) -> OutboundMailStatus {
+        self.status
+    }
+
+    pub fn get_attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn get_next_attempt_at(&self) -> &Date {
+        &self.next_attempt_at
+    }
+
+    pub fn get_last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    pub fn get_sent_at(&self) -> Option<&Date> {
+        self.sent_at.as_ref()
+    }
+
+    pub fn get_created_at(&self) -> &Date {
+        &self.created_at
+    }
+}
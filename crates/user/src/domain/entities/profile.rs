@@ -1,8 +1,9 @@
 use crate::domain::value_objects::display_name::DisplayName;
+use derive_builder::Builder;
 use shared::domain::value_objects::{pid::Pid, wallet_address::WalletAddress};
 
-#[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Builder)]
+#[builder(setter(into))]
 pub struct Profile {
     pid: Pid,
     user_id: Pid,
@@ -27,4 +28,12 @@ impl Profile {
     pub fn get_user_id(&self) -> &Pid {
         &self.user_id
     }
+
+    pub fn get_display_name(&self) -> &DisplayName {
+        &self.display_name
+    }
+
+    pub fn get_wallet_address(&self) -> &WalletAddress {
+        &self.wallet_address
+    }
 }
@@ -0,0 +1,111 @@
+use crate::domain::entities::session::Session;
+use chrono::{TimeDelta, Utc};
+use derive_builder::Builder;
+use rand::{Rng, rng};
+use sha2::{Digest, Sha256};
+use shared::domain::value_objects::{date::Date, pid::Pid};
+use std::str::FromStr;
+
+/// Single use token exchanged for a new access/refresh token pair.
+///
+/// Every token issued for a `Session` belongs to the same chain; `parent_id` links a
+/// rotated token to the one it replaced. Only the SHA-256 of the secret is stored.
+#[derive(Debug, Clone, Builder)]
+#[builder(setter(into))]
+pub struct RefreshToken {
+    pid: Pid,
+    session_id: Pid,
+    user_id: Pid,
+    secret_hash: String,
+    parent_id: Option<Pid>,
+    used_at: Option<Date>,
+    revoked_at: Option<Date>,
+    expires_at: Date,
+    created_at: Date,
+}
+
+impl RefreshToken {
+    /// issues a token for `session`, returning it with the opaque value handed to the client
+    pub fn issue(session: &Session, parent_id: Option<&Pid>) -> (Self, String) {
+        let secret: String = rng().random::<[u8; 32]>().iter().map(|b| format!("{b:02x}")).collect();
+        let token = Self {
+            pid: Pid::new(),
+            session_id: session.get_pid().clone(),
+            user_id: session.get_user_id().clone(),
+            secret_hash: Self::hash(&secret),
+            parent_id: parent_id.cloned(),
+            used_at: None,
+            revoked_at: None,
+            expires_at: Self::expiry().min(*session.get_expires_at()),
+            created_at: Utc::now(),
+        };
+
+        let value = format!("{}.{}", token.pid.to_string(), secret);
+        (token, value)
+    }
+
+    /// splits an opaque token value into the token id and its secret
+    pub fn parse(value: &str) -> Option<(Pid, String)> {
+        let (pid, secret) = value.split_once('.')?;
+        Some((Pid::from_str(pid).ok()?, secret.to_owned()))
+    }
+
+    pub fn matches(&self, secret: &str) -> bool {
+        self.secret_hash == Self::hash(secret)
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    pub fn has_expired(&self) -> bool {
+        self.expires_at.le(&Utc::now())
+    }
+
+    fn hash(secret: &str) -> String {
+        Sha256::digest(secret.as_bytes()).iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn expiry() -> Date {
+        Utc::now() + TimeDelta::days(30)
+    }
+}
+
+/// Getters
+impl RefreshToken {
+    pub fn get_pid(&self) -> &Pid {
+        &self.pid
+    }
+
+    pub fn get_session_id(&self) -> &Pid {
+        &self.session_id
+    }
+
+    pub fn get_user_id(&self) -> &Pid {
+        &self.user_id
+    }
+
+    pub fn get_secret_hash(&self) -> &str {
+        &self.secret_hash
+    }
+
+    pub fn get_parent_id(&self) -> Option<&Pid> {
+        self.parent_id.as_ref()
+    }
+
+    pub fn get_used_at(&self) -> Option<&Date> {
+        self.used_at.as_ref()
+    }
+
+    pub fn get_revoked_at(&self) -> Option<&Date> {
+        self.revoked_at.as_ref()
+    }
+
+    pub fn get_expires_at(&self) -> &Date {
+        &self.expires_at
+    }
+
+    pub fn get_created_at(&self) -> &Date {
+        &self.created_at
+    }
+}
@@ -1,14 +1,23 @@
 use crate::domain::value_objects::otp_code::OtpCode;
 use chrono::{TimeDelta, Utc};
+use derive_builder::Builder;
 use shared::domain::value_objects::{date::Date, pid::Pid};
+use subtle::ConstantTimeEq;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Builder)]
+#[builder(setter(into))]
 pub struct Session {
     pid: Pid,
     user_id: Pid,
     code: OtpCode,
     activated: bool,
     expires_at: Date,
+    /// wrong codes submitted for this session
+    #[builder(default)]
+    failed_attempts: u8,
+    /// a locked session can no longer be activated, whatever code is submitted
+    #[builder(default)]
+    locked: bool,
 }
 
 impl Session {
@@ -20,6 +29,8 @@ impl Session {
             code: OtpCode::six_digit(),
             activated: false,
             expires_at: Self::expiry(),
+            failed_attempts: 0,
+            locked: false,
         }
     }
 
@@ -35,6 +46,10 @@ impl Session {
         &self.code
     }
 
+    pub fn get_expires_at(&self) -> &Date {
+        &self.expires_at
+    }
+
     fn expiry() -> Date {
         Utc::now() + TimeDelta::minutes(10)
     }
@@ -47,15 +62,26 @@ impl Session {
         self.activated
     }
 
+    pub fn get_failed_attempts(&self) -> u8 {
+        self.failed_attempts
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// compares in constant time so response timing does not leak matching digits
     pub fn is_valid_code(&self, code: &str) -> bool {
-        self.code.to_string() == code.to_owned()
+        self.code.to_string().as_bytes().ct_eq(code.as_bytes()).into()
     }
 
     pub fn expire(&mut self) {
         self.expires_at = Utc::now() - TimeDelta::minutes(10)
     }
 
+    /// activating extends the session from the OTP window to the lifetime of its refresh chain
     pub fn activate(&mut self) {
         self.activated = true;
+        self.expires_at = Utc::now() + TimeDelta::days(30);
     }
 }
@@ -1,27 +1,74 @@
 use crate::domain::{
     aggregates::user::User,
-    entities::{profile::Profile, session::Session},
+    entities::{outbound_mail::OutboundMail, profile::Profile, refresh_token::RefreshToken, session::Session},
     value_objects::email::EmailAddress,
 };
 use async_trait::async_trait;
-use shared::{domain::value_objects::pid::Pid, infrastructure::types::Result};
+use chrono::TimeDelta;
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{messaging::outbox::RecordedEvent, types::Result},
+};
 
 #[async_trait]
-pub trait UserRepository {
+pub trait UserRepository: Sync + Send {
     async fn find_by_email(&self, email: &EmailAddress) -> Result<Option<User>>;
     async fn find_by_pid(&self, pid: &Pid) -> Result<Option<User>>;
     async fn save(&self, user: &User) -> Result<()>;
+    /// saves `user` and appends the events describing the change to the outbox as a single unit of work
+    async fn save_with_events(&self, user: &User, events: &[RecordedEvent]) -> Result<()>;
 }
 
 #[async_trait]
-pub trait SessionRepository {
+pub trait SessionRepository: Sync + Send {
     async fn find_by_pid(&self, pid: &Pid) -> Result<Option<Session>>;
     async fn save(&self, session: &Session) -> Result<()>;
+    /// saves `session` and appends the events describing the change to the outbox as a single unit of work
+    async fn save_with_events(&self, session: &Session, events: &[RecordedEvent]) -> Result<()>;
+    /// atomically counts an attempt at the code of an unexpired session that is neither activated nor locked
+    /// and has attempts left, so concurrent guesses can not exceed `max_attempts`; `None` when no attempt may be made
+    async fn claim_attempt(&self, pid: &Pid, max_attempts: u8) -> Result<Option<Session>>;
+    /// keeps a claimed attempt as a wrong code, locking the session once `max_attempts` were counted.
+    /// The events `record` derives from the session are appended to the outbox in the same unit of work
+    async fn record_failed_attempt(
+        &self,
+        pid: &Pid,
+        max_attempts: u8,
+        record: &(dyn for<'s> Fn(&'s Session) -> Result<Vec<RecordedEvent>> + Send + Sync),
+    ) -> Result<Option<(Session, Vec<RecordedEvent>)>>;
+    /// activates `session` unless it was activated or locked meanwhile, appending `events` to the outbox in the
+    /// same unit of work; `false` when nothing was activated. The claimed attempt is given back either way
+    async fn activate_with_events(&self, session: &Session, events: &[RecordedEvent]) -> Result<bool>;
     async fn expire_unused(&self, user_id: &Pid) -> Result<()>;
 }
 
 #[async_trait]
-pub trait ProfileRepository {
+pub trait ProfileRepository: Sync + Send {
     async fn find_by_user_id(&self, pid: &Pid) -> Result<Option<Profile>>;
     async fn save(&self, profile: &Profile) -> Result<()>;
+    /// saves `profile` and appends the events describing the change to the outbox as a single unit of work
+    async fn save_with_events(&self, profile: &Profile, events: &[RecordedEvent]) -> Result<()>;
+}
+
+#[async_trait]
+pub trait RefreshTokenRepository: Sync + Send {
+    async fn find_by_pid(&self, pid: &Pid) -> Result<Option<RefreshToken>>;
+    async fn save(&self, token: &RefreshToken) -> Result<()>;
+    /// spends the token unless it was spent already; `false` means it was presented before
+    async fn mark_used(&self, pid: &Pid) -> Result<bool>;
+    /// revokes every token of the session's chain that is not revoked yet
+    async fn revoke_for_session(&self, session_id: &Pid) -> Result<()>;
+}
+
+#[async_trait]
+pub trait MailQueueRepository: Sync + Send {
+    async fn find_by_pid(&self, pid: &Pid) -> Result<Option<OutboundMail>>;
+    async fn save(&self, mail: &OutboundMail) -> Result<()>;
+    /// claims the pending mails whose next attempt is due, oldest first, by moving their next attempt `lease` ahead;
+    /// concurrent workers never claim the same mail, a mail claimed by a worker that died is due again after the lease
+    async fn claim_due(&self, limit: u32, lease: TimeDelta) -> Result<Vec<OutboundMail>>;
+    /// most recently created dead letters first
+    async fn find_dead_letters(&self, limit: u32) -> Result<Vec<OutboundMail>>;
+    /// deletes the mails sent before `sent_before`, returns how many
+    async fn purge_sent(&self, sent_before: &Date) -> Result<u64>;
 }
@@ -1,4 +1,5 @@
 use rand::{Rng, rng};
+use std::{num::ParseIntError, str::FromStr};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct OtpCode(usize);
@@ -21,3 +22,10 @@ impl ToString for OtpCode {
         format!("{}", self.0)
     }
 }
+
+impl FromStr for OtpCode {
+    type Err = ParseIntError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
@@ -1,11 +1,17 @@
-use crate::{domain::aggregates::user::User, infrastructure::config::Config};
+use crate::{
+    domain::{aggregates::user::User, entities::session::Session},
+    infrastructure::{auth::keys, config::Config},
+};
 use chrono::{TimeDelta, Utc};
 use derive_builder::Builder;
 use di::injectable;
-use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use jsonwebtoken::{Algorithm, Header, Validation, decode, decode_header, encode, jwk::JwkSet};
 use serde::{Deserialize, Serialize};
-use shared::infrastructure::types::{self, Result};
-use std::sync::Arc;
+use shared::{
+    domain::value_objects::{pid::Pid, user_status::UserStatus},
+    infrastructure::types::{self, Result},
+};
+use std::{str::FromStr, sync::Arc};
 use thiserror::Error;
 
 #[injectable]
@@ -14,48 +20,67 @@ pub struct JWTService {
 }
 
 impl JWTService {
-    pub fn generate_token(&self, user: &User) -> Result<String> {
+    /// issues a token for `user` authenticated through `session`
+    pub fn generate_token(&self, user: &User, session: &Session) -> Result<String> {
+        let jwt = &self.config.jwt;
+        let now = Utc::now();
         let claims = ClaimsBuilder::default()
-            .exp(Self::get_expiry())
+            .aud(jwt.audience.as_str())
+            .iss(jwt.issuer.as_str())
+            .iat(now.timestamp())
+            .nbf(now.timestamp())
+            .exp((now + TimeDelta::seconds(jwt.ttl_seconds)).timestamp())
             .sub(user.get_pid().to_string())
+            .sid(session.get_pid().to_string())
+            .status(user.get_status().as_str())
             .build()
             .map_err(|_| types::error::Error::ServiceError)?;
 
-        let token = encode(
-            &Header::new(Self::get_alg()),
-            &claims,
-            &EncodingKey::from_secret(self.config.jwt.secret.as_bytes()),
-        )
-        .map_err(|_| types::error::Error::ServiceError)?;
-
-        Ok(token.to_string())
+        self.sign(&claims)
     }
 
-    pub fn decode_token(&self, token: &str) -> Result<Claims> {
-        decode::<Claims>(
-            &token,
-            &DecodingKey::from_secret(self.config.jwt.secret.as_bytes()),
-            &Self::get_validation(),
-        )
-        .map(|r| r.claims)
-        .map_err(|e| {
+    /// signs `claims` as they are with the active key, without checking them against the config
+    pub fn sign(&self, claims: &Claims) -> Result<String> {
+        let signing_key = keys::signing_key(&self.config.jwt)?;
+        let mut header = Header::new(signing_key.algorithm);
+        header.kid = signing_key.kid;
+
+        encode(&header, claims, &signing_key.key).map_err(|e| {
             println!("error: {e}");
             types::error::Error::ServiceError
         })
     }
 
-    fn get_expiry() -> i64 {
-        (Utc::now() + TimeDelta::days(1)).timestamp()
+    /// verifies `token` with the key named by its `kid`, active or retired
+    pub fn decode_token(&self, token: &str) -> Result<Claims> {
+        let header = decode_header(token).map_err(|e| {
+            println!("error: {e}");
+            types::error::Error::Unauthorized
+        })?;
+        let verification_key = keys::verification_key(&self.config.jwt, header.kid.as_deref())?;
+
+        decode::<Claims>(token, &verification_key.key, &self.get_validation(verification_key.algorithm))
+            .map(|r| r.claims)
+            .map_err(|e| {
+                println!("error: {e}");
+                types::error::Error::Unauthorized
+            })
     }
 
-    fn get_alg() -> Algorithm {
-        Algorithm::HS512
+    /// JWKS document other services verify tokens with, without holding any secret
+    pub fn jwks(&self) -> Result<JwkSet> {
+        keys::jwks(&self.config.jwt)
     }
 
-    fn get_validation() -> Validation {
-        let mut validation = Validation::new(Self::get_alg());
-        validation.set_audience(&["users.stash.it"]);
-        validation.set_issuer(&["auth.stash.it"]);
+    /// the algorithm is pinned by the resolved key, never taken from the token header
+    fn get_validation(&self, algorithm: Algorithm) -> Validation {
+        let jwt = &self.config.jwt;
+        let mut validation = Validation::new(algorithm);
+        validation.set_audience(&[&jwt.audience]);
+        validation.set_issuer(&[&jwt.issuer]);
+        validation.set_required_spec_claims(&["exp", "nbf", "aud", "iss", "sub"]);
+        validation.validate_nbf = true;
+        validation.leeway = jwt.leeway_seconds;
         validation
     }
 }
@@ -78,19 +103,35 @@ impl Into<types::error::Error> for TokenGenerationError {
 #[builder(setter(into))]
 pub struct Claims {
     /// Audience
-    #[builder(default = "users.stash.it".into())]
-    aud: String,
+    pub aud: String,
     /// Expiration time (as UTC timestamp)
-    exp: i64,
+    pub exp: i64,
     /// Issued at (as UTC timestamp)
     #[builder(default = Utc::now().timestamp())]
-    iat: i64,
+    pub iat: i64,
     /// Issuer
-    #[builder(default = "auth.stash.it".into())]
-    iss: String,
+    pub iss: String,
     /// Not Before (as UTC timestamp)
     #[builder(default = Utc::now().timestamp())]
-    nbf: i64,
+    pub nbf: i64,
     /// Subject (whom token refers to). value should be a `Pid`
     pub sub: String,
+    /// Session the token was issued for. value should be a `Pid`
+    pub sid: String,
+    /// Status of the user when the token was issued
+    pub status: String,
+}
+
+impl Claims {
+    pub fn user_id(&self) -> Result<Pid> {
+        Pid::from_str(&self.sub).map_err(|_| types::error::Error::Unauthorized)
+    }
+
+    pub fn session_id(&self) -> Result<Pid> {
+        Pid::from_str(&self.sid).map_err(|_| types::error::Error::Unauthorized)
+    }
+
+    pub fn user_status(&self) -> Result<UserStatus> {
+        UserStatus::from_str(&self.status).map_err(|_| types::error::Error::Unauthorized)
+    }
 }
@@ -0,0 +1,142 @@
+use crate::infrastructure::config::{JWTConfig, JWTKeyConfig};
+use aws_lc_rs::rsa::{PublicKey as RsaPublicKey, PublicKeyComponents};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey,
+    jwk::{
+        AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm, OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse,
+        RSAKeyParameters, RSAKeyType,
+    },
+};
+use shared::infrastructure::types::{Result, error::Error};
+
+/// DER prefix of an Ed25519 `SubjectPublicKeyInfo`, followed by the 32 byte public key
+const ED25519_SPKI_PREFIX: [u8; 12] = [0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+
+/// Key new tokens are signed with
+pub struct SigningKey {
+    pub kid: Option<String>,
+    pub algorithm: Algorithm,
+    pub key: EncodingKey,
+}
+
+/// Key a token is verified with, resolved from its header
+pub struct VerificationKey {
+    pub algorithm: Algorithm,
+    pub key: DecodingKey,
+}
+
+pub fn signing_key(config: &JWTConfig) -> Result<SigningKey> {
+    let Some(kid) = &config.active_key else {
+        return Ok(SigningKey {
+            kid: None,
+            algorithm: config.algorithm,
+            key: EncodingKey::from_secret(config.secret.as_bytes()),
+        });
+    };
+
+    let key = find_key(config, kid).ok_or_else(|| invalid_key(kid, "active key is not configured"))?;
+    let private_key = key
+        .private_key
+        .as_ref()
+        .ok_or_else(|| invalid_key(kid, "active key has no private key"))?;
+    let encoding_key = match key.algorithm {
+        Algorithm::RS256 => EncodingKey::from_rsa_pem(private_key.as_bytes()),
+        Algorithm::EdDSA => EncodingKey::from_ed_pem(private_key.as_bytes()),
+        _ => return Err(invalid_key(kid, "unsupported algorithm")),
+    }
+    .map_err(|e| invalid_key(kid, &e.to_string()))?;
+
+    Ok(SigningKey {
+        kid: Some(kid.to_owned()),
+        algorithm: key.algorithm,
+        key: encoding_key,
+    })
+}
+
+/// resolves the key for a token header `kid`; tokens without one fall back to the HMAC secret,
+/// but only while tokens are signed with it, never once an asymmetric key is active
+pub fn verification_key(config: &JWTConfig, kid: Option<&str>) -> Result<VerificationKey> {
+    let Some(kid) = kid else {
+        if config.active_key.is_some() || config.secret.is_empty() || !is_hmac(config.algorithm) {
+            return Err(Error::Unauthorized);
+        }
+
+        return Ok(VerificationKey {
+            algorithm: config.algorithm,
+            key: DecodingKey::from_secret(config.secret.as_bytes()),
+        });
+    };
+
+    let key = find_key(config, kid).ok_or(Error::Unauthorized)?;
+    let decoding_key = match key.algorithm {
+        Algorithm::RS256 => DecodingKey::from_rsa_pem(key.public_key.as_bytes()),
+        Algorithm::EdDSA => DecodingKey::from_ed_pem(key.public_key.as_bytes()),
+        _ => return Err(invalid_key(kid, "unsupported algorithm")),
+    }
+    .map_err(|e| invalid_key(kid, &e.to_string()))?;
+
+    Ok(VerificationKey {
+        algorithm: key.algorithm,
+        key: decoding_key,
+    })
+}
+
+/// public half of every configured key as a JWKS document
+pub fn jwks(config: &JWTConfig) -> Result<JwkSet> {
+    let keys = config.keys.iter().map(jwk).collect::<Result<Vec<_>>>()?;
+    Ok(JwkSet { keys })
+}
+
+fn jwk(key: &JWTKeyConfig) -> Result<Jwk> {
+    let der = pem::parse(&key.public_key).map_err(|e| invalid_key(&key.kid, &e.to_string()))?;
+    let (key_algorithm, algorithm) = match key.algorithm {
+        Algorithm::RS256 => {
+            let public_key = RsaPublicKey::from_der(der.contents()).map_err(|e| invalid_key(&key.kid, &e.to_string()))?;
+            let components = PublicKeyComponents::<Vec<u8>>::from(&public_key);
+            let parameters = RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: URL_SAFE_NO_PAD.encode(components.n),
+                e: URL_SAFE_NO_PAD.encode(components.e),
+            };
+            (KeyAlgorithm::RS256, AlgorithmParameters::RSA(parameters))
+        }
+        Algorithm::EdDSA => {
+            let x = der
+                .contents()
+                .strip_prefix(&ED25519_SPKI_PREFIX)
+                .filter(|x| x.len() == 32)
+                .ok_or_else(|| invalid_key(&key.kid, "not an Ed25519 public key"))?;
+            let parameters = OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(x),
+            };
+            (KeyAlgorithm::EdDSA, AlgorithmParameters::OctetKeyPair(parameters))
+        }
+        _ => return Err(invalid_key(&key.kid, "unsupported algorithm")),
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            key_algorithm: Some(key_algorithm),
+            key_id: Some(key.kid.to_owned()),
+            ..Default::default()
+        },
+        algorithm,
+    })
+}
+
+fn is_hmac(algorithm: Algorithm) -> bool {
+    matches!(algorithm, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512)
+}
+
+fn find_key<'a>(config: &'a JWTConfig, kid: &str) -> Option<&'a JWTKeyConfig> {
+    config.keys.iter().find(|key| key.kid == kid)
+}
+
+fn invalid_key(kid: &str, reason: &str) -> Error {
+    println!("invalid jwt key {kid}: {reason}");
+    Error::ServiceError
+}
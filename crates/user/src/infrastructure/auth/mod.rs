@@ -1 +1,3 @@
 pub mod jwt_service;
+pub mod keys;
+pub mod session_verifier;
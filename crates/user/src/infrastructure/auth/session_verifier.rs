@@ -0,0 +1,32 @@
+use crate::{application::auth::AuthenticationService, infrastructure::auth::jwt_service::JWTService};
+use async_trait::async_trait;
+use di::injectable;
+use shared::infrastructure::{
+    http::auth::{AuthenticatedUser, TokenVerifier},
+    types::{Result, error::Error},
+};
+use std::sync::Arc;
+
+/// Accepts access tokens only while the session they were issued for is active,
+/// so logging out or a detected refresh token reuse revokes them before they expire
+#[injectable(TokenVerifier)]
+pub struct SessionTokenVerifier {
+    jwt_service: Arc<JWTService>,
+    auth_service: Arc<AuthenticationService>,
+}
+
+#[async_trait]
+impl TokenVerifier for SessionTokenVerifier {
+    async fn verify(&self, token: &str) -> Result<AuthenticatedUser> {
+        let claims = self.jwt_service.decode_token(token)?;
+        let session_id = claims.session_id()?;
+        if !self.auth_service.is_valid_session(&session_id).await? {
+            return Err(Error::Unauthorized);
+        }
+
+        Ok(AuthenticatedUser {
+            user_id: claims.user_id()?,
+            session_id,
+        })
+    }
+}
@@ -1,13 +1,192 @@
+use chrono::TimeDelta;
 use di::injectable;
-use serde::Deserialize;
+use jsonwebtoken::Algorithm;
+use serde::{Deserialize, Deserializer, de::Error};
+use shared::infrastructure::{
+    mailing::{smtp::SmtpConfig, template::Locale},
+    rate_limiting::RateLimitPolicy,
+};
+use std::time::Duration;
 
-#[derive(Clone, Debug, Default, Deserialize)]
+/// Asymmetric key identified by `kid` in token headers and the JWKS document
+#[derive(Clone, Debug, Deserialize)]
+pub struct JWTKeyConfig {
+    pub kid: String,
+    /// `RS256` or `EdDSA`
+    pub algorithm: Algorithm,
+    /// PEM encoded public key, published in the JWKS document
+    pub public_key: String,
+    /// PEM encoded private key, only needed for the active key
+    #[serde(default)]
+    pub private_key: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
 pub struct JWTConfig {
+    /// HMAC secret used to sign tokens when no `active_key` is set, and to verify tokens without a `kid`.
+    /// Leave empty to only accept tokens signed by `keys`.
     pub secret: String,
+    /// `kid` of the key in `keys` new tokens are signed with
+    pub active_key: Option<String>,
+    /// active and retired keys; retired keys only verify tokens issued before a rotation
+    pub keys: Vec<JWTKeyConfig>,
+    /// `iss` claim issued and required
+    pub issuer: String,
+    /// `aud` claim issued and required
+    pub audience: String,
+    /// lifetime of an issued token
+    pub ttl_seconds: i64,
+    /// HMAC algorithm used with `secret`, one of `HS256`, `HS384` or `HS512`
+    pub algorithm: Algorithm,
+    /// clock skew tolerated when checking `exp` and `nbf`
+    pub leeway_seconds: u64,
+}
+
+impl JWTConfig {
+    /// rejects configs that would sign tokens with an empty secret or a non HMAC algorithm
+    pub fn validate(&self) -> Result<(), String> {
+        if self.active_key.is_none() && self.secret.is_empty() {
+            return Err("jwt.secret must be set when no jwt.active_key is configured".to_owned());
+        }
+
+        if !matches!(self.algorithm, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+            return Err(format!("jwt.algorithm must be HS256, HS384 or HS512, got {:?}", self.algorithm));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for JWTConfig {
+    fn default() -> Self {
+        Self {
+            secret: String::new(),
+            active_key: None,
+            keys: Vec::new(),
+            issuer: "auth.stash.it".to_owned(),
+            audience: "users.stash.it".to_owned(),
+            ttl_seconds: 60 * 60 * 24,
+            algorithm: Algorithm::HS512,
+            leeway_seconds: 60,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// wrong OTP codes tolerated before a session is locked
+    pub max_otp_attempts: u8,
+    /// OTP mails sent to one email address
+    pub otp_email_rate_limit: RateLimitPolicy,
+    /// OTP mails sent to one user account
+    pub otp_user_rate_limit: RateLimitPolicy,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            max_otp_attempts: 5,
+            otp_email_rate_limit: RateLimitPolicy::new(3, Duration::from_secs(5 * 60)),
+            otp_user_rate_limit: RateLimitPolicy::new(5, Duration::from_secs(5 * 60)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct MailingConfig {
+    /// mailbox used for the `From` header
+    pub sender: String,
+    /// language users are mailed in when they sign up without choosing one
+    pub locale: Locale,
+    /// relay `add_mailer` delivers mails through, the stub mailer is registered when missing
+    pub smtp: Option<SmtpConfig>,
+    pub queue: MailQueueConfig,
+}
+
+impl Default for MailingConfig {
+    fn default() -> Self {
+        Self {
+            sender: "noreply@stash.it".to_owned(),
+            locale: Locale::default(),
+            smtp: None,
+            queue: MailQueueConfig::default(),
+        }
+    }
+}
+
+/// Retry schedule of the outbound mail queue worker
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct MailQueueConfig {
+    /// failed deliveries before a mail is dead-lettered
+    pub max_attempts: u32,
+    /// wait after the first failure, doubled with every further one
+    pub base_backoff_seconds: u64,
+    pub max_backoff_seconds: u64,
+    /// mails delivered per worker run
+    pub batch_size: u32,
+    pub poll_interval_seconds: u64,
+    /// how long a worker holds the mails it claimed before another worker may deliver them
+    pub lease_seconds: u64,
+    /// how long sent mails are kept before the worker deletes them
+    pub sent_retention_seconds: u64,
+}
+
+impl MailQueueConfig {
+    /// exponential backoff after `attempts` failed deliveries, capped at `max_backoff_seconds`
+    pub fn backoff(&self, attempts: u32) -> TimeDelta {
+        let factor = 2u64.saturating_pow(attempts.saturating_sub(1));
+        let seconds = self.base_backoff_seconds.saturating_mul(factor).min(self.max_backoff_seconds);
+        TimeDelta::seconds(seconds.min(i64::MAX as u64) as i64)
+    }
+
+    pub fn lease(&self) -> TimeDelta {
+        TimeDelta::seconds(self.lease_seconds.min(i64::MAX as u64) as i64)
+    }
+
+    pub fn sent_retention(&self) -> TimeDelta {
+        TimeDelta::seconds(self.sent_retention_seconds.min(i64::MAX as u64) as i64)
+    }
+}
+
+impl Default for MailQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_backoff_seconds: 30,
+            max_backoff_seconds: 60 * 60,
+            batch_size: 50,
+            poll_interval_seconds: 5,
+            lease_seconds: 5 * 60,
+            sent_retention_seconds: 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct DatabaseConfig {
+    /// sqlite connection url, e.g. `sqlite://stash-it.db`
+    pub url: String,
 }
 
 #[injectable]
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct Config {
+    #[serde(deserialize_with = "validated_jwt_config")]
     pub jwt: JWTConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub mailing: MailingConfig,
+    #[serde(default)]
+    pub database: DatabaseConfig,
+}
+
+fn validated_jwt_config<'de, D: Deserializer<'de>>(deserializer: D) -> Result<JWTConfig, D::Error> {
+    let jwt = JWTConfig::deserialize(deserializer)?;
+    jwt.validate().map_err(D::Error::custom)?;
+    Ok(jwt)
 }
@@ -0,0 +1,4 @@
+pub mod profile_created;
+pub mod registry;
+pub mod stash_status_updated;
+pub mod user_created;
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use di::injectable;
+use shared::{
+    domain::events::user::ProfileCreatedEvent,
+    infrastructure::{
+        messaging::{EventHandler, TypedEventHandler},
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+
+use crate::application::{mailing::MailingService, user::UserManagementService};
+
+/// Confirms the profile a user created
+#[injectable(EventHandler)]
+pub struct OnProfileCreated {
+    user_service: Arc<UserManagementService>,
+    mailing_service: Arc<MailingService>,
+}
+
+#[async_trait]
+impl TypedEventHandler for OnProfileCreated {
+    type Event = ProfileCreatedEvent;
+
+    async fn handle(&self, event: &ProfileCreatedEvent) -> Result<()> {
+        let user = self
+            .user_service
+            .get_user_by_pid(&event.user_id)
+            .await?
+            .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+        let profile = self
+            .user_service
+            .get_user_profile(&event.user_id)
+            .await?
+            .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+
+        self.mailing_service.send_profile_created_mail(&user, &profile).await
+    }
+}
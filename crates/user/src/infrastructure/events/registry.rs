@@ -0,0 +1,23 @@
+use shared::{
+    domain::events::{
+        stash::StashStatusUpdatedEvent,
+        user::{
+            OtpVerificationFailedEvent, ProfileCreatedEvent, SessionActivatedEvent, SessionTerminatedEvent, UserCreatedEvent, UserStatusUpdatedEvent,
+        },
+    },
+    infrastructure::messaging::envelope::EventRegistry,
+};
+
+/// Registry of every event the user services emit, plus the stash events they consume
+pub fn event_registry() -> EventRegistry {
+    let mut registry = EventRegistry::default();
+    registry
+        .register::<UserCreatedEvent>(1)
+        .register::<ProfileCreatedEvent>(1)
+        .register::<SessionActivatedEvent>(1)
+        .register::<SessionTerminatedEvent>(1)
+        .register::<UserStatusUpdatedEvent>(1)
+        .register::<OtpVerificationFailedEvent>(1)
+        .register::<StashStatusUpdatedEvent>(1);
+    registry
+}
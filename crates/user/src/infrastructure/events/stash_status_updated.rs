@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use di::injectable;
+use shared::{
+    domain::events::stash::StashStatusUpdatedEvent,
+    infrastructure::{
+        messaging::{EventHandler, TypedEventHandler, event::DomainEvent},
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+
+use crate::application::{mailing::MailingService, user::UserManagementService};
+
+/// Tells the owner of a stash about its new status
+#[injectable(EventHandler)]
+pub struct OnStashStatusUpdated {
+    user_service: Arc<UserManagementService>,
+    mailing_service: Arc<MailingService>,
+}
+
+#[async_trait]
+impl TypedEventHandler for OnStashStatusUpdated {
+    type Event = StashStatusUpdatedEvent;
+
+    async fn handle(&self, event: &StashStatusUpdatedEvent) -> Result<()> {
+        let user = self
+            .user_service
+            .get_user_by_pid(&event.user_id)
+            .await?
+            .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+
+        self.mailing_service
+            .send_stash_status_changed_mail(&user, &event.aggregate_id(), event.old_status.as_str(), event.new_status.as_str())
+            .await
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use di::injectable;
+use shared::{
+    domain::events::user::UserCreatedEvent,
+    infrastructure::{
+        messaging::{EventHandler, TypedEventHandler},
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+
+use crate::application::{mailing::MailingService, user::UserManagementService};
+
+/// Welcomes users signing up
+#[injectable(EventHandler)]
+pub struct OnUserCreated {
+    user_service: Arc<UserManagementService>,
+    mailing_service: Arc<MailingService>,
+}
+
+#[async_trait]
+impl TypedEventHandler for OnUserCreated {
+    type Event = UserCreatedEvent;
+
+    async fn handle(&self, event: &UserCreatedEvent) -> Result<()> {
+        let user = self
+            .user_service
+            .get_user_by_pid(&event.user_id)
+            .await?
+            .ok_or(Error::DomainError(DomainError::EntityNotFound))?;
+
+        self.mailing_service.send_welcome_mail(&user).await
+    }
+}
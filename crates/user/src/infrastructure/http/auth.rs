@@ -0,0 +1,52 @@
+use crate::infrastructure::http::{
+    AuthApi,
+    dto::{ActivateSessionRequest, RefreshRequest, RequestOtpRequest, RequestOtpResponse, TokenResponse},
+};
+use axum::{extract::State, http::StatusCode};
+use jsonwebtoken::jwk::JwkSet;
+use shared::{
+    domain::value_objects::pid::Pid,
+    infrastructure::{
+        http::{
+            auth::AuthenticatedUser,
+            extract::{Json, Path},
+        },
+        types::Result,
+    },
+};
+use std::sync::Arc;
+
+pub(super) async fn request_otp(
+    State(api): State<Arc<AuthApi>>,
+    Json(request): Json<RequestOtpRequest>,
+) -> Result<(StatusCode, Json<RequestOtpResponse>)> {
+    let session_id = api.auth_service.create_new_session(&request.email, request.locale).await?;
+    let response = RequestOtpResponse {
+        session_id: session_id.to_string(),
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub(super) async fn activate_session(
+    State(api): State<Arc<AuthApi>>,
+    Path(session_id): Path<Pid>,
+    Json(request): Json<ActivateSessionRequest>,
+) -> Result<Json<TokenResponse>> {
+    let tokens = api.auth_service.activate_session(&session_id, &request.code).await?;
+    Ok(Json(tokens.into()))
+}
+
+pub(super) async fn refresh(State(api): State<Arc<AuthApi>>, Json(request): Json<RefreshRequest>) -> Result<Json<TokenResponse>> {
+    let tokens = api.auth_service.rotate(&request.refresh_token).await?;
+    Ok(Json(tokens.into()))
+}
+
+pub(super) async fn logout(State(api): State<Arc<AuthApi>>, AuthenticatedUser { user_id, session_id }: AuthenticatedUser) -> Result<StatusCode> {
+    api.auth_service.logout(&user_id, &session_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(super) async fn jwks(State(api): State<Arc<AuthApi>>) -> Result<Json<JwkSet>> {
+    Ok(Json(api.jwt_service.jwks()?))
+}
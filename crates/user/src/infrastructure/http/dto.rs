@@ -0,0 +1,44 @@
+use crate::{application::auth::TokenPair, domain::value_objects::email::EmailAddress};
+use serde::{Deserialize, Serialize};
+use shared::infrastructure::mailing::template::Locale;
+
+#[derive(Debug, Deserialize)]
+pub struct RequestOtpRequest {
+    pub email: EmailAddress,
+    /// language mails are sent in when the address signs up
+    #[serde(default)]
+    pub locale: Option<Locale>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequestOtpResponse {
+    /// session the emailed code has to be exchanged with
+    pub session_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivateSessionRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: &'static str,
+}
+
+impl From<TokenPair> for TokenResponse {
+    fn from(tokens: TokenPair) -> Self {
+        Self {
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+            token_type: "Bearer",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
@@ -0,0 +1,39 @@
+use crate::{application::auth::AuthenticationService, infrastructure::auth::jwt_service::JWTService};
+use axum::{
+    Extension, Router, middleware,
+    routing::{get, post},
+};
+use di::injectable;
+use shared::infrastructure::http::{auth::TokenVerifier, correlation};
+use std::sync::Arc;
+
+mod auth;
+pub mod dto;
+
+/// HTTP entrypoint of the OTP authentication flow
+#[injectable]
+pub struct AuthApi {
+    auth_service: Arc<AuthenticationService>,
+    jwt_service: Arc<JWTService>,
+    token_verifier: Arc<dyn TokenVerifier>,
+}
+
+impl AuthApi {
+    /// verifier other routers install to resolve `AuthenticatedUser`
+    pub fn token_verifier(&self) -> Arc<dyn TokenVerifier> {
+        self.token_verifier.clone()
+    }
+
+    pub fn router(self: Arc<Self>) -> Router {
+        let token_verifier = self.token_verifier();
+        Router::new()
+            .route("/auth/sessions", post(auth::request_otp))
+            .route("/auth/sessions/{session_id}/activate", post(auth::activate_session))
+            .route("/auth/refresh", post(auth::refresh))
+            .route("/auth/logout", post(auth::logout))
+            .route("/.well-known/jwks.json", get(auth::jwks))
+            .layer(Extension(token_verifier))
+            .layer(middleware::from_fn(correlation::correlate))
+            .with_state(self)
+    }
+}
@@ -1,2 +1,6 @@
 pub mod auth;
 pub mod config;
+pub mod events;
+pub mod http;
+pub mod persistence;
+pub mod workers;
@@ -0,0 +1,132 @@
+use crate::{
+    domain::{
+        entities::outbound_mail::{OutboundMail, OutboundMailBuilder, OutboundMailStatus},
+        repositories::MailQueueRepository,
+    },
+    infrastructure::persistence::{db_error, parse},
+};
+use async_trait::async_trait;
+use chrono::{TimeDelta, Utc};
+use di::injectable;
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{
+        mailing::Email,
+        types::{Result, error::Error},
+    },
+};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use std::sync::Arc;
+
+const SELECT_MAIL: &str = "SELECT pid, email, status, attempts, next_attempt_at, last_error, sent_at, created_at FROM mail_outbox";
+
+#[derive(FromRow)]
+struct OutboundMailRow {
+    pid: String,
+    email: Json<Email>,
+    status: String,
+    attempts: u32,
+    next_attempt_at: Date,
+    last_error: Option<String>,
+    sent_at: Option<Date>,
+    created_at: Date,
+}
+
+impl TryFrom<OutboundMailRow> for OutboundMail {
+    type Error = Error;
+    fn try_from(row: OutboundMailRow) -> Result<Self> {
+        OutboundMailBuilder::default()
+            .pid(parse::<Pid>(&row.pid)?)
+            .email(row.email.0)
+            .status(parse::<OutboundMailStatus>(&row.status)?)
+            .attempts(row.attempts)
+            .next_attempt_at(row.next_attempt_at)
+            .last_error(row.last_error)
+            .sent_at(row.sent_at)
+            .created_at(row.created_at)
+            .build()
+            .map_err(|e| Error::BuilderError(e.to_string()))
+    }
+}
+
+#[injectable(MailQueueRepository)]
+pub struct SqliteMailQueueRepository {
+    pool: Arc<SqlitePool>,
+}
+
+#[async_trait]
+impl MailQueueRepository for SqliteMailQueueRepository {
+    async fn find_by_pid(&self, pid: &Pid) -> Result<Option<OutboundMail>> {
+        let row: Option<OutboundMailRow> = sqlx::query_as(&format!("{SELECT_MAIL} WHERE pid = ?"))
+            .bind(pid.to_string())
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        row.map(OutboundMail::try_from).transpose()
+    }
+
+    async fn save(&self, mail: &OutboundMail) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO mail_outbox (pid, email, status, attempts, next_attempt_at, last_error, sent_at, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT (pid) DO UPDATE SET email = excluded.email, status = excluded.status, attempts = excluded.attempts, \
+             next_attempt_at = excluded.next_attempt_at, last_error = excluded.last_error, sent_at = excluded.sent_at",
+        )
+        .bind(mail.get_pid().to_string())
+        .bind(Json(mail.get_email()))
+        .bind(mail.get_status().as_str())
+        .bind(mail.get_attempts())
+        .bind(mail.get_next_attempt_at())
+        .bind(mail.get_last_error())
+        .bind(mail.get_sent_at())
+        .bind(mail.get_created_at())
+        .execute(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn claim_due(&self, limit: u32, lease: TimeDelta) -> Result<Vec<OutboundMail>> {
+        let now = Utc::now();
+        let mut rows: Vec<OutboundMailRow> = sqlx::query_as(
+            "UPDATE mail_outbox SET next_attempt_at = ? WHERE pid IN \
+             (SELECT pid FROM mail_outbox WHERE status = ? AND next_attempt_at <= ? ORDER BY next_attempt_at LIMIT ?) \
+             RETURNING pid, email, status, attempts, next_attempt_at, last_error, sent_at, created_at",
+        )
+        .bind(now + lease)
+        .bind(OutboundMailStatus::Pending.as_str())
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.sort_by_key(|row| row.created_at);
+        rows.into_iter().map(OutboundMail::try_from).collect()
+    }
+
+    async fn find_dead_letters(&self, limit: u32) -> Result<Vec<OutboundMail>> {
+        let rows: Vec<OutboundMailRow> = sqlx::query_as(&format!("{SELECT_MAIL} WHERE status = ? ORDER BY created_at DESC LIMIT ?"))
+            .bind(OutboundMailStatus::DeadLettered.as_str())
+            .bind(limit)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        rows.into_iter().map(OutboundMail::try_from).collect()
+    }
+
+    async fn purge_sent(&self, sent_before: &Date) -> Result<u64> {
+        let purged = sqlx::query("DELETE FROM mail_outbox WHERE status = ? AND sent_at < ?")
+            .bind(OutboundMailStatus::Sent.as_str())
+            .bind(sent_before)
+            .execute(&*self.pool)
+            .await
+            .map_err(db_error)?
+            .rows_affected();
+
+        Ok(purged)
+    }
+}
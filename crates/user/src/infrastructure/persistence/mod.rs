@@ -0,0 +1,67 @@
+use crate::infrastructure::persistence::{
+    mail_queue_repository::SqliteMailQueueRepository, outbox::SqliteOutbox, profile_repository::SqliteProfileRepository,
+    refresh_token_repository::SqliteRefreshTokenRepository, session_repository::SqliteSessionRepository, user_repository::SqliteUserRepository,
+};
+use di::{Injectable, ServiceCollection, singleton_as_self};
+use shared::infrastructure::types::{
+    Result,
+    error::{DomainError, Error},
+};
+use sqlx::{
+    SqlitePool,
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+use std::{str::FromStr, sync::Arc};
+
+pub mod mail_queue_repository;
+pub mod outbox;
+pub mod profile_repository;
+pub mod refresh_token_repository;
+pub mod session_repository;
+pub mod user_repository;
+
+/// Migrations for the identity schema, embedded at compile time from `crates/user/migrations`
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Opens (creating it when missing) the sqlite database at `database_url` and brings its schema up to date
+pub async fn connect(database_url: &str) -> Result<SqlitePool> {
+    let options = SqliteConnectOptions::from_str(database_url)
+        .map_err(db_error)?
+        .create_if_missing(true)
+        .foreign_keys(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.map_err(db_error)?;
+    MIGRATOR.run(&pool).await.map_err(|e| {
+        println!("failed to run user migrations: {e}");
+        Error::ServiceError
+    })?;
+
+    Ok(pool)
+}
+
+/// Registers `pool` and the sqlite backed `UserRepository`, `SessionRepository`, `ProfileRepository`, `RefreshTokenRepository`, `MailQueueRepository` and `Outbox`
+pub fn add_sqlite_repositories(services: &mut ServiceCollection, pool: SqlitePool) -> &mut ServiceCollection {
+    let pool = Arc::new(pool);
+    services
+        .add(singleton_as_self::<SqlitePool>().from(move |_| pool.clone()))
+        .add(SqliteUserRepository::singleton())
+        .add(SqliteSessionRepository::singleton())
+        .add(SqliteProfileRepository::singleton())
+        .add(SqliteRefreshTokenRepository::singleton())
+        .add(SqliteMailQueueRepository::singleton())
+        .add(SqliteOutbox::singleton())
+}
+
+pub(crate) fn db_error(e: sqlx::Error) -> Error {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => Error::DomainError(DomainError::EntityAlreadyExist),
+        _ => {
+            println!("database error: {e}");
+            Error::ServiceError
+        }
+    }
+}
+
+pub(crate) fn parse<T: FromStr>(value: &str) -> Result<T> {
+    T::from_str(value).map_err(|_| Error::ParseError)
+}
@@ -0,0 +1,117 @@
+use crate::infrastructure::persistence::{db_error, parse};
+use async_trait::async_trait;
+use chrono::Utc;
+use di::injectable;
+use serde_json::Value;
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{
+        messaging::{envelope::EventEnvelope, outbox::Outbox},
+        types::{Result, error::Error},
+    },
+};
+use sqlx::{FromRow, SqliteConnection, SqlitePool, types::Json};
+use std::sync::Arc;
+
+#[derive(FromRow)]
+struct OutboxRow {
+    pid: String,
+    event_type: String,
+    schema_version: u32,
+    aggregate_id: String,
+    correlation_id: Option<String>,
+    causation_id: Option<String>,
+    payload: Json<Value>,
+    occurred_at: Date,
+}
+
+impl TryFrom<OutboxRow> for EventEnvelope {
+    type Error = Error;
+    fn try_from(row: OutboxRow) -> Result<Self> {
+        Ok(Self {
+            event_id: parse::<Pid>(&row.pid)?,
+            event_type: row.event_type,
+            schema_version: row.schema_version,
+            aggregate_id: parse::<Pid>(&row.aggregate_id)?,
+            correlation_id: row.correlation_id,
+            causation_id: row.causation_id,
+            payload: row.payload.0,
+            occurred_at: row.occurred_at,
+        })
+    }
+}
+
+#[injectable(Outbox)]
+pub struct SqliteOutbox {
+    pool: Arc<SqlitePool>,
+}
+
+impl SqliteOutbox {
+    /// inserts `envelopes` on the sqlite connection holding the repository's open transaction
+    pub(super) async fn insert(connection: &mut SqliteConnection, envelopes: &[EventEnvelope]) -> Result<()> {
+        for envelope in envelopes {
+            sqlx::query(
+                "INSERT INTO event_outbox (pid, event_type, schema_version, aggregate_id, correlation_id, causation_id, payload, occurred_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(envelope.event_id.to_string())
+            .bind(&envelope.event_type)
+            .bind(envelope.schema_version)
+            .bind(envelope.aggregate_id.to_string())
+            .bind(&envelope.correlation_id)
+            .bind(&envelope.causation_id)
+            .bind(Json(&envelope.payload))
+            .bind(envelope.occurred_at)
+            .execute(&mut *connection)
+            .await
+            .map_err(db_error)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Outbox for SqliteOutbox {
+    async fn append(&self, envelopes: &[EventEnvelope]) -> Result<()> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        Self::insert(&mut tx, envelopes).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn pending(&self, limit: u32) -> Result<Vec<EventEnvelope>> {
+        let rows: Vec<OutboxRow> = sqlx::query_as(
+            "SELECT pid, event_type, schema_version, aggregate_id, correlation_id, causation_id, payload, occurred_at \
+             FROM event_outbox WHERE published_at IS NULL AND failed_at IS NULL ORDER BY id LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        rows.into_iter().map(EventEnvelope::try_from).collect()
+    }
+
+    async fn mark_published(&self, id: &Pid) -> Result<()> {
+        sqlx::query("UPDATE event_outbox SET published_at = ? WHERE pid = ? AND published_at IS NULL")
+            .bind(Utc::now())
+            .bind(id.to_string())
+            .execute(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(&self, id: &Pid, error: &str) -> Result<()> {
+        sqlx::query("UPDATE event_outbox SET failed_at = ?, last_error = ? WHERE pid = ? AND published_at IS NULL")
+            .bind(Utc::now())
+            .bind(error)
+            .bind(id.to_string())
+            .execute(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+}
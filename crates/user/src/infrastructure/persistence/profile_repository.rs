@@ -0,0 +1,88 @@
+use crate::{
+    domain::{
+        entities::profile::{Profile, ProfileBuilder},
+        repositories::ProfileRepository,
+        value_objects::display_name::DisplayName,
+    },
+    infrastructure::persistence::{db_error, outbox::SqliteOutbox, parse},
+};
+use async_trait::async_trait;
+use di::injectable;
+use shared::{
+    domain::value_objects::{pid::Pid, wallet_address::WalletAddress},
+    infrastructure::{
+        messaging::{envelope::EventEnvelope, outbox::RecordedEvent},
+        types::{Result, error::Error},
+    },
+};
+use sqlx::{FromRow, SqliteExecutor, SqlitePool};
+use std::sync::Arc;
+
+#[derive(FromRow)]
+struct ProfileRow {
+    pid: String,
+    user_id: String,
+    display_name: String,
+    wallet_address: String,
+}
+
+impl TryFrom<ProfileRow> for Profile {
+    type Error = Error;
+    fn try_from(row: ProfileRow) -> Result<Self> {
+        ProfileBuilder::default()
+            .pid(parse::<Pid>(&row.pid)?)
+            .user_id(parse::<Pid>(&row.user_id)?)
+            .display_name(parse::<DisplayName>(&row.display_name)?)
+            .wallet_address(parse::<WalletAddress>(&row.wallet_address)?)
+            .build()
+            .map_err(|e| Error::BuilderError(e.to_string()))
+    }
+}
+
+#[injectable(ProfileRepository)]
+pub struct SqliteProfileRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl SqliteProfileRepository {
+    async fn upsert<'e>(executor: impl SqliteExecutor<'e>, profile: &Profile) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO profiles (pid, user_id, display_name, wallet_address) VALUES (?, ?, ?, ?) \
+             ON CONFLICT (pid) DO UPDATE SET display_name = excluded.display_name, wallet_address = excluded.wallet_address",
+        )
+        .bind(profile.get_pid().to_string())
+        .bind(profile.get_user_id().to_string())
+        .bind(profile.get_display_name().to_string())
+        .bind(profile.get_wallet_address().to_string())
+        .execute(executor)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProfileRepository for SqliteProfileRepository {
+    async fn find_by_user_id(&self, pid: &Pid) -> Result<Option<Profile>> {
+        let row: Option<ProfileRow> = sqlx::query_as("SELECT pid, user_id, display_name, wallet_address FROM profiles WHERE user_id = ?")
+            .bind(pid.to_string())
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        row.map(Profile::try_from).transpose()
+    }
+
+    async fn save(&self, profile: &Profile) -> Result<()> {
+        Self::upsert(&*self.pool, profile).await
+    }
+
+    async fn save_with_events(&self, profile: &Profile, events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        Self::upsert(&mut *tx, profile).await?;
+        SqliteOutbox::insert(&mut tx, &envelopes).await?;
+        tx.commit().await.map_err(db_error)
+    }
+}
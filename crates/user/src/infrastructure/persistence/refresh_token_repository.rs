@@ -0,0 +1,112 @@
+use crate::{
+    domain::{
+        entities::refresh_token::{RefreshToken, RefreshTokenBuilder},
+        repositories::RefreshTokenRepository,
+    },
+    infrastructure::persistence::{db_error, parse},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use di::injectable;
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::types::{Result, error::Error},
+};
+use sqlx::{FromRow, SqlitePool};
+use std::sync::Arc;
+
+#[derive(FromRow)]
+struct RefreshTokenRow {
+    pid: String,
+    session_id: String,
+    user_id: String,
+    secret_hash: String,
+    parent_id: Option<String>,
+    used_at: Option<Date>,
+    revoked_at: Option<Date>,
+    expires_at: Date,
+    created_at: Date,
+}
+
+impl TryFrom<RefreshTokenRow> for RefreshToken {
+    type Error = Error;
+    fn try_from(row: RefreshTokenRow) -> Result<Self> {
+        RefreshTokenBuilder::default()
+            .pid(parse::<Pid>(&row.pid)?)
+            .session_id(parse::<Pid>(&row.session_id)?)
+            .user_id(parse::<Pid>(&row.user_id)?)
+            .secret_hash(row.secret_hash)
+            .parent_id(row.parent_id.as_deref().map(parse::<Pid>).transpose()?)
+            .used_at(row.used_at)
+            .revoked_at(row.revoked_at)
+            .expires_at(row.expires_at)
+            .created_at(row.created_at)
+            .build()
+            .map_err(|e| Error::BuilderError(e.to_string()))
+    }
+}
+
+#[injectable(RefreshTokenRepository)]
+pub struct SqliteRefreshTokenRepository {
+    pool: Arc<SqlitePool>,
+}
+
+#[async_trait]
+impl RefreshTokenRepository for SqliteRefreshTokenRepository {
+    async fn find_by_pid(&self, pid: &Pid) -> Result<Option<RefreshToken>> {
+        let row: Option<RefreshTokenRow> = sqlx::query_as(
+            "SELECT pid, session_id, user_id, secret_hash, parent_id, used_at, revoked_at, expires_at, created_at \
+             FROM refresh_tokens WHERE pid = ?",
+        )
+        .bind(pid.to_string())
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(RefreshToken::try_from).transpose()
+    }
+
+    async fn save(&self, token: &RefreshToken) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO refresh_tokens (pid, session_id, user_id, secret_hash, parent_id, used_at, revoked_at, expires_at, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT (pid) DO UPDATE SET used_at = excluded.used_at, revoked_at = excluded.revoked_at",
+        )
+        .bind(token.get_pid().to_string())
+        .bind(token.get_session_id().to_string())
+        .bind(token.get_user_id().to_string())
+        .bind(token.get_secret_hash())
+        .bind(token.get_parent_id().map(|pid| pid.to_string()))
+        .bind(token.get_used_at())
+        .bind(token.get_revoked_at())
+        .bind(token.get_expires_at())
+        .bind(token.get_created_at())
+        .execute(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn mark_used(&self, pid: &Pid) -> Result<bool> {
+        let result = sqlx::query("UPDATE refresh_tokens SET used_at = ? WHERE pid = ? AND used_at IS NULL")
+            .bind(Utc::now())
+            .bind(pid.to_string())
+            .execute(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn revoke_for_session(&self, session_id: &Pid) -> Result<()> {
+        sqlx::query("UPDATE refresh_tokens SET revoked_at = ? WHERE session_id = ? AND revoked_at IS NULL")
+            .bind(Utc::now())
+            .bind(session_id.to_string())
+            .execute(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{
+    domain::{
+        entities::session::{Session, SessionBuilder},
+        repositories::SessionRepository,
+        value_objects::otp_code::OtpCode,
+    },
+    infrastructure::persistence::{db_error, outbox::SqliteOutbox, parse},
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use di::injectable;
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{
+        messaging::{envelope::EventEnvelope, outbox::RecordedEvent},
+        types::{Result, error::Error},
+    },
+};
+use sqlx::{FromRow, SqliteExecutor, SqlitePool};
+use std::sync::Arc;
+
+#[derive(FromRow)]
+struct SessionRow {
+    pid: String,
+    user_id: String,
+    code: String,
+    activated: bool,
+    expires_at: Date,
+    failed_attempts: u8,
+    locked: bool,
+}
+
+impl TryFrom<SessionRow> for Session {
+    type Error = Error;
+    fn try_from(row: SessionRow) -> Result<Self> {
+        SessionBuilder::default()
+            .pid(parse::<Pid>(&row.pid)?)
+            .user_id(parse::<Pid>(&row.user_id)?)
+            .code(parse::<OtpCode>(&row.code)?)
+            .activated(row.activated)
+            .expires_at(row.expires_at)
+            .failed_attempts(row.failed_attempts)
+            .locked(row.locked)
+            .build()
+            .map_err(|e| Error::BuilderError(e.to_string()))
+    }
+}
+
+#[injectable(SessionRepository)]
+pub struct SqliteSessionRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl SqliteSessionRepository {
+    async fn upsert<'e>(executor: impl SqliteExecutor<'e>, session: &Session) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO sessions (pid, user_id, code, activated, expires_at, failed_attempts, locked) VALUES (?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT (pid) DO UPDATE SET activated = excluded.activated, expires_at = excluded.expires_at",
+        )
+        .bind(session.get_pid().to_string())
+        .bind(session.get_user_id().to_string())
+        .bind(session.get_code().to_string())
+        .bind(session.activated())
+        .bind(session.get_expires_at())
+        .bind(session.get_failed_attempts())
+        .bind(session.is_locked())
+        .execute(executor)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SqliteSessionRepository {
+    async fn find_by_pid(&self, pid: &Pid) -> Result<Option<Session>> {
+        let row: Option<SessionRow> =
+            sqlx::query_as("SELECT pid, user_id, code, activated, expires_at, failed_attempts, locked FROM sessions WHERE pid = ?")
+                .bind(pid.to_string())
+                .fetch_optional(&*self.pool)
+                .await
+                .map_err(db_error)?;
+
+        row.map(Session::try_from).transpose()
+    }
+
+    async fn save(&self, session: &Session) -> Result<()> {
+        Self::upsert(&*self.pool, session).await
+    }
+
+    async fn save_with_events(&self, session: &Session, events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        Self::upsert(&mut *tx, session).await?;
+        SqliteOutbox::insert(&mut tx, &envelopes).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    async fn claim_attempt(&self, pid: &Pid, max_attempts: u8) -> Result<Option<Session>> {
+        let row: Option<SessionRow> = sqlx::query_as(
+            "UPDATE sessions SET failed_attempts = failed_attempts + 1 \
+             WHERE pid = ? AND activated = 0 AND locked = 0 AND failed_attempts < ? AND expires_at > ? \
+             RETURNING pid, user_id, code, activated, expires_at, failed_attempts, locked",
+        )
+        .bind(pid.to_string())
+        .bind(max_attempts)
+        .bind(Utc::now())
+        .fetch_optional(&*self.pool)
+        .await
+        .map_err(db_error)?;
+
+        row.map(Session::try_from).transpose()
+    }
+
+    async fn record_failed_attempt(
+        &self,
+        pid: &Pid,
+        max_attempts: u8,
+        record: &(dyn for<'s> Fn(&'s Session) -> Result<Vec<RecordedEvent>> + Send + Sync),
+    ) -> Result<Option<(Session, Vec<RecordedEvent>)>> {
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        let row: Option<SessionRow> = sqlx::query_as(
+            "UPDATE sessions SET locked = (locked OR failed_attempts >= ?) \
+             WHERE pid = ? RETURNING pid, user_id, code, activated, expires_at, failed_attempts, locked",
+        )
+        .bind(max_attempts)
+        .bind(pid.to_string())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        let Some(session) = row.map(Session::try_from).transpose()? else {
+            return Ok(None);
+        };
+        let events = record(&session)?;
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        SqliteOutbox::insert(&mut tx, &envelopes).await?;
+        tx.commit().await.map_err(db_error)?;
+
+        Ok(Some((session, events)))
+    }
+
+    async fn activate_with_events(&self, session: &Session, events: &[RecordedEvent]) -> Result<bool> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        let activated = sqlx::query(
+            "UPDATE sessions SET activated = 1, expires_at = ?, failed_attempts = MAX(failed_attempts - 1, 0) \
+             WHERE pid = ? AND activated = 0 AND locked = 0",
+        )
+        .bind(session.get_expires_at())
+        .bind(session.get_pid().to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .rows_affected()
+            > 0;
+        if !activated {
+            // the attempt carried the right code, so it must not stay counted as a wrong one
+            sqlx::query("UPDATE sessions SET failed_attempts = MAX(failed_attempts - 1, 0) WHERE pid = ?")
+                .bind(session.get_pid().to_string())
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+            tx.commit().await.map_err(db_error)?;
+            return Ok(false);
+        }
+
+        SqliteOutbox::insert(&mut tx, &envelopes).await?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(true)
+    }
+
+    async fn expire_unused(&self, user_id: &Pid) -> Result<()> {
+        let now = Utc::now();
+        sqlx::query("UPDATE sessions SET expires_at = ? WHERE user_id = ? AND activated = 0 AND expires_at > ?")
+            .bind(now)
+            .bind(user_id.to_string())
+            .bind(now)
+            .execute(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+}
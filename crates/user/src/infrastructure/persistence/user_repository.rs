@@ -0,0 +1,106 @@
+use crate::{
+    domain::{
+        aggregates::user::{User, UserBuilder},
+        repositories::UserRepository,
+        value_objects::email::EmailAddress,
+    },
+    infrastructure::persistence::{db_error, outbox::SqliteOutbox, parse},
+};
+use async_trait::async_trait;
+use di::injectable;
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid, user_status::UserStatus},
+    infrastructure::{
+        mailing::template::Locale,
+        messaging::{envelope::EventEnvelope, outbox::RecordedEvent},
+        types::{Result, error::Error},
+    },
+};
+use sqlx::{FromRow, SqliteExecutor, SqlitePool};
+use std::sync::Arc;
+
+const SELECT_USER: &str = "SELECT pid, email, status, locale, created_at, last_login_at FROM users";
+
+#[derive(FromRow)]
+struct UserRow {
+    pid: String,
+    email: String,
+    status: String,
+    locale: String,
+    created_at: Date,
+    last_login_at: Date,
+}
+
+impl TryFrom<UserRow> for User {
+    type Error = Error;
+    fn try_from(row: UserRow) -> Result<Self> {
+        UserBuilder::default()
+            .pid(parse::<Pid>(&row.pid)?)
+            .email(parse::<EmailAddress>(&row.email)?)
+            .status(parse::<UserStatus>(&row.status)?)
+            .locale(parse::<Locale>(&row.locale)?)
+            .created_at(row.created_at)
+            .last_login_at(row.last_login_at)
+            .build()
+            .map_err(|e| Error::BuilderError(e.to_string()))
+    }
+}
+
+#[injectable(UserRepository)]
+pub struct SqliteUserRepository {
+    pool: Arc<SqlitePool>,
+}
+
+impl SqliteUserRepository {
+    async fn upsert<'e>(executor: impl SqliteExecutor<'e>, user: &User) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO users (pid, email, status, locale, created_at, last_login_at) VALUES (?, ?, ?, ?, ?, ?) \
+             ON CONFLICT (pid) DO UPDATE SET email = excluded.email, status = excluded.status, locale = excluded.locale, \
+             last_login_at = excluded.last_login_at",
+        )
+        .bind(user.get_pid().to_string())
+        .bind(user.get_email().to_string())
+        .bind(user.get_status().as_str())
+        .bind(user.get_locale().as_str())
+        .bind(user.get_created_at())
+        .bind(user.get_last_login_at())
+        .execute(executor)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn find_one(&self, column: &str, value: String) -> Result<Option<User>> {
+        let row: Option<UserRow> = sqlx::query_as(&format!("{SELECT_USER} WHERE {column} = ?"))
+            .bind(value)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(db_error)?;
+
+        row.map(User::try_from).transpose()
+    }
+}
+
+#[async_trait]
+impl UserRepository for SqliteUserRepository {
+    async fn find_by_email(&self, email: &EmailAddress) -> Result<Option<User>> {
+        self.find_one("email", email.to_string()).await
+    }
+
+    async fn find_by_pid(&self, pid: &Pid) -> Result<Option<User>> {
+        self.find_one("pid", pid.to_string()).await
+    }
+
+    async fn save(&self, user: &User) -> Result<()> {
+        Self::upsert(&*self.pool, user).await
+    }
+
+    async fn save_with_events(&self, user: &User, events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+        Self::upsert(&mut *tx, user).await?;
+        SqliteOutbox::insert(&mut tx, &envelopes).await?;
+        tx.commit().await.map_err(db_error)
+    }
+}
@@ -0,0 +1,11 @@
+use crate::application::mail_queue::MailQueueService;
+use di::ServiceProvider;
+use shared::infrastructure::messaging::workers;
+use tokio::task::JoinHandle;
+
+/// starts the background tasks of the user context next to its router; abort the handles to stop them
+pub fn spawn_workers(provider: &ServiceProvider) -> Vec<JoinHandle<()>> {
+    let mut workers = workers::spawn_workers(provider);
+    workers.push(provider.get_required::<MailQueueService>().spawn_worker());
+    workers
+}
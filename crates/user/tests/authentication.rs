@@ -1,31 +1,50 @@
-use crate::common::{bootstrap::bootstrap, prepare::prepare_authenticated_user, string_utils::extract_otp};
+use crate::common::{
+    bootstrap::{bootstrap, stub_services},
+    delivery::deliver_queued_mails,
+    prepare::prepare_authenticated_user,
+    string_utils::extract_otp,
+};
 use insta::{assert_debug_snapshot, with_settings};
 use shared::{
     configure_insta,
-    domain::events::user::{SessionActivatedEvent, SessionTerminatedEvent, UserCreatedEvent},
-    infrastructure::{mailing::Mailer, messaging::EventBus, types::Result},
+    domain::events::user::{OtpVerificationFailedEvent, SessionActivatedEvent, SessionTerminatedEvent, UserCreatedEvent},
+    infrastructure::{
+        messaging::EventBus,
+        rate_limiting::RateLimitPolicy,
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
     testing::insta_filters::redactions::cleanup_model_generics,
 };
-use std::{str::FromStr, sync::Arc};
+use std::{str::FromStr, sync::Arc, time::Duration};
 use user::{
-    application::{auth::AuthenticationService, user::UserManagementService},
+    application::{auth::AuthenticationService, session::SessionManagementService, user::UserManagementService},
     domain::value_objects::email::EmailAddress,
 };
 
-mod common;
+mod common {
+    pub mod bootstrap;
+    pub mod delivery;
+    pub mod prepare;
+    pub mod repositories;
+    pub mod services;
+    pub mod session;
+    pub mod string_utils;
+}
 
 #[tokio::test]
 async fn can_create_auth_session() -> Result<()> {
     // Arrange
     configure_insta!();
     let provider = bootstrap();
-    let mailer = provider.get_required::<dyn Mailer>();
     let authentication_service: Arc<AuthenticationService> = provider.get_required();
     let email = EmailAddress::from_str("tom@stash.it").unwrap();
 
     // Act
-    let result = authentication_service.create_new_session(&email).await;
-    let deliveries = mailer.deliveries().await;
+    let result = authentication_service.create_new_session(&email, None).await;
+    let deliveries = deliver_queued_mails(&provider).await;
     let code = extract_otp(&deliveries.messages.first().unwrap()).unwrap();
 
     // Assert
@@ -82,3 +101,119 @@ async fn can_terminate_auth_session() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn wrong_otp_is_counted_and_reported() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let session_service: Arc<SessionManagementService> = provider.get_required();
+    let email = EmailAddress::from_str("tom@stash.it").unwrap();
+    let session_id = authentication_service.create_new_session(&email, None).await?;
+
+    // Act
+    let result = authentication_service.activate_session(&session_id, "000000").await;
+
+    // Assert
+    assert!(matches!(result, Err(Error::DomainError(DomainError::EntityInvalid))));
+    let session = session_service.get_session_by_id(&session_id).await?.unwrap();
+    assert_eq!(session.get_failed_attempts(), 1, "failed attempt must be counted");
+    assert!(!session.is_locked(), "session must not be locked yet");
+    let event = OtpVerificationFailedEvent::new(session.get_user_id(), &session_id, 1, false);
+    assert!(event_bus.published(event).await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn session_is_locked_after_max_otp_attempts() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let session_service: Arc<SessionManagementService> = provider.get_required();
+    let email = EmailAddress::from_str("tom@stash.it").unwrap();
+    let session_id = authentication_service.create_new_session(&email, None).await?;
+    let deliveries = deliver_queued_mails(&provider).await;
+    let code = extract_otp(&deliveries.messages.first().unwrap()).unwrap();
+
+    // Act
+    for _ in 0..3 {
+        let _ = authentication_service.activate_session(&session_id, "000000").await;
+    }
+    let result = authentication_service.activate_session(&session_id, &code).await;
+
+    // Assert
+    assert!(
+        matches!(result, Err(Error::DomainError(DomainError::EntityInvalid))),
+        "locked session must reject the right code"
+    );
+    let session = session_service.get_session_by_id(&session_id).await?.unwrap();
+    assert!(session.is_locked(), "session must be locked");
+    assert_eq!(session.get_failed_attempts(), 3, "attempts on a locked session must not be counted");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn otp_requests_are_rate_limited_per_email() -> Result<()> {
+    // Arrange
+    let provider = stub_services(|config| config.auth.otp_user_rate_limit = unlimited())
+        .build_provider()
+        .unwrap();
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let email = EmailAddress::from_str("tom@stash.it").unwrap();
+    for _ in 0..3 {
+        authentication_service.create_new_session(&email, None).await?;
+    }
+
+    // Act
+    let result = authentication_service.create_new_session(&email, None).await;
+    let other = authentication_service
+        .create_new_session(&EmailAddress::from_str("jerry@stash.it").unwrap(), None)
+        .await;
+
+    // Assert
+    assert!(
+        matches!(result, Err(Error::TooManyRequests { .. })),
+        "fourth request must be limited, got: {result:?}"
+    );
+    assert!(other.is_ok(), "other addresses must not be limited");
+    assert_eq!(deliver_queued_mails(&provider).await.count, 4, "limited request must not send a mail");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn otp_requests_are_rate_limited_per_user() -> Result<()> {
+    // Arrange
+    let provider = stub_services(|config| config.auth.otp_email_rate_limit = unlimited())
+        .build_provider()
+        .unwrap();
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let email = EmailAddress::from_str("tom@stash.it").unwrap();
+    for _ in 0..3 {
+        authentication_service.create_new_session(&email, None).await?;
+    }
+
+    // Act
+    let result = authentication_service.create_new_session(&email, None).await;
+    let other = authentication_service
+        .create_new_session(&EmailAddress::from_str("jerry@stash.it").unwrap(), None)
+        .await;
+
+    // Assert
+    assert!(
+        matches!(result, Err(Error::TooManyRequests { .. })),
+        "fourth request for the user must be limited, got: {result:?}"
+    );
+    assert!(other.is_ok(), "other users must not be limited");
+    assert_eq!(deliver_queued_mails(&provider).await.count, 4, "limited request must not send a mail");
+
+    Ok(())
+}
+
+/// a limit that never triggers in a test, so the other OTP limit triggers on its own
+fn unlimited() -> RateLimitPolicy {
+    RateLimitPolicy::new(u32::MAX, Duration::from_secs(600))
+}
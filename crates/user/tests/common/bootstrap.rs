@@ -1,29 +1,28 @@
-use di::{Injectable, ServiceCollection, ServiceProvider, singleton_as_self};
-use shared::infrastructure::{config::get_config, mailing::stub_mailer::StubMailer, messaging::memory::InMemoryEventBus};
-use std::sync::Arc;
+use di::{Injectable, ServiceCollection, ServiceProvider};
+use shared::infrastructure::{config::get_config, messaging::outbox::memory::InMemoryOutbox};
+use user::infrastructure::config::Config;
 
-use user::{
-    application::{auth::AuthenticationService, mailing::MailingService, session::SessionManagementService, user::UserManagementService},
-    infrastructure::{auth::jwt_service::JWTService, config::Config},
+use crate::common::{
+    repositories::{StubMailQueueRepository, StubProfileRepository, StubRefreshTokenRepository, StubSessionRepository, StubUserRepository},
+    services::services,
 };
 
-use crate::common::repositories::{StubProfileRepository, StubSessionRepository, StubUserRepository};
-
 pub fn bootstrap() -> ServiceProvider {
-    let config = Arc::new(get_config::<Config>().unwrap());
+    stub_services(|_| {}).build_provider().unwrap()
+}
+
+/// Services backed by the stub repositories with the test configuration adjusted by `configure`
+pub fn stub_services(configure: impl FnOnce(&mut Config)) -> ServiceCollection {
+    let mut config = get_config::<Config>().unwrap();
+    configure(&mut config);
 
-    ServiceCollection::new()
-        .add(singleton_as_self::<Config>().from(move |_| config.clone()))
-        .add(AuthenticationService::singleton())
-        .add(MailingService::singleton())
-        .add(SessionManagementService::singleton())
-        .add(UserManagementService::singleton())
-        .add(JWTService::singleton())
+    let mut services = services(config);
+    services
         .add(StubUserRepository::singleton())
         .add(StubSessionRepository::singleton())
         .add(StubProfileRepository::singleton())
-        .add(InMemoryEventBus::singleton())
-        .add(StubMailer::singleton())
-        .build_provider()
-        .unwrap()
+        .add(StubRefreshTokenRepository::singleton())
+        .add(StubMailQueueRepository::singleton())
+        .add(InMemoryOutbox::singleton());
+    services
 }
@@ -0,0 +1,18 @@
+use di::ServiceProvider;
+use shared::{domain::value_objects::pid::Pid, infrastructure::config::get_config};
+use user::infrastructure::{
+    config::Config,
+    persistence::{self, add_sqlite_repositories},
+};
+
+use crate::common::services::services;
+
+/// Builds a provider backed by a fresh sqlite database file in the temp directory
+pub async fn bootstrap_sqlite() -> ServiceProvider {
+    let database = std::env::temp_dir().join(format!("stash-it-{}.db", Pid::new().to_string()));
+    let pool = persistence::connect(&format!("sqlite://{}", database.display())).await.unwrap();
+
+    let mut services = services(get_config::<Config>().unwrap());
+    add_sqlite_repositories(&mut services, pool);
+    services.build_provider().unwrap()
+}
@@ -0,0 +1,11 @@
+use di::ServiceProvider;
+use shared::infrastructure::mailing::{Deliveries, Mailer};
+use std::sync::Arc;
+use user::application::mail_queue::MailQueueService;
+
+/// runs the mail queue worker once and returns everything the stub mailer delivered so far
+pub async fn deliver_queued_mails(provider: &ServiceProvider) -> Deliveries {
+    let mail_queue: Arc<MailQueueService> = provider.get_required();
+    mail_queue.process_due().await.unwrap();
+    provider.get_required::<dyn Mailer>().deliveries().await
+}
@@ -1,4 +0,0 @@
-pub mod bootstrap;
-pub mod prepare;
-pub mod repositories;
-pub mod string_utils;
@@ -1,18 +1,34 @@
 use async_trait::async_trait;
+use chrono::{TimeDelta, Utc};
 use di::injectable;
-use shared::{domain::value_objects::pid::Pid, infrastructure::types::Result};
+use shared::{
+    domain::value_objects::{date::Date, pid::Pid},
+    infrastructure::{
+        messaging::{
+            envelope::EventEnvelope,
+            outbox::{Outbox, RecordedEvent},
+        },
+        types::Result,
+    },
+};
+use std::sync::Arc;
 use tokio::sync::Mutex;
 use user::domain::{
     aggregates::user::User,
-    entities::{profile::Profile, session::Session},
-    repositories::{ProfileRepository, SessionRepository, UserRepository},
+    entities::{
+        outbound_mail::{OutboundMail, OutboundMailStatus},
+        profile::Profile,
+        refresh_token::{RefreshToken, RefreshTokenBuilder},
+        session::{Session, SessionBuilder},
+    },
+    repositories::{MailQueueRepository, ProfileRepository, RefreshTokenRepository, SessionRepository, UserRepository},
     value_objects::email::EmailAddress,
 };
 
 #[injectable(SessionRepository)]
-#[derive(Default)]
 pub struct StubSessionRepository {
     sessions: Mutex<Vec<Session>>,
+    outbox: Arc<dyn Outbox>,
 }
 
 #[async_trait]
@@ -30,17 +46,83 @@ impl SessionRepository for StubSessionRepository {
         Ok(())
     }
 
+    async fn save_with_events(&self, session: &Session, events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        self.save(session).await?;
+        self.outbox.append(&envelopes).await
+    }
+
+    async fn claim_attempt(&self, pid: &Pid, max_attempts: u8) -> Result<Option<Session>> {
+        let mut sessions = self.sessions.lock().await;
+        let Some(session) = sessions.iter_mut().find(|s| s.get_pid() == pid) else {
+            return Ok(None);
+        };
+        if session.activated() || session.is_locked() || session.get_failed_attempts() >= max_attempts || session.has_expired() {
+            return Ok(None);
+        }
+        *session = with_attempts(session, session.get_failed_attempts() + 1, false);
+        Ok(Some(session.clone()))
+    }
+
+    async fn record_failed_attempt(
+        &self,
+        pid: &Pid,
+        max_attempts: u8,
+        record: &(dyn for<'s> Fn(&'s Session) -> Result<Vec<RecordedEvent>> + Send + Sync),
+    ) -> Result<Option<(Session, Vec<RecordedEvent>)>> {
+        let mut sessions = self.sessions.lock().await;
+        let Some(session) = sessions.iter_mut().find(|s| s.get_pid() == pid) else {
+            return Ok(None);
+        };
+        let locked = session.is_locked() || session.get_failed_attempts() >= max_attempts;
+        *session = with_attempts(session, session.get_failed_attempts(), locked);
+        let events = record(session)?;
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        self.outbox.append(&envelopes).await?;
+        Ok(Some((session.clone(), events)))
+    }
+
+    async fn activate_with_events(&self, session: &Session, events: &[RecordedEvent]) -> Result<bool> {
+        let mut sessions = self.sessions.lock().await;
+        let Some(stored) = sessions.iter_mut().find(|s| s.get_pid() == session.get_pid()) else {
+            return Ok(false);
+        };
+        let failed_attempts = stored.get_failed_attempts().saturating_sub(1);
+        if stored.activated() || stored.is_locked() {
+            *stored = with_attempts(stored, failed_attempts, stored.is_locked());
+            return Ok(false);
+        }
+        *stored = with_attempts(session, failed_attempts, false);
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        self.outbox.append(&envelopes).await?;
+        Ok(true)
+    }
+
     async fn expire_unused(&self, user_id: &Pid) -> Result<()> {
         let mut sessions = self.sessions.lock().await;
-        sessions.retain(|u| u.get_user_id() != user_id);
+        sessions.retain(|s| s.get_user_id() != user_id || s.activated());
         Ok(())
     }
 }
 
+/// `session` with the attempt counters the sqlite repository keeps in its columns
+fn with_attempts(session: &Session, failed_attempts: u8, locked: bool) -> Session {
+    SessionBuilder::default()
+        .pid(session.get_pid().clone())
+        .user_id(session.get_user_id().clone())
+        .code(session.get_code().clone())
+        .activated(session.activated())
+        .expires_at(*session.get_expires_at())
+        .failed_attempts(failed_attempts)
+        .locked(locked)
+        .build()
+        .unwrap()
+}
+
 #[injectable(ProfileRepository)]
-#[derive(Default)]
 pub struct StubProfileRepository {
     profiles: Mutex<Vec<Profile>>,
+    outbox: Arc<dyn Outbox>,
 }
 
 #[async_trait]
@@ -57,12 +139,18 @@ impl ProfileRepository for StubProfileRepository {
         profiles.push(profile.clone());
         Ok(())
     }
+
+    async fn save_with_events(&self, profile: &Profile, events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        self.save(profile).await?;
+        self.outbox.append(&envelopes).await
+    }
 }
 
 #[injectable(UserRepository)]
-#[derive(Default)]
 pub struct StubUserRepository {
     users: Mutex<Vec<User>>,
+    outbox: Arc<dyn Outbox>,
 }
 
 #[async_trait]
@@ -85,4 +173,125 @@ impl UserRepository for StubUserRepository {
         users.push(user.clone());
         Ok(())
     }
+
+    async fn save_with_events(&self, user: &User, events: &[RecordedEvent]) -> Result<()> {
+        let envelopes: Vec<EventEnvelope> = events.iter().map(|recorded| recorded.envelope.clone()).collect();
+        self.save(user).await?;
+        self.outbox.append(&envelopes).await
+    }
+}
+
+#[injectable(RefreshTokenRepository)]
+#[derive(Default)]
+pub struct StubRefreshTokenRepository {
+    tokens: Mutex<Vec<RefreshToken>>,
+}
+
+#[async_trait]
+impl RefreshTokenRepository for StubRefreshTokenRepository {
+    async fn find_by_pid(&self, pid: &Pid) -> Result<Option<RefreshToken>> {
+        let tokens = self.tokens.lock().await;
+        let token = tokens.iter().find(|t| t.get_pid() == pid).cloned();
+        Ok(token)
+    }
+
+    async fn save(&self, token: &RefreshToken) -> Result<()> {
+        let mut tokens = self.tokens.lock().await;
+        tokens.retain(|t| t.get_pid() != token.get_pid());
+        tokens.push(token.clone());
+        Ok(())
+    }
+
+    async fn mark_used(&self, pid: &Pid) -> Result<bool> {
+        let mut tokens = self.tokens.lock().await;
+        match tokens.iter_mut().find(|t| t.get_pid() == pid) {
+            Some(token) if token.get_used_at().is_none() => {
+                *token = with_timestamps(token, Some(Utc::now()), token.get_revoked_at().copied());
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn revoke_for_session(&self, session_id: &Pid) -> Result<()> {
+        let mut tokens = self.tokens.lock().await;
+        for token in tokens.iter_mut().filter(|t| t.get_session_id() == session_id) {
+            let revoked_at = token.get_revoked_at().copied().unwrap_or_else(Utc::now);
+            *token = with_timestamps(token, token.get_used_at().copied(), Some(revoked_at));
+        }
+        Ok(())
+    }
+}
+
+/// `token` with the `used_at` and `revoked_at` columns the sqlite repository updates
+fn with_timestamps(token: &RefreshToken, used_at: Option<Date>, revoked_at: Option<Date>) -> RefreshToken {
+    RefreshTokenBuilder::default()
+        .pid(token.get_pid().clone())
+        .session_id(token.get_session_id().clone())
+        .user_id(token.get_user_id().clone())
+        .secret_hash(token.get_secret_hash())
+        .parent_id(token.get_parent_id().cloned())
+        .used_at(used_at)
+        .revoked_at(revoked_at)
+        .expires_at(*token.get_expires_at())
+        .created_at(*token.get_created_at())
+        .build()
+        .unwrap()
+}
+
+#[injectable(MailQueueRepository)]
+#[derive(Default)]
+pub struct StubMailQueueRepository {
+    mails: Mutex<Vec<OutboundMail>>,
+}
+
+#[async_trait]
+impl MailQueueRepository for StubMailQueueRepository {
+    async fn find_by_pid(&self, pid: &Pid) -> Result<Option<OutboundMail>> {
+        let mails = self.mails.lock().await;
+        let mail = mails.iter().find(|m| m.get_pid() == pid).cloned();
+        Ok(mail)
+    }
+
+    async fn save(&self, mail: &OutboundMail) -> Result<()> {
+        let mut mails = self.mails.lock().await;
+        match mails.iter_mut().find(|m| m.get_pid() == mail.get_pid()) {
+            Some(existing) => *existing = mail.clone(),
+            None => mails.push(mail.clone()),
+        }
+        Ok(())
+    }
+
+    async fn claim_due(&self, limit: u32, lease: TimeDelta) -> Result<Vec<OutboundMail>> {
+        let mut mails = self.mails.lock().await;
+        let mut due: Vec<&mut OutboundMail> = mails.iter_mut().filter(|m| m.is_due()).collect();
+        due.sort_by_key(|m| *m.get_next_attempt_at());
+        due.truncate(limit as usize);
+        Ok(due
+            .into_iter()
+            .map(|mail| {
+                mail.claim(lease);
+                mail.clone()
+            })
+            .collect())
+    }
+
+    async fn find_dead_letters(&self, limit: u32) -> Result<Vec<OutboundMail>> {
+        let mails = self.mails.lock().await;
+        let dead_letters = mails
+            .iter()
+            .rev()
+            .filter(|m| m.get_status() == OutboundMailStatus::DeadLettered)
+            .take(limit as usize)
+            .cloned()
+            .collect();
+        Ok(dead_letters)
+    }
+
+    async fn purge_sent(&self, sent_before: &Date) -> Result<u64> {
+        let mut mails = self.mails.lock().await;
+        let count = mails.len();
+        mails.retain(|m| m.get_sent_at().is_none_or(|sent_at| sent_at >= sent_before));
+        Ok((count - mails.len()) as u64)
+    }
 }
@@ -0,0 +1,53 @@
+use di::{Injectable, ServiceCollection, singleton_as_self};
+use shared::infrastructure::{
+    mailing::smtp::add_mailer,
+    messaging::{
+        envelope::EventRegistry, memory::InMemoryEventBus, outbox::OutboxRelay, quarantine::memory::InMemoryQuarantineStore,
+        subscriber::EventSubscriber,
+    },
+    rate_limiting::memory::InMemoryRateLimiter,
+};
+use std::sync::Arc;
+use user::{
+    application::{
+        auth::AuthenticationService, mail_queue::MailQueueService, mailing::MailingService, refresh_token::RefreshTokenService,
+        session::SessionManagementService, user::UserManagementService,
+    },
+    infrastructure::{
+        auth::{jwt_service::JWTService, session_verifier::SessionTokenVerifier},
+        config::Config,
+        events::{
+            profile_created::OnProfileCreated, registry::event_registry, stash_status_updated::OnStashStatusUpdated, user_created::OnUserCreated,
+        },
+        http::AuthApi,
+    },
+};
+
+/// Registers the services of the user context, repositories and outbox excluded
+pub fn services(config: Config) -> ServiceCollection {
+    let mut services = ServiceCollection::new();
+    add_mailer(&mut services, config.mailing.smtp.as_ref()).unwrap();
+
+    let config = Arc::new(config);
+    services
+        .add(singleton_as_self::<Config>().from(move |_| config.clone()))
+        .add(AuthenticationService::singleton())
+        .add(MailingService::singleton())
+        .add(MailQueueService::singleton())
+        .add(SessionManagementService::singleton())
+        .add(RefreshTokenService::singleton())
+        .add(UserManagementService::singleton())
+        .add(JWTService::singleton())
+        .add(SessionTokenVerifier::singleton())
+        .add(AuthApi::singleton())
+        .add(InMemoryEventBus::singleton())
+        .add(OutboxRelay::singleton())
+        .add(singleton_as_self::<EventRegistry>().from(|_| Arc::new(event_registry())))
+        .add(InMemoryQuarantineStore::singleton())
+        .add(OnUserCreated::singleton())
+        .add(OnProfileCreated::singleton())
+        .add(OnStashStatusUpdated::singleton())
+        .add(EventSubscriber::singleton())
+        .add(InMemoryRateLimiter::singleton());
+    services
+}
@@ -0,0 +1,21 @@
+use di::ServiceProvider;
+use shared::{domain::value_objects::pid::Pid, infrastructure::types::Result};
+use std::{str::FromStr, sync::Arc};
+use user::{
+    application::auth::{AuthenticationService, TokenPair},
+    domain::value_objects::email::EmailAddress,
+};
+
+use crate::common::{delivery::deliver_queued_mails, string_utils::extract_otp};
+
+/// authenticates `test@stash.it` and returns the activated session id with the issued tokens
+pub async fn prepare_token_pair(provider: &ServiceProvider) -> Result<(Pid, TokenPair)> {
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let email = EmailAddress::from_str("test@stash.it").unwrap();
+
+    let session_id = authentication_service.create_new_session(&email, None).await?;
+    let deliveries = deliver_queued_mails(provider).await;
+    let code = extract_otp(deliveries.messages.last().unwrap()).unwrap();
+    let tokens = authentication_service.activate_session(&session_id, &code).await?;
+    Ok((session_id, tokens))
+}
@@ -0,0 +1,189 @@
+use crate::common::{bootstrap::bootstrap, delivery::deliver_queued_mails, string_utils::extract_otp};
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode},
+};
+use di::ServiceProvider;
+use http_body_util::BodyExt;
+use serde_json::{Value, json};
+use shared::{domain::value_objects::pid::Pid, infrastructure::types::Result};
+use tower::ServiceExt;
+use user::{application::auth::AuthenticationService, infrastructure::http::AuthApi};
+
+mod common {
+    pub mod bootstrap;
+    pub mod delivery;
+    pub mod repositories;
+    pub mod services;
+    pub mod string_utils;
+}
+
+fn router(provider: &ServiceProvider) -> Router {
+    provider.get_required::<AuthApi>().router()
+}
+
+async fn send(router: &Router, method: Method, uri: &str, token: Option<&str>, body: Value) -> (StatusCode, Value) {
+    let mut request = Request::builder().method(method).uri(uri).header("content-type", "application/json");
+    if let Some(token) = token {
+        request = request.header("authorization", format!("Bearer {token}"));
+    }
+
+    let response = router.clone().oneshot(request.body(Body::from(body.to_string())).unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, body)
+}
+
+/// requests an OTP for `email` and returns the session id with the mailed code
+async fn request_otp(provider: &ServiceProvider, router: &Router, email: &str) -> (String, String) {
+    let (status, body) = send(router, Method::POST, "/auth/sessions", None, json!({ "email": email })).await;
+    assert_eq!(status, StatusCode::CREATED, "otp must be requested: {body}");
+
+    let deliveries = deliver_queued_mails(provider).await;
+    let code = extract_otp(deliveries.messages.last().unwrap()).unwrap();
+    (body["session_id"].as_str().unwrap().to_owned(), code)
+}
+
+async fn login(provider: &ServiceProvider, router: &Router, email: &str) -> (String, String) {
+    let (session_id, code) = request_otp(provider, router, email).await;
+    let uri = format!("/auth/sessions/{session_id}/activate");
+    let (status, body) = send(router, Method::POST, &uri, None, json!({ "code": code })).await;
+    assert_eq!(status, StatusCode::OK, "session must be activated: {body}");
+    (session_id, body["access_token"].as_str().unwrap().to_owned())
+}
+
+#[tokio::test]
+async fn can_exchange_otp_for_token() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let router = router(&provider);
+    let (session_id, code) = request_otp(&provider, &router, "tom@stash.it").await;
+
+    // Act
+    let uri = format!("/auth/sessions/{session_id}/activate");
+    let (status, body) = send(&router, Method::POST, &uri, None, json!({ "code": code })).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["token_type"], "Bearer");
+    let token = body["access_token"].as_str().unwrap();
+    let authenticated = provider.get_required::<AuthApi>().token_verifier().verify(token).await?;
+    assert_eq!(
+        authenticated.session_id.to_string(),
+        session_id,
+        "token must resolve to the activated session"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn wrong_otp_is_rejected() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let router = router(&provider);
+    let (session_id, _) = request_otp(&provider, &router, "tom@stash.it").await;
+
+    // Act
+    let uri = format!("/auth/sessions/{session_id}/activate");
+    let (status, body) = send(&router, Method::POST, &uri, None, json!({ "code": "000000" })).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "entity_invalid");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_logout() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let router = router(&provider);
+    let authentication_service = provider.get_required::<AuthenticationService>();
+    let (session_id, token) = login(&provider, &router, "tom@stash.it").await;
+
+    // Act
+    let (status, _) = send(&router, Method::POST, "/auth/logout", Some(&token), json!({})).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let session_id = session_id.parse::<Pid>().unwrap();
+    assert!(!authentication_service.is_valid_session(&session_id).await?, "session must be terminated");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn logout_requires_bearer_token() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let router = router(&provider);
+    login(&provider, &router, "tom@stash.it").await;
+
+    // Act
+    let (missing_status, missing) = send(&router, Method::POST, "/auth/logout", None, json!({})).await;
+    let (invalid_status, _) = send(&router, Method::POST, "/auth/logout", Some("not-a-jwt"), json!({})).await;
+
+    // Assert
+    assert_eq!(missing_status, StatusCode::UNAUTHORIZED);
+    assert_eq!(missing["code"], "unauthorized");
+    assert_eq!(invalid_status, StatusCode::UNAUTHORIZED);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn logout_terminates_only_the_session_of_the_token() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let router = router(&provider);
+    let authentication_service = provider.get_required::<AuthenticationService>();
+    let (_, token) = login(&provider, &router, "tom@stash.it").await;
+    let (other_session_id, other_token) = login(&provider, &router, "tom@stash.it").await;
+
+    // Act
+    let (status, _) = send(&router, Method::POST, "/auth/logout", Some(&token), json!({})).await;
+    let (revoked_status, revoked) = send(&router, Method::POST, "/auth/logout", Some(&token), json!({})).await;
+
+    // Assert
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert_eq!(revoked_status, StatusCode::UNAUTHORIZED, "token of a terminated session must be rejected");
+    assert_eq!(revoked["code"], "unauthorized");
+    let other_session_id = other_session_id.parse::<Pid>().unwrap();
+    assert!(
+        authentication_service.is_valid_session(&other_session_id).await?,
+        "other session must stay active"
+    );
+    assert!(provider.get_required::<AuthApi>().token_verifier().verify(&other_token).await.is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn limited_otp_request_has_retry_after() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let router = router(&provider);
+    for _ in 0..3 {
+        request_otp(&provider, &router, "tom@stash.it").await;
+    }
+
+    // Act
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/auth/sessions")
+        .header("content-type", "application/json")
+        .body(Body::from(json!({ "email": "tom@stash.it" }).to_string()))
+        .unwrap();
+    let response = router.oneshot(request).await.unwrap();
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after = response.headers()["retry-after"].to_str().unwrap().parse::<u64>().unwrap();
+    assert!(retry_after > 0 && retry_after <= 600, "retry after must be within the refill interval");
+
+    Ok(())
+}
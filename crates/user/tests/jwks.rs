@@ -0,0 +1,171 @@
+use chrono::{TimeDelta, Utc};
+use di::{Injectable, ServiceCollection, singleton_as_self};
+use jsonwebtoken::{DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
+use shared::{
+    domain::value_objects::{pid::Pid, user_status::UserStatus},
+    infrastructure::{
+        config::get_config,
+        types::{Result, error::Error},
+    },
+};
+use std::sync::Arc;
+use user::infrastructure::{
+    auth::jwt_service::{Claims, ClaimsBuilder, JWTService},
+    config::{Config, JWTKeyConfig},
+};
+
+fn rsa_key(with_private_key: bool) -> JWTKeyConfig {
+    JWTKeyConfig {
+        kid: "rsa-2026-10".to_owned(),
+        algorithm: jsonwebtoken::Algorithm::RS256,
+        public_key: include_str!("fixtures/keys/rsa-2026-10.pub.pem").to_owned(),
+        private_key: with_private_key.then(|| include_str!("fixtures/keys/rsa-2026-10.pem").to_owned()),
+    }
+}
+
+fn ed_key(with_private_key: bool) -> JWTKeyConfig {
+    JWTKeyConfig {
+        kid: "ed-2026-04".to_owned(),
+        algorithm: jsonwebtoken::Algorithm::EdDSA,
+        public_key: include_str!("fixtures/keys/ed-2026-04.pub.pem").to_owned(),
+        private_key: with_private_key.then(|| include_str!("fixtures/keys/ed-2026-04.pem").to_owned()),
+    }
+}
+
+fn provide(config: Config) -> Arc<JWTService> {
+    let config = Arc::new(config);
+    ServiceCollection::new()
+        .add(singleton_as_self::<Config>().from(move |_| config.clone()))
+        .add(JWTService::singleton())
+        .build_provider()
+        .unwrap()
+        .get_required::<JWTService>()
+}
+
+/// builds a `JWTService` that holds no HMAC secret and signs with `active_key`
+fn jwt_service(active_key: Option<&str>, keys: Vec<JWTKeyConfig>) -> Arc<JWTService> {
+    let mut config = get_config::<Config>().unwrap();
+    config.jwt.secret = String::new();
+    config.jwt.active_key = active_key.map(str::to_owned);
+    config.jwt.keys = keys;
+    provide(config)
+}
+
+fn claims() -> Claims {
+    ClaimsBuilder::default()
+        .aud("users.stash.it")
+        .iss("auth.stash.it")
+        .exp((Utc::now() + TimeDelta::minutes(5)).timestamp())
+        .sub(Pid::new().to_string())
+        .sid(Pid::new().to_string())
+        .status(UserStatus::Active.as_str())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn signs_with_active_asymmetric_key() -> Result<()> {
+    for (kid, keys) in [("rsa-2026-10", vec![rsa_key(true)]), ("ed-2026-04", vec![ed_key(true)])] {
+        // Arrange
+        let jwt_service = jwt_service(Some(kid), keys);
+        let claims = claims();
+
+        // Act
+        let token = jwt_service.sign(&claims)?;
+        let decoded = jwt_service.decode_token(&token)?;
+
+        // Assert
+        let header = decode_header(&token).unwrap();
+        assert_eq!(header.kid.as_deref(), Some(kid), "header must name the signing key");
+        assert_eq!(decoded.sub, claims.sub, "claims must round trip");
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retired_key_still_verifies_tokens_issued_before_rotation() -> Result<()> {
+    // Arrange
+    let before_rotation = jwt_service(Some("ed-2026-04"), vec![ed_key(true)]);
+    let after_rotation = jwt_service(Some("rsa-2026-10"), vec![rsa_key(true), ed_key(false)]);
+    let token = before_rotation.sign(&claims())?;
+
+    // Act
+    let decoded = after_rotation.decode_token(&token);
+    let reissued = after_rotation.sign(&claims())?;
+
+    // Assert
+    assert!(decoded.is_ok(), "token of the retired key must verify: {decoded:?}");
+    assert_eq!(
+        decode_header(&reissued).unwrap().kid.as_deref(),
+        Some("rsa-2026-10"),
+        "new tokens must use the active key"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_key_and_secret_signed_tokens_are_rejected() -> Result<()> {
+    // Arrange
+    let rsa_only = jwt_service(Some("rsa-2026-10"), vec![rsa_key(true)]);
+    let ed_token = jwt_service(Some("ed-2026-04"), vec![ed_key(true)]).sign(&claims())?;
+    let hmac_token = provide(get_config::<Config>().unwrap()).sign(&claims())?;
+
+    // Act
+    let unknown_kid = rsa_only.decode_token(&ed_token);
+    let without_kid = rsa_only.decode_token(&hmac_token);
+
+    // Assert
+    assert!(matches!(unknown_kid, Err(Error::Unauthorized)), "unknown kid must be rejected");
+    assert!(matches!(without_kid, Err(Error::Unauthorized)), "secret signed token must be rejected");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokens_without_kid_are_rejected_once_an_asymmetric_key_is_active() -> Result<()> {
+    // Arrange
+    let mut config = get_config::<Config>().unwrap();
+    let hmac_token = provide(config.clone()).sign(&claims())?;
+    config.jwt.active_key = Some("rsa-2026-10".to_owned());
+    config.jwt.keys = vec![rsa_key(true)];
+    let rotated = provide(config);
+
+    // Act
+    let without_kid = rotated.decode_token(&hmac_token);
+
+    // Assert
+    assert!(
+        matches!(without_kid, Err(Error::Unauthorized)),
+        "a configured secret must not verify tokens once an asymmetric key signs them"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn jwks_verifies_tokens_offline() -> Result<()> {
+    // Arrange
+    let jwt_service = jwt_service(Some("rsa-2026-10"), vec![rsa_key(true), ed_key(false)]);
+    let token = jwt_service.sign(&claims())?;
+
+    // Act
+    let document = serde_json::to_string(&jwt_service.jwks()?).unwrap();
+
+    // Assert
+    let jwks: JwkSet = serde_json::from_str(&document).unwrap();
+    assert_eq!(jwks.keys.len(), 2, "active and retired keys must be published");
+    assert!(!document.contains("\"d\""), "private key material must never be published");
+    let jwk = jwks.find("rsa-2026-10").unwrap();
+    let mut validation = Validation::new(jsonwebtoken::Algorithm::RS256);
+    validation.set_audience(&["users.stash.it"]);
+    let decoded = decode::<Claims>(&token, &DecodingKey::from_jwk(jwk).unwrap(), &validation);
+    assert!(decoded.is_ok(), "token must verify with the published key: {decoded:?}");
+    assert!(
+        DecodingKey::from_jwk(jwks.find("ed-2026-04").unwrap()).is_ok(),
+        "Ed25519 key must be usable"
+    );
+
+    Ok(())
+}
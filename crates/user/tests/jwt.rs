@@ -0,0 +1,142 @@
+use crate::common::{bootstrap::bootstrap, prepare::prepare_authenticated_user};
+use chrono::{TimeDelta, Utc};
+use serde_json::json;
+use shared::{
+    domain::value_objects::{pid::Pid, user_status::UserStatus},
+    infrastructure::types::{Result, error::Error},
+};
+use std::sync::Arc;
+use user::{
+    application::{session::SessionManagementService, user::UserManagementService},
+    infrastructure::{
+        auth::jwt_service::{Claims, ClaimsBuilder, JWTService},
+        config::Config,
+    },
+};
+
+mod common {
+    pub mod bootstrap;
+    pub mod delivery;
+    pub mod prepare;
+    pub mod repositories;
+    pub mod services;
+    pub mod session;
+    pub mod string_utils;
+}
+
+fn claims(config: &Config) -> ClaimsBuilder {
+    let mut builder = ClaimsBuilder::default();
+    builder
+        .aud(config.jwt.audience.as_str())
+        .iss(config.jwt.issuer.as_str())
+        .exp((Utc::now() + TimeDelta::minutes(5)).timestamp())
+        .sub(Pid::new().to_string())
+        .sid(Pid::new().to_string())
+        .status(UserStatus::Active.as_str());
+    builder
+}
+
+fn assert_rejected(jwt_service: &JWTService, claims: &Claims) -> Result<()> {
+    let token = jwt_service.sign(claims)?;
+    let result = jwt_service.decode_token(&token);
+    assert!(matches!(result, Err(Error::Unauthorized)), "token must be rejected, got: {result:?}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn issued_token_round_trips_custom_claims() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let jwt_service: Arc<JWTService> = provider.get_required();
+    let config: Arc<Config> = provider.get_required();
+    let user_service: Arc<UserManagementService> = provider.get_required();
+    let (user_id, session_id) = prepare_authenticated_user(&provider).await?;
+    let user = user_service.get_user_by_pid(&user_id).await?.unwrap();
+    let session_service: Arc<SessionManagementService> = provider.get_required();
+
+    // Act
+    let session = session_service.get_session_by_id(&session_id).await?.unwrap();
+    let token = jwt_service.generate_token(&user, &session)?;
+    let claims = jwt_service.decode_token(&token)?;
+
+    // Assert
+    assert_eq!(claims.user_id()?, user_id, "sub must be the user");
+    assert_eq!(claims.session_id()?, session_id, "sid must be the session");
+    assert_eq!(&claims.user_status()?, user.get_status(), "status must be the user status");
+    assert_eq!(claims.iss, config.jwt.issuer, "iss must come from the config");
+    assert_eq!(claims.aud, config.jwt.audience, "aud must come from the config");
+    assert_eq!(claims.exp - claims.iat, config.jwt.ttl_seconds, "ttl must come from the config");
+
+    Ok(())
+}
+
+#[test]
+fn config_without_signing_key_or_with_non_hmac_algorithm_is_rejected() {
+    // Arrange
+    let without_secret = json!({ "jwt": {} });
+    let asymmetric_algorithm = json!({ "jwt": { "secret": "secret", "algorithm": "RS256" } });
+    let with_secret = json!({ "jwt": { "secret": "secret" } });
+
+    // Act
+    let without_secret = serde_json::from_value::<Config>(without_secret);
+    let asymmetric_algorithm = serde_json::from_value::<Config>(asymmetric_algorithm);
+    let with_secret = serde_json::from_value::<Config>(with_secret);
+
+    // Assert
+    assert!(without_secret.is_err(), "missing secret must fail config loading");
+    assert!(asymmetric_algorithm.is_err(), "secret must be used with an HMAC algorithm");
+    assert!(with_secret.is_ok(), "config with a secret must load");
+}
+
+#[tokio::test]
+async fn expired_token_is_rejected() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let jwt_service: Arc<JWTService> = provider.get_required();
+    let config: Arc<Config> = provider.get_required();
+    let claims = claims(&config)
+        .iat((Utc::now() - TimeDelta::hours(2)).timestamp())
+        .nbf((Utc::now() - TimeDelta::hours(2)).timestamp())
+        .exp((Utc::now() - TimeDelta::minutes(1)).timestamp())
+        .build()
+        .unwrap();
+
+    // Act & Assert
+    assert_rejected(&jwt_service, &claims)
+}
+
+#[tokio::test]
+async fn not_yet_valid_token_is_rejected() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let jwt_service: Arc<JWTService> = provider.get_required();
+    let config: Arc<Config> = provider.get_required();
+    let claims = claims(&config).nbf((Utc::now() + TimeDelta::minutes(1)).timestamp()).build().unwrap();
+
+    // Act & Assert
+    assert_rejected(&jwt_service, &claims)
+}
+
+#[tokio::test]
+async fn token_for_another_audience_is_rejected() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let jwt_service: Arc<JWTService> = provider.get_required();
+    let config: Arc<Config> = provider.get_required();
+    let claims = claims(&config).aud("admins.stash.it").build().unwrap();
+
+    // Act & Assert
+    assert_rejected(&jwt_service, &claims)
+}
+
+#[tokio::test]
+async fn token_from_another_issuer_is_rejected() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let jwt_service: Arc<JWTService> = provider.get_required();
+    let config: Arc<Config> = provider.get_required();
+    let claims = claims(&config).iss("evil.example").build().unwrap();
+
+    // Act & Assert
+    assert_rejected(&jwt_service, &claims)
+}
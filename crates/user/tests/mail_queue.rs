@@ -0,0 +1,243 @@
+use crate::common::{
+    bootstrap::{bootstrap, stub_services},
+    delivery::deliver_queued_mails,
+};
+use async_trait::async_trait;
+use chrono::TimeDelta;
+use di::{ServiceProvider, singleton};
+use shared::infrastructure::{
+    mailing::{Deliveries, Email, Mailer},
+    types::{
+        Result,
+        error::{DomainError, Error},
+    },
+};
+use std::{
+    str::FromStr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+use user::{
+    application::{
+        auth::AuthenticationService,
+        mail_queue::{MailQueueRun, MailQueueService},
+    },
+    domain::{entities::outbound_mail::OutboundMailStatus, repositories::MailQueueRepository, value_objects::email::EmailAddress},
+    infrastructure::config::{Config, MailQueueConfig},
+};
+
+mod common {
+    pub mod bootstrap;
+    pub mod delivery;
+    pub mod repositories;
+    pub mod services;
+}
+
+/// Mailer failing its first `failures` deliveries
+#[derive(Default)]
+struct FlakyMailer {
+    failures: AtomicUsize,
+    delivered: AtomicUsize,
+}
+
+impl FlakyMailer {
+    fn failing(failures: usize) -> Arc<Self> {
+        Arc::new(Self {
+            failures: AtomicUsize::new(failures),
+            delivered: AtomicUsize::new(0),
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for FlakyMailer {
+    async fn mail(&self, _email: &Email) -> Result<()> {
+        if self
+            .failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |f| f.checked_sub(1))
+            .is_ok()
+        {
+            return Err(Error::ServiceError);
+        }
+        self.delivered.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn deliveries(&self) -> Deliveries {
+        Deliveries {
+            count: self.delivered.load(Ordering::SeqCst),
+            ..Default::default()
+        }
+    }
+}
+
+/// Same as `bootstrap` with `mailer` replacing the stub mailer
+fn bootstrap_with_mailer<M: Mailer + 'static>(mailer: Arc<M>, configure: impl FnOnce(&mut Config)) -> ServiceProvider {
+    let mut services = stub_services(configure);
+    services.add(singleton::<dyn Mailer, M>().from(move |_| mailer.clone()));
+    services.build_provider().unwrap()
+}
+
+fn email() -> Email {
+    Email {
+        from: "noreply@stash.it".to_owned(),
+        to: "tom@stash.it".to_owned(),
+        subject: "Hello".to_owned(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn login_does_not_fail_when_mailer_fails() -> Result<()> {
+    // Arrange
+    let mailer = FlakyMailer::failing(usize::MAX);
+    let provider = bootstrap_with_mailer(mailer.clone(), |_| {});
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let mail_queue: Arc<MailQueueService> = provider.get_required();
+    let email = EmailAddress::from_str("tom@stash.it").unwrap();
+
+    // Act
+    let result = authentication_service.create_new_session(&email, None).await;
+    let run = mail_queue.process_due().await?;
+
+    // Assert
+    assert!(result.is_ok(), "session must be created while the mailer is down");
+    assert_eq!(
+        run,
+        MailQueueRun {
+            sent: 0,
+            retried: 1,
+            dead_lettered: 0
+        }
+    );
+    assert_eq!(
+        mail_queue.process_due().await?,
+        MailQueueRun::default(),
+        "retry must wait for its backoff"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn retries_with_backoff_until_delivered() -> Result<()> {
+    // Arrange
+    let mailer = FlakyMailer::failing(2);
+    let provider = bootstrap_with_mailer(mailer.clone(), |config| config.mailing.queue.base_backoff_seconds = 0);
+    let mail_queue: Arc<MailQueueService> = provider.get_required();
+    mail_queue.enqueue(email()).await?;
+
+    // Act
+    let first = mail_queue.process_due().await?;
+    let second = mail_queue.process_due().await?;
+    let third = mail_queue.process_due().await?;
+
+    // Assert
+    assert_eq!(first.retried, 1);
+    assert_eq!(second.retried, 1);
+    assert_eq!(third.sent, 1, "third attempt must deliver the mail");
+    assert_eq!(mailer.deliveries().await.count, 1);
+    assert!(mail_queue.get_dead_letters(10).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sent_mails_drop_their_body_and_are_purged_after_the_retention() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_with_mailer(FlakyMailer::failing(0), |config| config.mailing.queue.sent_retention_seconds = 0);
+    let mail_queue: Arc<MailQueueService> = provider.get_required();
+    let mail_queue_repo = provider.get_required::<dyn MailQueueRepository>();
+    let mail_id = mail_queue
+        .enqueue(Email {
+            text: "Your code is 123456".to_owned(),
+            html: "<p>Your code is 123456</p>".to_owned(),
+            ..email()
+        })
+        .await?;
+
+    // Act
+    mail_queue.process_due().await?;
+    let sent = mail_queue_repo.find_by_pid(&mail_id).await?.unwrap();
+    let purged = mail_queue.purge_sent().await?;
+
+    // Assert
+    assert_eq!(sent.get_status(), OutboundMailStatus::Sent);
+    assert!(sent.get_email().text.is_empty(), "sent mail must not keep its text");
+    assert!(sent.get_email().html.is_empty(), "sent mail must not keep its html");
+    assert_eq!(purged, 1, "sent mail must be purged after the retention");
+    assert!(mail_queue_repo.find_by_pid(&mail_id).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dead_letters_can_be_inspected_and_requeued() -> Result<()> {
+    // Arrange
+    let mailer = FlakyMailer::failing(2);
+    let provider = bootstrap_with_mailer(mailer.clone(), |config| {
+        config.mailing.queue.max_attempts = 2;
+        config.mailing.queue.base_backoff_seconds = 0;
+    });
+    let mail_queue: Arc<MailQueueService> = provider.get_required();
+    let mail_id = mail_queue.enqueue(email()).await?;
+    mail_queue.process_due().await?;
+    let run = mail_queue.process_due().await?;
+
+    // Act
+    let dead_letters = mail_queue.get_dead_letters(10).await?;
+    let requeued = mail_queue.requeue(&mail_id).await?;
+    let after_requeue = mail_queue.process_due().await?;
+
+    // Assert
+    assert_eq!(run.dead_lettered, 1, "second failure must dead-letter the mail");
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].get_pid(), &mail_id);
+    assert_eq!(dead_letters[0].get_attempts(), 2);
+    assert_eq!(dead_letters[0].get_last_error(), Some("ServiceError"));
+    assert_eq!(requeued.get_status(), OutboundMailStatus::Pending);
+    assert_eq!(requeued.get_attempts(), 0, "requeue must reset the attempt budget");
+    assert_eq!(after_requeue.sent, 1);
+    assert!(mail_queue.get_dead_letters(10).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_dead_letters_can_be_requeued() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let mail_queue: Arc<MailQueueService> = provider.get_required();
+    let mail_id = mail_queue.enqueue(email()).await?;
+    deliver_queued_mails(&provider).await;
+
+    // Act
+    let result = mail_queue.requeue(&mail_id).await;
+
+    // Assert
+    assert!(
+        matches!(result, Err(Error::DomainError(DomainError::EntityInvalid))),
+        "sent mail must not be requeued"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn backoff_grows_exponentially_up_to_the_cap() {
+    // Arrange
+    let queue = MailQueueConfig {
+        base_backoff_seconds: 30,
+        max_backoff_seconds: 100,
+        ..Default::default()
+    };
+
+    // Act
+    let backoffs: Vec<TimeDelta> = (1..=4).map(|attempts| queue.backoff(attempts)).collect();
+
+    // Assert
+    let expected = [30, 60, 100, 100].map(TimeDelta::seconds);
+    assert_eq!(backoffs, expected);
+}
@@ -0,0 +1,258 @@
+use crate::common::{bootstrap::bootstrap, delivery::deliver_queued_mails};
+use di::ServiceProvider;
+use insta::{assert_snapshot, with_settings};
+use shared::{
+    configure_insta,
+    domain::{
+        events::stash::StashStatusUpdatedEvent,
+        value_objects::{pid::Pid, stash_status::StashStatus, wallet_address::WalletAddress},
+    },
+    infrastructure::{
+        mailing::{Email, template::Locale},
+        messaging::{EventBus, subscriber::EventSubscriber},
+        types::Result,
+    },
+    testing::insta_filters::redactions::cleanup_model_generics,
+};
+use std::{str::FromStr, sync::Arc};
+use user::{
+    application::{
+        auth::AuthenticationService,
+        mailing::MailingService,
+        user::{UserManagementService, command::CreateUserProfileCommand},
+    },
+    domain::{
+        aggregates::user::User,
+        entities::{profile::Profile, session::Session},
+        value_objects::{display_name::DisplayName, email::EmailAddress},
+    },
+};
+
+mod common {
+    pub mod bootstrap;
+    pub mod delivery;
+    pub mod repositories;
+    pub mod services;
+}
+
+/// Same as `bootstrap` with the event listeners subscribed to the bus
+async fn bootstrap_with_listeners() -> ServiceProvider {
+    let provider = bootstrap();
+    provider.get_required::<EventSubscriber>().subscribe_listeners().await;
+    provider
+}
+
+fn user() -> User {
+    user_in(Locale::En)
+}
+
+fn user_in(locale: Locale) -> User {
+    User::new(EmailAddress::from_str("tom@stash.it").unwrap(), locale)
+}
+
+/// OTP codes are random unless the `testing` feature fixes them
+fn otp_filters() -> Vec<(&'static str, &'static str)> {
+    vec![(r"\b\d{6}\b", "[OTP]")]
+}
+
+async fn last_email(provider: &ServiceProvider) -> Email {
+    deliver_queued_mails(provider).await.emails.last().cloned().unwrap()
+}
+
+fn render(email: &Email) -> String {
+    format!(
+        "To: {}\nFrom: {}\nSubject: {}\n\n{}\n----------\n\n{}",
+        email.to, email.from, email.subject, email.text, email.html
+    )
+}
+
+#[tokio::test]
+async fn renders_otp_mail() -> Result<()> {
+    // Arrange
+    configure_insta!();
+    let provider = bootstrap();
+    let mailing_service: Arc<MailingService> = provider.get_required();
+    let user = user();
+    let session = Session::new(user.get_pid());
+
+    // Act
+    mailing_service.send_authentication_mail(&user, &session).await?;
+
+    // Assert
+    let email = last_email(&provider).await;
+    with_settings!({ filters => otp_filters() }, {
+        assert_snapshot!("otp_mail", render(&email));
+    });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn renders_otp_mail_in_locale_of_recipient() -> Result<()> {
+    // Arrange
+    configure_insta!();
+    let provider = bootstrap();
+    let mailing_service: Arc<MailingService> = provider.get_required();
+    let user = user_in(Locale::De);
+    let session = Session::new(user.get_pid());
+
+    // Act
+    mailing_service.send_authentication_mail(&user, &session).await?;
+
+    // Assert
+    let email = last_email(&provider).await;
+    with_settings!({ filters => otp_filters() }, {
+        assert_snapshot!("otp_mail_de", render(&email));
+    });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn renders_welcome_mail() -> Result<()> {
+    // Arrange
+    configure_insta!();
+    let provider = bootstrap();
+    let mailing_service: Arc<MailingService> = provider.get_required();
+
+    // Act
+    mailing_service.send_welcome_mail(&user()).await?;
+
+    // Assert
+    let email = last_email(&provider).await;
+    assert_snapshot!("welcome_mail", render(&email));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn renders_profile_created_mail() -> Result<()> {
+    // Arrange
+    configure_insta!();
+    let provider = bootstrap();
+    let mailing_service: Arc<MailingService> = provider.get_required();
+    let user = user();
+    let profile = Profile::new(
+        user.get_pid(),
+        &DisplayName::from_str("Tom & <Jerry>").unwrap(),
+        &WalletAddress::from_str("0x52471a768b76B8cC647f2F28198cB0E44C38C2cF").unwrap(),
+    );
+
+    // Act
+    mailing_service.send_profile_created_mail(&user, &profile).await?;
+
+    // Assert
+    let email = last_email(&provider).await;
+    assert!(email.html.contains("Tom &amp; &lt;Jerry&gt;"), "values must be escaped in HTML");
+    assert!(email.text.contains("Tom & <Jerry>"), "values must be kept verbatim in plain text");
+    assert_snapshot!("profile_created_mail", render(&email));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn renders_stash_status_changed_mail() -> Result<()> {
+    // Arrange
+    configure_insta!();
+    let provider = bootstrap();
+    let mailing_service: Arc<MailingService> = provider.get_required();
+
+    // Act
+    mailing_service
+        .send_stash_status_changed_mail(&user(), &Pid::new(), "ACTIVE", "PAUSED")
+        .await?;
+
+    // Assert
+    let email = last_email(&provider).await;
+    with_settings!({ filters => cleanup_model_generics() }, {
+        assert_snapshot!("stash_status_changed_mail", render(&email));
+    });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn welcomes_user_signing_up_in_chosen_locale() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_with_listeners().await;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let email = EmailAddress::from_str("tom@stash.it").unwrap();
+
+    // Act
+    authentication_service.create_new_session(&email, Some(Locale::De)).await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "listeners must not fail: {failures:?}");
+    let subjects: Vec<String> = deliver_queued_mails(&provider)
+        .await
+        .emails
+        .into_iter()
+        .map(|email| email.subject)
+        .collect();
+    assert_eq!(subjects, vec!["Willkommen bei Stash", "Dein Stash-Anmeldecode"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mails_user_once_profile_is_created() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_with_listeners().await;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let user_service: Arc<UserManagementService> = provider.get_required();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let email = EmailAddress::from_str("tom@stash.it").unwrap();
+    authentication_service.create_new_session(&email, None).await?;
+    let user = user_service.get_user_by_email(&email).await?.unwrap();
+
+    // Act
+    user_service
+        .create_user_profile(CreateUserProfileCommand {
+            user_id: user.get_pid().to_owned(),
+            display_name: DisplayName::from_str("Tom").unwrap(),
+            wallet_address: WalletAddress::from_str("0x52471a768b76B8cC647f2F28198cB0E44C38C2cF").unwrap(),
+        })
+        .await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "listeners must not fail: {failures:?}");
+    let email = last_email(&provider).await;
+    assert_eq!(email.to, "tom@stash.it");
+    assert_eq!(email.subject, "Your Stash profile is ready");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mails_owner_once_stash_status_changes() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_with_listeners().await;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let user_service: Arc<UserManagementService> = provider.get_required();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let email = EmailAddress::from_str("tom@stash.it").unwrap();
+    authentication_service.create_new_session(&email, None).await?;
+    let user = user_service.get_user_by_email(&email).await?.unwrap();
+
+    // Act
+    event_bus
+        .publish(StashStatusUpdatedEvent::new(
+            &Pid::new(),
+            user.get_pid(),
+            &StashStatus::ACTIVE,
+            &StashStatus::PAUSED,
+        ))
+        .await?;
+    let failures = event_bus.wait_until_idle().await;
+
+    // Assert
+    assert!(failures.is_empty(), "listeners must not fail: {failures:?}");
+    let email = last_email(&provider).await;
+    assert_eq!(email.to, "tom@stash.it");
+    assert_eq!(email.subject, "Your stash is now PAUSED");
+
+    Ok(())
+}
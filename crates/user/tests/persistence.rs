@@ -0,0 +1,463 @@
+use crate::common::{
+    database::bootstrap_sqlite, delivery::deliver_queued_mails, prepare::prepare_authenticated_user, session::prepare_token_pair,
+    string_utils::extract_otp,
+};
+use chrono::{TimeDelta, Utc};
+use shared::{
+    domain::{
+        events::user::UserCreatedEvent,
+        value_objects::{pid::Pid, user_status::UserStatus, wallet_address::WalletAddress},
+    },
+    infrastructure::{
+        mailing::{Email, Mailer},
+        messaging::{
+            EventBus,
+            outbox::{Outbox, OutboxRelay, RecordedEvent},
+        },
+        types::{
+            Result,
+            error::{DomainError, Error},
+        },
+    },
+};
+use std::{str::FromStr, sync::Arc, time::Duration};
+use user::{
+    application::{
+        auth::AuthenticationService,
+        session::SessionManagementService,
+        user::{UserManagementService, command::CreateUserProfileCommand},
+    },
+    domain::{
+        entities::outbound_mail::OutboundMail,
+        repositories::MailQueueRepository,
+        value_objects::{display_name::DisplayName, email::EmailAddress},
+    },
+    infrastructure::workers::spawn_workers,
+};
+
+mod common {
+    pub mod database;
+    pub mod delivery;
+    pub mod prepare;
+    pub mod services;
+    pub mod session;
+    pub mod string_utils;
+}
+
+#[tokio::test]
+async fn can_authenticate_and_create_profile_with_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let user_service: Arc<UserManagementService> = provider.get_required();
+    let (user_id, session_id) = prepare_authenticated_user(&provider).await?;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let command = CreateUserProfileCommand {
+        user_id: user_id.clone(),
+        display_name: DisplayName::from_str("testuser").unwrap(),
+        wallet_address: WalletAddress::from_str("0x52471a768b76B8cC647f2F28198cB0E44C38C2cF").unwrap(),
+    };
+
+    // Act
+    let profile = user_service.create_user_profile(command).await?;
+    let user = user_service.get_user_by_pid(&user_id).await?.unwrap();
+
+    // Assert
+    assert!(authentication_service.is_valid_session(&session_id).await?, "session must be active");
+    assert_eq!(user.get_email().to_string(), "test@stash.it", "email must be persisted");
+    assert_eq!(user.get_status(), &UserStatus::PendingProfile, "status must be persisted");
+    assert_eq!(profile.get_user_id(), &user_id, "User id on profile must match user pid");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn expires_unused_sessions_with_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let email = EmailAddress::from_str("tom@stash.it").unwrap();
+
+    // Act
+    let first_session_id = authentication_service.create_new_session(&email, None).await?;
+    let second_session_id = authentication_service.create_new_session(&email, None).await?;
+    let deliveries = deliver_queued_mails(&provider).await;
+    let code = extract_otp(deliveries.messages.last().unwrap()).unwrap();
+    let first_activation = authentication_service.activate_session(&first_session_id, &code).await;
+    let second_activation = authentication_service.activate_session(&second_session_id, &code).await;
+
+    // Assert
+    assert!(
+        matches!(first_activation, Err(Error::DomainError(DomainError::EntityInvalid))),
+        "superseded session must be expired"
+    );
+    assert!(second_activation.is_ok(), "latest session must be activatable");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rotates_refresh_tokens_with_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let (session_id, tokens) = prepare_token_pair(&provider).await?;
+
+    // Act
+    let rotated = authentication_service.rotate(&tokens.refresh_token).await?;
+    let reuse = authentication_service.rotate(&tokens.refresh_token).await;
+    let descendant = authentication_service.rotate(&rotated.refresh_token).await;
+
+    // Assert
+    assert!(matches!(reuse, Err(Error::Unauthorized)), "reused token must be rejected");
+    assert!(matches!(descendant, Err(Error::Unauthorized)), "revoked chain must be persisted");
+    assert!(!authentication_service.is_valid_session(&session_id).await?, "session must be terminated");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_rotations_spend_refresh_token_once_with_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let (session_id, tokens) = prepare_token_pair(&provider).await?;
+
+    // Act
+    let rotations: Vec<_> = (0..4)
+        .map(|_| {
+            let authentication_service = Arc::clone(&authentication_service);
+            let refresh_token = tokens.refresh_token.clone();
+            tokio::spawn(async move { authentication_service.rotate(&refresh_token).await })
+        })
+        .collect();
+    let mut results = Vec::new();
+    for rotation in rotations {
+        results.push(rotation.await.unwrap());
+    }
+
+    // Assert
+    assert!(results.iter().filter(|result| result.is_ok()).count() <= 1, "token must be spent once");
+    assert!(
+        results.iter().any(|result| matches!(result, Err(Error::Unauthorized))),
+        "concurrent reuse must be rejected"
+    );
+    assert!(
+        !authentication_service.is_valid_session(&session_id).await?,
+        "reuse must terminate the session"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn persists_otp_attempts_with_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let session_service: Arc<SessionManagementService> = provider.get_required();
+    let email = EmailAddress::from_str("test@stash.it").unwrap();
+    let session_id = authentication_service.create_new_session(&email, None).await?;
+
+    // Act
+    for _ in 0..3 {
+        let _ = authentication_service.activate_session(&session_id, "000000").await;
+    }
+
+    // Assert
+    let session = session_service.get_session_by_id(&session_id).await?.unwrap();
+    assert_eq!(session.get_failed_attempts(), 3, "failed attempts must be persisted");
+    assert!(session.is_locked(), "lock must be persisted");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn counts_concurrent_otp_attempts_with_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let session_service: Arc<SessionManagementService> = provider.get_required();
+    let email = EmailAddress::from_str("test@stash.it").unwrap();
+    let session_id = authentication_service.create_new_session(&email, None).await?;
+
+    // Act
+    let attempts: Vec<_> = (0..4)
+        .map(|_| {
+            let authentication_service = Arc::clone(&authentication_service);
+            let session_id = session_id.clone();
+            tokio::spawn(async move { authentication_service.activate_session(&session_id, "000000").await })
+        })
+        .collect();
+    for attempt in attempts {
+        let _ = attempt.await.unwrap();
+    }
+
+    // Assert
+    let session = session_service.get_session_by_id(&session_id).await?.unwrap();
+    assert_eq!(session.get_failed_attempts(), 3, "no more attempts than allowed may be counted");
+    assert!(session.is_locked(), "session must be locked after the last allowed attempt");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn activates_session_once_for_concurrent_right_codes_with_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let session_service: Arc<SessionManagementService> = provider.get_required();
+    let email = EmailAddress::from_str("test@stash.it").unwrap();
+    let session_id = authentication_service.create_new_session(&email, None).await?;
+    let deliveries = deliver_queued_mails(&provider).await;
+    let code = extract_otp(deliveries.messages.last().unwrap()).unwrap();
+
+    // Act
+    let attempts: Vec<_> = (0..3)
+        .map(|_| {
+            let authentication_service = Arc::clone(&authentication_service);
+            let session_id = session_id.clone();
+            let code = code.clone();
+            tokio::spawn(async move { authentication_service.activate_session(&session_id, &code).await })
+        })
+        .collect();
+    let mut activated = 0;
+    for attempt in attempts {
+        activated += attempt.await.unwrap().is_ok() as usize;
+    }
+
+    // Assert
+    assert_eq!(activated, 1, "only one submission may receive a token pair");
+    let session = session_service.get_session_by_id(&session_id).await?.unwrap();
+    assert!(session.activated());
+    assert_eq!(session.get_failed_attempts(), 0, "the right code must not count as a failed attempt");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn persists_mail_queue_with_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let mail_queue_repo = provider.get_required::<dyn MailQueueRepository>();
+    let email = Email {
+        from: "noreply@stash.it".to_owned(),
+        to: "tom@stash.it".to_owned(),
+        cc: Some("jerry@stash.it".to_owned()),
+        subject: "Hello".to_owned(),
+        ..Default::default()
+    };
+    let mut dead_letter = OutboundMail::new(email.clone());
+    dead_letter.record_failure("ServiceError".to_owned(), 1, TimeDelta::zero());
+    let pending = OutboundMail::new(email);
+
+    // Act
+    mail_queue_repo.save(&dead_letter).await?;
+    mail_queue_repo.save(&pending).await?;
+
+    // Assert
+    let due = mail_queue_repo.claim_due(10, TimeDelta::minutes(5)).await?;
+    assert_eq!(due.len(), 1, "only the pending mail must be due");
+    assert_eq!(due[0].get_pid(), pending.get_pid());
+    assert_eq!(due[0].get_email().cc.as_deref(), Some("jerry@stash.it"), "email must round trip");
+    let dead_letters = mail_queue_repo.find_dead_letters(10).await?;
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].get_pid(), dead_letter.get_pid());
+    assert_eq!(dead_letters[0].get_last_error(), Some("ServiceError"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redacts_and_purges_sent_mails_with_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let mail_queue_repo = provider.get_required::<dyn MailQueueRepository>();
+    let mut mail = OutboundMail::new(Email {
+        text: "Your code is 123456".to_owned(),
+        html: "<p>Your code is 123456</p>".to_owned(),
+        ..Default::default()
+    });
+    let pending = OutboundMail::new(Email::default());
+    mail_queue_repo.save(&mail).await?;
+    mail_queue_repo.save(&pending).await?;
+
+    // Act
+    mail.mark_sent();
+    mail_queue_repo.save(&mail).await?;
+    let sent = mail_queue_repo.find_by_pid(mail.get_pid()).await?.unwrap();
+    let kept = mail_queue_repo.purge_sent(&(Utc::now() - TimeDelta::minutes(1))).await?;
+    let purged = mail_queue_repo.purge_sent(&(Utc::now() + TimeDelta::minutes(1))).await?;
+
+    // Assert
+    assert!(sent.get_email().text.is_empty(), "stored mail must not keep its text once sent");
+    assert!(sent.get_email().html.is_empty(), "stored mail must not keep its html once sent");
+    assert_eq!(kept, 0, "mails sent within the retention must be kept");
+    assert_eq!(purged, 1, "only the sent mail must be purged");
+    assert!(mail_queue_repo.find_by_pid(pending.get_pid()).await?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn claims_due_mails_once_with_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let mail_queue_repo = provider.get_required::<dyn MailQueueRepository>();
+    for _ in 0..4 {
+        mail_queue_repo.save(&OutboundMail::new(Email::default())).await?;
+    }
+
+    // Act
+    let (first, second) = tokio::join!(
+        mail_queue_repo.claim_due(10, TimeDelta::minutes(5)),
+        mail_queue_repo.claim_due(10, TimeDelta::minutes(5))
+    );
+
+    // Assert
+    assert_eq!(first?.len() + second?.len(), 4, "every mail must be claimed by exactly one worker");
+    assert!(
+        mail_queue_repo.claim_due(10, TimeDelta::minutes(5)).await?.is_empty(),
+        "claimed mails must not be due"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sets_aside_undecodable_events_in_sqlite_outbox() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let outbox = provider.get_required::<dyn Outbox>();
+    let relay = provider.get_required::<OutboxRelay>();
+    let mut poison = RecordedEvent::record(UserCreatedEvent::new(&Pid::new()))?.envelope;
+    poison.event_type = "Unknown".to_owned();
+    let good = RecordedEvent::record(UserCreatedEvent::new(&Pid::new()))?.envelope;
+    outbox.append(&[poison.clone(), good]).await?;
+
+    // Act
+    relay.relay_pending(1).await?;
+    let relayed = relay.relay_pending(1).await?;
+
+    // Assert
+    assert_eq!(relayed, 1, "event behind the undecodable one must be relayed");
+    assert!(outbox.pending(10).await?.is_empty(), "undecodable event must not stay pending");
+    let (last_error,): (Option<String>,) = sqlx::query_as("SELECT last_error FROM event_outbox WHERE pid = ?")
+        .bind(poison.event_id.to_string())
+        .fetch_one(&*provider.get_required::<sqlx::SqlitePool>())
+        .await
+        .unwrap();
+    assert!(last_error.is_some(), "undecodable event must keep its error");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn records_user_events_in_sqlite_outbox() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let outbox = provider.get_required::<dyn Outbox>();
+    let user_service: Arc<UserManagementService> = provider.get_required();
+    let (user_id, _) = prepare_authenticated_user(&provider).await?;
+    let command = CreateUserProfileCommand {
+        user_id: user_id.clone(),
+        display_name: DisplayName::from_str("testuser").unwrap(),
+        wallet_address: WalletAddress::from_str("0x52471a768b76B8cC647f2F28198cB0E44C38C2cF").unwrap(),
+    };
+
+    // Act
+    user_service.create_user_profile(command).await?;
+
+    // Assert
+    assert!(outbox.pending(10).await?.is_empty(), "dispatched events must be marked published");
+    let stored: Vec<(String, String)> = sqlx::query_as("SELECT event_type, aggregate_id FROM event_outbox ORDER BY id")
+        .fetch_all(&*provider.get_required::<sqlx::SqlitePool>())
+        .await
+        .unwrap();
+    let event_types: Vec<&str> = stored.iter().map(|(event_type, _)| event_type.as_str()).collect();
+    assert_eq!(
+        event_types,
+        vec!["UserCreated", "SessionActivated", "ProfileCreated"],
+        "events must be stored with the aggregates"
+    );
+    assert!(stored.iter().all(|(_, aggregate_id)| aggregate_id == &user_id.to_string()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stores_session_events_with_the_session_in_sqlite_outbox() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let (session_id, _) = prepare_token_pair(&provider).await?;
+
+    // Act
+    authentication_service.terminate_session(&session_id).await?;
+
+    // Assert
+    let event_types: Vec<String> = sqlx::query_scalar("SELECT event_type FROM event_outbox WHERE event_type LIKE 'Session%' ORDER BY id")
+        .fetch_all(&*provider.get_required::<sqlx::SqlitePool>())
+        .await
+        .unwrap();
+    assert_eq!(
+        event_types,
+        vec!["SessionActivated", "SessionTerminated"],
+        "session events must be stored with the session"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn spawned_workers_relay_events_left_in_sqlite_outbox() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let outbox = provider.get_required::<dyn Outbox>();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let recorded = RecordedEvent::record(UserCreatedEvent::new(&Pid::new()))?;
+    outbox.append(&[recorded.envelope]).await?;
+
+    // Act
+    let workers = spawn_workers(&provider);
+    let relayed = tokio::time::timeout(Duration::from_secs(5), async {
+        while !outbox.pending(10).await.unwrap().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    workers.iter().for_each(|worker| worker.abort());
+
+    // Assert
+    assert!(relayed.is_ok(), "pending event must be relayed by the outbox worker");
+    assert!(event_bus.published(UserCreatedEvent::new(&Pid::new())).await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn spawned_workers_deliver_mails_queued_in_sqlite() -> Result<()> {
+    // Arrange
+    let provider = bootstrap_sqlite().await;
+    let mail_queue_repo = provider.get_required::<dyn MailQueueRepository>();
+    let mailer = provider.get_required::<dyn Mailer>();
+    mail_queue_repo.save(&OutboundMail::new(Email::default())).await?;
+
+    // Act
+    let workers = spawn_workers(&provider);
+    let delivered = tokio::time::timeout(Duration::from_secs(5), async {
+        while mailer.deliveries().await.count == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    workers.iter().for_each(|worker| worker.abort());
+
+    // Assert
+    assert!(delivered.is_ok(), "queued mail must be delivered by the mail queue worker");
+    assert!(
+        mail_queue_repo.claim_due(10, TimeDelta::minutes(5)).await?.is_empty(),
+        "delivered mail must not be due again"
+    );
+
+    Ok(())
+}
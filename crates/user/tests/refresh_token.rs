@@ -0,0 +1,105 @@
+use crate::common::{bootstrap::bootstrap, session::prepare_token_pair};
+use shared::{
+    domain::events::user::SessionTerminatedEvent,
+    infrastructure::{
+        messaging::EventBus,
+        types::{Result, error::Error},
+    },
+};
+use std::sync::Arc;
+use user::{application::auth::AuthenticationService, infrastructure::auth::jwt_service::JWTService};
+
+mod common {
+    pub mod bootstrap;
+    pub mod delivery;
+    pub mod repositories;
+    pub mod services;
+    pub mod session;
+    pub mod string_utils;
+}
+
+#[tokio::test]
+async fn can_rotate_refresh_token() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let jwt_service: Arc<JWTService> = provider.get_required();
+    let (session_id, tokens) = prepare_token_pair(&provider).await?;
+
+    // Act
+    let rotated = authentication_service.rotate(&tokens.refresh_token).await?;
+    let rotated_again = authentication_service.rotate(&rotated.refresh_token).await?;
+
+    // Assert
+    assert_ne!(rotated.refresh_token, tokens.refresh_token, "refresh token must be replaced");
+    assert_ne!(rotated_again.refresh_token, rotated.refresh_token, "refresh token must be replaced");
+    let claims = jwt_service.decode_token(&rotated_again.access_token)?;
+    assert_eq!(claims.session_id()?, session_id, "access token must belong to the session");
+    assert!(authentication_service.is_valid_session(&session_id).await?, "session must stay active");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reused_refresh_token_revokes_session_chain() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let event_bus = provider.get_required::<dyn EventBus>();
+    let jwt_service: Arc<JWTService> = provider.get_required();
+    let (session_id, tokens) = prepare_token_pair(&provider).await?;
+    let user_id = jwt_service.decode_token(&tokens.access_token)?.user_id()?;
+    let rotated = authentication_service.rotate(&tokens.refresh_token).await?;
+
+    // Act
+    let reuse = authentication_service.rotate(&tokens.refresh_token).await;
+    let descendant = authentication_service.rotate(&rotated.refresh_token).await;
+
+    // Assert
+    assert!(matches!(reuse, Err(Error::Unauthorized)), "reused token must be rejected");
+    assert!(
+        matches!(descendant, Err(Error::Unauthorized)),
+        "tokens of the revoked chain must be rejected"
+    );
+    assert!(!authentication_service.is_valid_session(&session_id).await?, "session must be terminated");
+    assert!(event_bus.published(SessionTerminatedEvent::new(&user_id, &session_id)).await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn terminated_session_can_not_be_refreshed() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let (session_id, tokens) = prepare_token_pair(&provider).await?;
+    authentication_service.terminate_session(&session_id).await?;
+
+    // Act
+    let result = authentication_service.rotate(&tokens.refresh_token).await;
+
+    // Assert
+    assert!(matches!(result, Err(Error::Unauthorized)), "revoked token must be rejected");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tampered_refresh_token_is_rejected() -> Result<()> {
+    // Arrange
+    let provider = bootstrap();
+    let authentication_service: Arc<AuthenticationService> = provider.get_required();
+    let (_, tokens) = prepare_token_pair(&provider).await?;
+    let (token_id, _) = tokens.refresh_token.split_once('.').unwrap();
+
+    // Act
+    let forged = authentication_service.rotate(&format!("{token_id}.deadbeef")).await;
+    let garbage = authentication_service.rotate("not-a-refresh-token").await;
+
+    // Assert
+    assert!(matches!(forged, Err(Error::Unauthorized)), "wrong secret must be rejected");
+    assert!(matches!(garbage, Err(Error::Unauthorized)), "malformed token must be rejected");
+    authentication_service.rotate(&tokens.refresh_token).await?;
+
+    Ok(())
+}
@@ -10,7 +10,15 @@ use user::{
     domain::value_objects::display_name::DisplayName,
 };
 
-mod common;
+mod common {
+    pub mod bootstrap;
+    pub mod delivery;
+    pub mod prepare;
+    pub mod repositories;
+    pub mod services;
+    pub mod session;
+    pub mod string_utils;
+}
 
 #[tokio::test]
 async fn can_create_user_profile() -> Result<()> {